@@ -0,0 +1,203 @@
+//! Isomorphism types of finite abelian groups.
+//!
+//! By the structure theorem a finite abelian group of order
+//! `N = p_1^e_1 ⋯ p_r^e_r` is the product of its Sylow subgroups, and the
+//! Sylow `p_i`-subgroup is `Z/p_i^λ_1 × ⋯ × Z/p_i^λ_k` for a unique
+//! partition `λ` of `e_i`. So the types of order `N` correspond to one
+//! partition of each exponent.
+
+use alloc::vec::Vec;
+
+use count::p_const;
+use factor::factorise;
+use streaming_iterator::StreamingIterator;
+use Partitions;
+
+/// Counts the abelian groups of order `n` up to isomorphism.
+///
+/// # Panics
+///
+/// Panics if `n` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::abelian::count_abelian_groups;
+///
+/// // 72 = 2³ 3², so p(3) p(2) = 6.
+/// assert_eq!(count_abelian_groups(72), 6);
+/// ```
+pub fn count_abelian_groups(n: usize) -> u128 {
+    factorise(n).iter().map(|&(_, e)| p_const(e as usize) as u128).product()
+}
+
+/// Iterates over the abelian groups of order `n` up to isomorphism.
+///
+/// Each group is yielded as its invariant factors
+/// `d_1 | d_2 | ⋯ | d_k`, all greater than one, whose product is `n`, so
+/// that the group is `Z/d_1 × ⋯ × Z/d_k`. The trivial group has no
+/// factors. The primary decomposition is available from `primary`.
+///
+/// The partitions for the smallest prime change slowest, each in the
+/// order of `Partitions`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::abelian::AbelianGroups;
+///
+/// let mut ag = AbelianGroups::new(12);
+/// assert_eq!(ag.next(), Some(&[2, 6][..]));
+/// assert_eq!(ag.primary(), &[2, 2, 3]);
+/// assert_eq!(ag.next(), Some(&[12][..]));
+/// assert_eq!(ag.primary(), &[4, 3]);
+/// assert_eq!(ag.next(), None);
+/// ```
+#[derive(Debug)]
+pub struct AbelianGroups {
+    primes: Vec<(usize, usize)>,
+    parts: Vec<Partitions>,
+    factors: Vec<usize>,
+    primary: Vec<usize>,
+    state: State,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum State {
+    Fresh,
+    Going,
+    Done,
+}
+
+impl AbelianGroups {
+    /// Makes a new iterator.
+    ///
+    /// This factorises `n` by trial division.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn new(n: usize) -> AbelianGroups {
+        let primes: Vec<(usize, usize)> = factorise(n).into_iter().map(|(p, e)| (p, e as usize)).collect();
+        AbelianGroups {
+            parts: primes.iter().map(|&(_, e)| Partitions::new(e)).collect(),
+            primes,
+            factors: Vec::new(),
+            primary: Vec::new(),
+            state: State::Fresh,
+        }
+    }
+
+    /// Advances the iterator and returns the invariant factors of the
+    /// next group.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Returns the primary decomposition of the current group: the
+    /// orders of its cyclic factors of prime power order, by prime and
+    /// then from the smallest.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator is not on a group.
+    #[inline]
+    pub fn primary(&self) -> &[usize] {
+        self.get().expect("no current group");
+        &self.primary
+    }
+
+    /// Works out both decompositions from the current partitions.
+    fn build(&mut self) {
+        let k = self.parts.iter().map(|pp| pp.get().unwrap().len()).max().unwrap_or(0);
+        self.factors.clear();
+        self.factors.resize(k, 1);
+        self.primary.clear();
+
+        for (&(p, _), pp) in self.primes.iter().zip(&self.parts) {
+            let lambda = pp.get().unwrap();
+            // The largest parts go into the largest factors.
+            let offset = k - lambda.len();
+            for (i, &x) in lambda.iter().enumerate() {
+                let q = p.pow(x as u32);
+                self.factors[offset + i] *= q;
+                self.primary.push(q);
+            }
+        }
+    }
+}
+
+impl StreamingIterator for AbelianGroups {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        match self.state {
+            State::Going => Some(&self.factors),
+            _ => None,
+        }
+    }
+
+    fn advance(&mut self) {
+        match self.state {
+            State::Fresh => {
+                for pp in &mut self.parts {
+                    pp.advance();
+                }
+            }
+            State::Going => {
+                // Step the partitions like an odometer, the last prime
+                // fastest.
+                let mut i = self.parts.len();
+                loop {
+                    if i == 0 {
+                        self.state = State::Done;
+                        return;
+                    }
+                    i -= 1;
+                    self.parts[i].advance();
+                    if self.parts[i].get().is_some() {
+                        break;
+                    }
+                    self.parts[i].reinit(self.primes[i].1);
+                    self.parts[i].advance();
+                }
+            }
+            State::Done => return,
+        }
+        self.state = State::Going;
+        self.build();
+    }
+}
+
+#[test]
+fn structure_theorem() {
+    //! Tests the decompositions and their number against A000688.
+
+    let tests: &[u128] = &[
+        1, 1, 1, 2, 1, 1, 1, 3, 2, 1, 1, 2, 1, 1, 1, 5, 1, 2, 1, 2,
+        1, 1, 1, 3, 2, 1, 3, 2, 1, 1, 1, 7, 1, 1, 1, 4, 1, 1, 1, 3,
+    ];
+
+    for n in 1..2000 {
+        let mut seen = Vec::new();
+        let mut ag = AbelianGroups::new(n);
+        while let Some(d) = ag.next().map(|d| d.to_vec()) {
+            assert_eq!(d.iter().product::<usize>(), n);
+            assert!(d.iter().all(|&x| x > 1));
+            assert!(d.windows(2).all(|w| w[1] % w[0] == 0));
+            assert_eq!(ag.primary().iter().product::<usize>(), n);
+            seen.push(d);
+        }
+        let count = seen.len();
+        seen.sort();
+        seen.dedup();
+        assert_eq!(seen.len(), count);
+        assert_eq!(count as u128, count_abelian_groups(n));
+        if n <= tests.len() {
+            assert_eq!(count as u128, tests[n - 1]);
+        }
+    }
+}
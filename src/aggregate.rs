@@ -0,0 +1,139 @@
+//! Folding a function over all the partitions of a number.
+//!
+//! The accumulator can be any type, so sums that outgrow the machine
+//! integers can be kept in a big integer type, such as `BigUint` with
+//! the `num-bigint` feature.
+
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::thread;
+
+#[cfg(feature = "std")]
+use cursor::SharedCursor;
+#[cfg(feature = "std")]
+use rank::RankTable;
+use Partitions;
+
+/// Applies `map` to each partition of `n` and combines the results with
+/// `reduce`, in the order of `Partitions`.
+///
+/// There is always at least one partition, so no starting value is
+/// needed.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::aggregate::sum_over_partitions;
+/// use integer_partitions::tableau::count_standard;
+///
+/// // The squares of the dimensions add up to n!.
+/// let sum = sum_over_partitions(10, |p| count_standard(p).unwrap().pow(2), |a, b| a + b);
+/// assert_eq!(sum, 3628800);
+/// ```
+pub fn sum_over_partitions<T, M, R>(n: usize, mut map: M, mut reduce: R) -> T
+where
+    M: FnMut(&[usize]) -> T,
+    R: FnMut(T, T) -> T,
+{
+    let mut pp = Partitions::new(n);
+    let mut acc = map(pp.next().unwrap());
+    while let Some(p) = pp.next() {
+        acc = reduce(acc, map(p));
+    }
+    acc
+}
+
+/// Applies `map` to each partition of `n` and combines the results with
+/// `reduce`, using `threads` threads.
+///
+/// The threads draw blocks of partitions from a `SharedCursor`, so that
+/// the work stays balanced even when `map` costs more on some shapes
+/// than on others. Each thread combines the results of its own blocks
+/// first, and the blocks can be handed out in any order, so `reduce`
+/// should be associative and commutative.
+///
+/// # Panics
+///
+/// Panics if `n` is greater than `count::P_CONST_MAX`, past which the
+/// number of partitions does not fit in a `u64`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::aggregate::sum_over_partitions_par;
+///
+/// // The total number of parts over all partitions of 30.
+/// let total = sum_over_partitions_par(30, 4, |p| p.len(), |a, b| a + b);
+/// assert_eq!(total, 54563);
+/// ```
+#[cfg(feature = "std")]
+pub fn sum_over_partitions_par<T, M, R>(n: usize, threads: usize, map: M, reduce: R) -> T
+where
+    T: Send,
+    M: Fn(&[usize]) -> T + Sync,
+    R: Fn(T, T) -> T + Sync,
+{
+    let threads = threads.max(1);
+    // Aim for plenty of blocks per thread, to even out the load.
+    let block = (RankTable::new(n).count() / (64 * threads)).max(1);
+    let cursor = SharedCursor::new(n, block);
+    let (cursor, map, reduce) = (&cursor, &map, &reduce);
+
+    let combine = |a: Option<T>, b: T| match a {
+        Some(a) => Some(reduce(a, b)),
+        None => Some(b),
+    };
+
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..threads).map(|_| {
+            s.spawn(move || {
+                let mut w = cursor.worker();
+                let mut acc = None;
+                while let Some(p) = w.next() {
+                    acc = combine(acc, map(p));
+                }
+                acc
+            })
+        }).collect();
+
+        let mut acc = None;
+        for h in handles {
+            if let Some(x) = h.join().unwrap() {
+                acc = combine(acc, x);
+            }
+        }
+        acc.expect("every number has a partition")
+    })
+}
+
+#[test]
+fn sum_of_squares() {
+    //! Tests the sequential and parallel folds against each other, and
+    //! against `n!`.
+
+    use tableau::count_standard;
+
+    let mut factorial: u128 = 1;
+    for n in 0..21 {
+        if n > 0 {
+            factorial *= n as u128;
+        }
+        let square = |p: &[usize]| count_standard(p).unwrap().pow(2);
+        assert_eq!(sum_over_partitions(n, square, |a, b| a + b), factorial);
+        #[cfg(feature = "std")]
+        for &threads in &[1, 3, 8] {
+            assert_eq!(sum_over_partitions_par(n, threads, square, |a, b| a + b), factorial);
+        }
+    }
+
+    #[cfg(all(feature = "num-bigint", feature = "std"))]
+    {
+        use num_bigint::BigUint;
+        use tableau::count_standard_big;
+
+        let square = |p: &[usize]| count_standard_big(p).pow(2);
+        let sum = sum_over_partitions_par(40, 4, square, |a, b| a + b);
+        assert_eq!(sum, (1..41u32).map(BigUint::from).product::<BigUint>());
+    }
+}
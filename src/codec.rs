@@ -0,0 +1,214 @@
+//! A compact binary encoding of single partitions.
+//!
+//! A partition with parts `x_1 ≤ x_2 ≤ … ≤ x_k` is written as the
+//! number of parts `k`, then `x_1`, then the differences
+//! `x_2 - x_1, …, x_k - x_(k-1)`, each as an unsigned LEB128 varint:
+//! seven bits at a time, least significant group first, with the high
+//! bit of each byte set on all but the last. Partitions with many equal
+//! parts therefore take about one byte per part, and the empty partition
+//! is the single byte `0`.
+//!
+//! # Stability
+//!
+//! This format is fixed. The bytes written for a partition will not
+//! change in later versions of the crate, and anything that decodes now
+//! will go on decoding to the same partition, whatever the word size of
+//! the machine. Decoding only accepts the shortest encoding of each
+//! number, so every partition has exactly one encoding and the bytes can
+//! be compared for equality or used as keys. They do not sort in any
+//! useful order.
+//!
+//! # Examples
+//!
+//! ```
+//! use integer_partitions::codec::{decode, encode};
+//!
+//! let mut bytes = Vec::new();
+//! encode(&[1, 1, 3, 200], &mut bytes);
+//! assert_eq!(bytes, vec![4, 1, 0, 2, 197, 1]);
+//!
+//! let mut p = Vec::new();
+//! assert_eq!(decode(&bytes, &mut p), Ok(6));
+//! assert_eq!(p, vec![1, 1, 3, 200]);
+//! ```
+
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+
+/// The ways that decoding can fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecodeError {
+    /// The input ended in the middle of a partition.
+    Truncated,
+    /// A number was written with more bytes than it needs.
+    NotCanonical,
+    /// A number, or the sum of the parts, does not fit in a `usize`.
+    Overflow,
+    /// The smallest part is zero.
+    ZeroPart,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            DecodeError::Truncated => "input ends in the middle of a partition",
+            DecodeError::NotCanonical => "number is not in its shortest encoding",
+            DecodeError::Overflow => "number too large",
+            DecodeError::ZeroPart => "partition has a part of zero",
+        })
+    }
+}
+
+impl Error for DecodeError {}
+
+fn put(mut x: u64, out: &mut Vec<u8>) {
+    while x >= 0x80 {
+        out.push(x as u8 | 0x80);
+        x >>= 7;
+    }
+    out.push(x as u8);
+}
+
+fn len(mut x: u64) -> usize {
+    let mut l = 1;
+    while x >= 0x80 {
+        x >>= 7;
+        l += 1;
+    }
+    l
+}
+
+/// Reads a varint from the start of `bytes`, returning it and the
+/// number of bytes it took.
+fn take(bytes: &[u8]) -> Result<(usize, usize), DecodeError> {
+    let mut x: u64 = 0;
+    for (i, &b) in bytes.iter().enumerate() {
+        if i == 9 && b > 1 {
+            return Err(DecodeError::Overflow);
+        }
+        x |= ((b & 0x7f) as u64) << (7 * i);
+        if b & 0x80 == 0 {
+            if b == 0 && i > 0 {
+                return Err(DecodeError::NotCanonical);
+            }
+            if x > usize::MAX as u64 {
+                return Err(DecodeError::Overflow);
+            }
+            return Ok((x as usize, i + 1));
+        }
+    }
+    Err(DecodeError::Truncated)
+}
+
+/// Appends the encoding of `p` to `out`.
+///
+/// # Panics
+///
+/// Panics if `p` is not in non-decreasing order, or has a part of zero.
+pub fn encode(p: &[usize], out: &mut Vec<u8>) {
+    assert!(p.first() != Some(&0), "parts must be positive");
+    put(p.len() as u64, out);
+    let mut last = 0;
+    for &x in p {
+        assert!(x >= last, "parts must be in non-decreasing order");
+        put((x - last) as u64, out);
+        last = x;
+    }
+}
+
+/// Returns the number of bytes that `encode` writes for `p`.
+pub fn encoded_len(p: &[usize]) -> usize {
+    let mut l = len(p.len() as u64);
+    let mut last = 0;
+    for &x in p {
+        l += len(x.wrapping_sub(last) as u64);
+        last = x;
+    }
+    l
+}
+
+/// Decodes the partition at the start of `bytes` into `out`, which is
+/// cleared first, and returns the number of bytes it took.
+///
+/// Anything after the partition is left alone, so a sequence of encoded
+/// partitions can be decoded one after another.
+pub fn decode(bytes: &[u8], out: &mut Vec<usize>) -> Result<usize, DecodeError> {
+    out.clear();
+
+    let (k, mut pos) = take(bytes)?;
+    // Each part takes at least one byte, which bounds the allocation.
+    if bytes.len() - pos < k {
+        return Err(DecodeError::Truncated);
+    }
+    out.reserve(k);
+
+    let (mut last, mut sum) = (0usize, 0usize);
+    for i in 0..k {
+        let (d, l) = take(&bytes[pos..])?;
+        pos += l;
+        if i == 0 && d == 0 {
+            return Err(DecodeError::ZeroPart);
+        }
+        last = last.checked_add(d).ok_or(DecodeError::Overflow)?;
+        sum = sum.checked_add(last).ok_or(DecodeError::Overflow)?;
+        out.push(last);
+    }
+    Ok(pos)
+}
+
+#[test]
+fn round_trip() {
+    //! Tests encoding then decoding a stream of partitions, and some
+    //! malformed inputs.
+
+    use Partitions;
+
+    let mut bytes = Vec::new();
+    let mut count = 0;
+    for n in 0..20 {
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            let before = bytes.len();
+            encode(p, &mut bytes);
+            assert_eq!(bytes.len() - before, encoded_len(p));
+            count += 1;
+        }
+    }
+    let huge = [1, 127, 128, 16383, 16384, usize::MAX / 2];
+    encode(&huge, &mut bytes);
+
+    let mut buf = Vec::new();
+    let mut pos = 0;
+    for n in 0..20 {
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            pos += decode(&bytes[pos..], &mut buf).unwrap();
+            assert_eq!(&buf[..], p);
+            count -= 1;
+        }
+    }
+    assert_eq!(count, 0);
+    pos += decode(&bytes[pos..], &mut buf).unwrap();
+    assert_eq!(buf, huge);
+    assert_eq!(pos, bytes.len());
+
+    assert_eq!(decode(&[], &mut buf), Err(DecodeError::Truncated));
+    assert_eq!(decode(&[2, 1], &mut buf), Err(DecodeError::Truncated));
+    assert_eq!(decode(&[1, 0x81], &mut buf), Err(DecodeError::Truncated));
+    assert_eq!(decode(&[1, 0x81, 0], &mut buf), Err(DecodeError::NotCanonical));
+    assert_eq!(decode(&[1, 0], &mut buf), Err(DecodeError::ZeroPart));
+    assert_eq!(decode(&[1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 2], &mut buf), Err(DecodeError::Overflow));
+    if usize::MAX as u64 == u64::MAX {
+        let max = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 1];
+        let mut one = vec![1];
+        one.extend(&max);
+        assert_eq!(decode(&one, &mut buf), Ok(11));
+        assert_eq!(buf, vec![usize::MAX]);
+
+        let mut two = vec![2];
+        two.extend(&max);
+        two.push(0);
+        assert_eq!(decode(&two, &mut buf), Err(DecodeError::Overflow));
+    }
+}
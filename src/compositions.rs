@@ -0,0 +1,244 @@
+//! Compositions: ordered partitions.
+//!
+//! A composition of `n` is a sequence of positive parts adding up to
+//! `n`, in any order, so `n` has `2^(n - 1)` of them. `Compositions`
+//! lists them in lexicographic order, from all ones to `n` alone, each in
+//! constant amortized time, and can be limited to those with exactly `k`
+//! parts. Like `Partitions`, it reuses one vector throughout, which can
+//! be handed in with `recycle` and taken back with `end`.
+//!
+//! # Examples
+//!
+//! ```
+//! use integer_partitions::compositions::Compositions;
+//!
+//! let mut cc = Compositions::new(3);
+//! assert_eq!(cc.next(), Some(&[1, 1, 1][..]));
+//! assert_eq!(cc.next(), Some(&[1, 2][..]));
+//! assert_eq!(cc.next(), Some(&[2, 1][..]));
+//! assert_eq!(cc.next(), Some(&[3][..]));
+//! assert_eq!(cc.next(), None);
+//!
+//! let mut cc = Compositions::exactly(5, 3);
+//! assert_eq!(cc.next(), Some(&[1, 1, 3][..]));
+//! assert_eq!(cc.next(), Some(&[1, 2, 2][..]));
+//! assert_eq!(cc.next(), Some(&[1, 3, 1][..]));
+//! ```
+
+use alloc::vec::Vec;
+
+use streaming_iterator::StreamingIterator;
+
+/// Iterates over the compositions of a given nonnegative integer.
+#[derive(Debug)]
+pub struct Compositions {
+    a: Vec<usize>,
+    n: usize,
+    k: Option<usize>,
+    /// With exactly `k` parts, the index of the last part above one
+    /// after the first, or zero if there is none.
+    j: usize,
+    state: State,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum State {
+    Fresh,
+    Going,
+    Done,
+}
+
+impl Compositions {
+    /// Makes a new iterator.
+    #[inline]
+    pub fn new(n: usize) -> Compositions {
+        Compositions::recycle(n, Vec::new())
+    }
+
+    /// Makes a new iterator over the compositions of `n` into exactly
+    /// `k` parts, in the same order as `new`.
+    #[inline]
+    pub fn exactly(n: usize, k: usize) -> Compositions {
+        let mut c = Compositions::new(n);
+        c.reinit_exactly(n, k);
+        c
+    }
+
+    /// Makes a new iterator, trying to avoid allocations.
+    ///
+    /// Any vector can be passed to this function, since its contents
+    /// will be cleared, but note that the vector will still reallocate
+    /// if its capacity is less than `n`.
+    #[inline]
+    pub fn recycle(n: usize, vec: Vec<usize>) -> Compositions {
+        let mut c = Compositions {
+            a: vec,
+            n,
+            k: None,
+            j: 0,
+            state: State::Fresh,
+        };
+        c.reinit(n);
+        c
+    }
+
+    /// Advances the iterator and returns the next composition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Restarts the iterator at the first composition of `n`.
+    ///
+    /// The existing vector is reused, and only reallocates if its
+    /// capacity is less than `n`.
+    #[inline]
+    pub fn reinit(&mut self, n: usize) {
+        self.a.clear();
+        self.n = n;
+        self.k = None;
+        self.state = State::Fresh;
+    }
+
+    /// Restarts the iterator at the first composition of `n` into
+    /// exactly `k` parts, like `exactly`.
+    #[inline]
+    pub fn reinit_exactly(&mut self, n: usize, k: usize) {
+        self.reinit(n);
+        self.k = Some(k);
+    }
+
+    /// Destroys the iterator and returns a vector for further use.
+    ///
+    /// You only need to call this function if you want to reuse the
+    /// vector for something else. Its contents will be in an undefined
+    /// state, and so cannot be relied upon.
+    #[inline]
+    pub fn end(self) -> Vec<usize> {
+        self.a
+    }
+}
+
+impl StreamingIterator for Compositions {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        match self.state {
+            State::Going => Some(&self.a),
+            _ => None,
+        }
+    }
+
+    fn advance(&mut self) {
+        let a = &mut self.a;
+        let found = match self.state {
+            State::Fresh => match self.k {
+                None => {
+                    a.resize(self.n, 1);
+                    true
+                }
+                Some(0) => self.n == 0,
+                Some(k) if k > self.n => false,
+                Some(k) => {
+                    a.resize(k, 1);
+                    a[k - 1] = self.n - k + 1;
+                    self.j = if k >= 2 && self.n > k { k - 1 } else { 0 };
+                    true
+                }
+            },
+            State::Going if a.len() < 2 => false,
+            State::Going => match self.k {
+                None => {
+                    // One more in the second last part, and what is left
+                    // of the last part as ones.
+                    let r = a.pop().unwrap() - 1;
+                    *a.last_mut().unwrap() += 1;
+                    a.resize(a.len() + r, 1);
+                    true
+                }
+                Some(_) if self.j == 0 => false,
+                Some(k) => {
+                    // The last part above one gives one to the part
+                    // before it, and the rest to the last part. Only
+                    // ones lie between them, so the next last part above
+                    // one is whichever of the two is.
+                    let j = self.j;
+                    let x = a[j];
+                    a[j - 1] += 1;
+                    a[j] = 1;
+                    a[k - 1] += x - 2;
+                    self.j = if a[k - 1] > 1 { k - 1 } else { j - 1 };
+                    true
+                }
+            },
+            State::Done => false,
+        };
+        self.state = if found { State::Going } else { State::Done };
+    }
+}
+
+#[test]
+fn brute_force() {
+    //! Compares against the compositions read off from subsets of the
+    //! gaps between `n` ones, sorted.
+
+    let mut c = Compositions::new(0);
+    for n in 0..14 {
+        let mut all = Vec::new();
+        if n == 0 {
+            all.push(Vec::new());
+        } else {
+            for cuts in 0..(1u32 << (n - 1)) {
+                let mut comp = vec![1];
+                for i in 0..(n - 1) {
+                    if cuts >> i & 1 == 1 {
+                        comp.push(1);
+                    } else {
+                        *comp.last_mut().unwrap() += 1;
+                    }
+                }
+                all.push(comp);
+            }
+        }
+        all.sort();
+
+        c.reinit(n);
+        for x in &all {
+            assert_eq!(c.next(), Some(&x[..]));
+        }
+        assert_eq!(c.next(), None);
+        assert_eq!(c.next(), None);
+
+        for k in 0..(n + 2) {
+            c.reinit_exactly(n, k);
+            for x in all.iter().filter(|x| x.len() == k) {
+                assert_eq!(c.next(), Some(&x[..]));
+            }
+            assert_eq!(c.next(), None);
+        }
+    }
+
+    let v = Compositions::exactly(7, 3).end();
+    let mut c = Compositions::recycle(4, v);
+    assert_eq!(c.next(), Some(&[1, 1, 1, 1][..]));
+}
+
+#[test]
+fn last_above_one() {
+    //! Tests that the index kept of the last part above one matches the
+    //! composition, with exactly `k` parts.
+
+    let mut c = Compositions::new(0);
+    for n in 0..16 {
+        for k in 0..(n + 2) {
+            c.reinit_exactly(n, k);
+            while c.next().is_some() {
+                let x = c.get().unwrap();
+                let j = x.iter().skip(1).rposition(|&y| y > 1).map_or(0, |j| j + 1);
+                assert_eq!(c.j, j, "{:?}", x);
+            }
+        }
+    }
+}
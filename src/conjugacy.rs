@@ -0,0 +1,150 @@
+//! Representatives of the conjugacy classes of the symmetric group.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use streaming_iterator::StreamingIterator;
+use Partitions;
+
+/// Iterates over one permutation of `{0, …, n - 1}` for each cycle type.
+///
+/// The partitions of `n` come in the same order as from `Partitions`,
+/// and for each one the iterator yields, in one-line notation, the
+/// permutation whose cycles are runs of consecutive integers with the
+/// lengths given by the partition. For the cycle type `[1, 2, 3]` that
+/// is `(0)(1 2)(3 4 5)`, or `[0, 2, 1, 4, 5, 3]` in one-line notation.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::conjugacy::ConjugacyClasses;
+///
+/// let mut cc = ConjugacyClasses::new(3);
+/// assert_eq!(cc.next(), Some(&[0, 1, 2][..]));
+/// assert_eq!(cc.cycle_type(), &[1, 1, 1]);
+/// assert_eq!(cc.next(), Some(&[0, 2, 1][..]));
+/// assert_eq!(cc.cycle_type(), &[1, 2]);
+/// assert_eq!(cc.next(), Some(&[1, 2, 0][..]));
+/// assert_eq!(cc.cycles().collect::<Vec<_>>(), vec![0..3]);
+/// assert_eq!(cc.next(), None);
+/// ```
+#[derive(Debug)]
+pub struct ConjugacyClasses {
+    parts: Partitions,
+    perm: Vec<usize>,
+}
+
+impl ConjugacyClasses {
+    /// Makes a new iterator.
+    pub fn new(n: usize) -> ConjugacyClasses {
+        ConjugacyClasses {
+            parts: Partitions::new(n),
+            perm: Vec::with_capacity(n),
+        }
+    }
+
+    /// Advances the iterator and returns the next permutation.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Returns the cycle type of the current permutation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator is not on a permutation.
+    #[inline]
+    pub fn cycle_type(&self) -> &[usize] {
+        self.parts.get().expect("no current permutation")
+    }
+
+    /// Returns the cycles of the current permutation, as ranges of the
+    /// elements they contain.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator is not on a permutation.
+    #[inline]
+    pub fn cycles<'a>(&'a self) -> Cycles<'a> {
+        Cycles {
+            parts: self.cycle_type().iter(),
+            start: 0,
+        }
+    }
+}
+
+impl StreamingIterator for ConjugacyClasses {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        self.parts.get().map(|_| &self.perm[..])
+    }
+
+    fn advance(&mut self) {
+        self.parts.advance();
+        self.perm.clear();
+
+        if let Some(p) = self.parts.get() {
+            for &len in p {
+                let start = self.perm.len();
+                for i in (start + 1)..(start + len) {
+                    self.perm.push(i);
+                }
+                self.perm.push(start);
+            }
+        }
+    }
+}
+
+/// Iterates over the cycles of a conjugacy class representative.
+#[derive(Debug, Clone)]
+pub struct Cycles<'a> {
+    parts: ::core::slice::Iter<'a, usize>,
+    start: usize,
+}
+
+impl<'a> Iterator for Cycles<'a> {
+    type Item = Range<usize>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Range<usize>> {
+        self.parts.next().map(|&len| {
+            let start = self.start;
+            self.start += len;
+            start..self.start
+        })
+    }
+}
+
+#[test]
+fn cycle_types() {
+    //! Tests that each permutation has the cycle type it claims.
+
+    for n in 0..12 {
+        let mut cc = ConjugacyClasses::new(n);
+
+        while let Some(perm) = cc.next() {
+            let mut seen = vec![false; n];
+            let mut lengths = Vec::new();
+            for i in 0..n {
+                let mut len = 0;
+                let mut j = i;
+                while !seen[j] {
+                    seen[j] = true;
+                    j = perm[j];
+                    len += 1;
+                }
+                if len > 0 {
+                    lengths.push(len);
+                }
+            }
+            lengths.sort();
+
+            assert_eq!(&lengths[..], cc.cycle_type());
+            assert_eq!(cc.cycles().map(|c| c.len()).collect::<Vec<_>>(), lengths);
+        }
+    }
+}
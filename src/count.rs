@@ -0,0 +1,796 @@
+//! Counting partitions without enumerating them.
+//!
+//! Counts are returned as `Option<u128>`, with `None` when they do not
+//! fit.
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+#[cfg(feature = "num-bigint")]
+use num_bigint::BigUint;
+use Partitions;
+
+/// Counts the ways to write every point of the box `[0, target]` as a
+/// sum of the vectors, returning the table in mixed-radix order with
+/// the first coordinate varying slowest.
+///
+/// This is the coin-change recurrence, with one pass over the box for
+/// each vector.
+fn box_counts(vectors: &[&[usize]], target: &[usize]) -> Vec<Option<u128>> {
+    let d = target.len();
+    let size = target.iter().map(|&t| t + 1).product();
+    let mut strides = vec![1; d];
+    for i in (0..d.saturating_sub(1)).rev() {
+        strides[i] = strides[i + 1] * (target[i + 1] + 1);
+    }
+
+    let mut table = vec![Some(0u128); size];
+    table[0] = Some(1);
+    let mut x = vec![0; d];
+    for v in vectors {
+        assert_eq!(v.len(), d, "vectors must match the target in length");
+        assert!(v.iter().any(|&c| c > 0), "vectors must be nonzero");
+        if v.iter().zip(target).any(|(c, t)| c > t) {
+            continue;
+        }
+        let offset: usize = v.iter().zip(&strides).map(|(c, s)| c * s).sum();
+
+        for c in x.iter_mut() {
+            *c = 0;
+        }
+        for i in 0..size {
+            if x.iter().zip(v.iter()).all(|(a, b)| a >= b) {
+                table[i] = match (table[i], table[i - offset]) {
+                    (Some(a), Some(b)) => a.checked_add(b),
+                    _ => None,
+                };
+            }
+
+            // Step the coordinates to those of i + 1.
+            for j in (0..d).rev() {
+                if x[j] < target[j] {
+                    x[j] += 1;
+                    break;
+                }
+                x[j] = 0;
+            }
+        }
+    }
+
+    table
+}
+
+/// Counts the partitions of `n` whose parts all come from `parts`.
+///
+/// Each part may be used any number of times; repeated entries in
+/// `parts` are counted as distinct kinds of part, as with coins of the
+/// same value but different colours.
+///
+/// # Panics
+///
+/// Panics if `parts` contains a zero.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::count::partitions_into;
+///
+/// // Making change for a dollar.
+/// assert_eq!(partitions_into(100, &[1, 5, 10, 25, 50, 100]), Some(293));
+/// ```
+pub fn partitions_into(n: usize, parts: &[usize]) -> Option<u128> {
+    partitions_into_numbers(n, parts)[n]
+}
+
+/// Returns the numbers of partitions of `0, …, n` whose parts all come
+/// from `parts`, as for `partitions_into`.
+///
+/// # Panics
+///
+/// Panics if `parts` contains a zero.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::count::partitions_into_numbers;
+///
+/// let p: Vec<u128> = partitions_into_numbers(7, &[2, 3]).into_iter().map(Option::unwrap).collect();
+/// assert_eq!(p, vec![1, 0, 1, 1, 1, 1, 2, 1]);
+/// ```
+pub fn partitions_into_numbers(n: usize, parts: &[usize]) -> Vec<Option<u128>> {
+    let vectors: Vec<[usize; 1]> = parts.iter().map(|&p| [p]).collect();
+    let vectors: Vec<&[usize]> = vectors.iter().map(|v| &v[..]).collect();
+    box_counts(&vectors, &[n])
+}
+
+/// Evaluates the vector partition function: counts the ways to write
+/// `target` as a sum of the given vectors, each used any number of
+/// times.
+///
+/// The work is proportional to the number of vectors times the number
+/// of points in the box between zero and `target`.
+///
+/// # Panics
+///
+/// Panics if a vector is zero or has a different length from `target`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::count::kostant;
+///
+/// // Zero, one or two copies of (1, 1), topped up with the others.
+/// let vectors: &[&[usize]] = &[&[1, 0], &[0, 1], &[1, 1]];
+/// assert_eq!(kostant(vectors, &[2, 2]), Some(3));
+/// ```
+pub fn kostant(vectors: &[&[usize]], target: &[usize]) -> Option<u128> {
+    let table = box_counts(vectors, target);
+    table[table.len() - 1]
+}
+
+/// Returns the positive roots of the root system `A_rank`, written in
+/// the basis of simple roots.
+///
+/// These are the sums `α_i + α_(i+1) + … + α_j` of consecutive simple
+/// roots, so each is a vector of zeroes with a single run of ones.
+pub fn type_a_positive_roots(rank: usize) -> Vec<Vec<usize>> {
+    let mut roots = Vec::with_capacity(rank * (rank + 1) / 2);
+    for i in 0..rank {
+        for j in i..rank {
+            let mut root = vec![0; rank];
+            for c in &mut root[i..(j + 1)] {
+                *c = 1;
+            }
+            roots.push(root);
+        }
+    }
+    roots
+}
+
+/// Evaluates Kostant's partition function for the root system `A_r`,
+/// where `r` is the length of `target`, written in the basis of simple
+/// roots.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::count::kostant_type_a;
+///
+/// // For A_2 the count at a α_1 + b α_2 is min(a, b) + 1.
+/// assert_eq!(kostant_type_a(&[3, 5]), Some(4));
+/// // The sum of all positive roots of A_3.
+/// assert_eq!(kostant_type_a(&[3, 4, 3]), Some(26));
+/// ```
+pub fn kostant_type_a(target: &[usize]) -> Option<u128> {
+    let roots = type_a_positive_roots(target.len());
+    let roots: Vec<&[usize]> = roots.iter().map(|r| &r[..]).collect();
+    kostant(&roots, target)
+}
+
+/// The largest `n` for which `p(n)` fits in a `u64`.
+pub const P_CONST_MAX: usize = 416;
+
+/// Returns the number of partitions of `n`, in a constant context.
+///
+/// This runs Euler's pentagonal number recurrence on a fixed table, so
+/// it takes `O(n^1.5)` steps each time it is called, which is only
+/// cheap at compile time.
+///
+/// # Panics
+///
+/// Panics, or fails to compile in a constant, if `n` is greater than
+/// `P_CONST_MAX`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::count::p_const;
+///
+/// const P20: u64 = p_const(20);
+/// static TABLE: [u8; P20 as usize] = [0; P20 as usize];
+/// assert_eq!(TABLE.len(), 627);
+/// ```
+pub const fn p_const(n: usize) -> u64 {
+    assert!(n <= P_CONST_MAX, "p(n) does not fit in a u64");
+
+    let mut p = [0u64; P_CONST_MAX + 1];
+    p[0] = 1;
+    let mut i = 1;
+    while i <= n {
+        // The kth pair of terms has sign (-1)^(k + 1). Keep the positive
+        // and negative sums apart to stay unsigned.
+        let (mut plus, mut minus) = (0u128, 0u128);
+        let mut k = 1;
+        loop {
+            let g1 = k * (3 * k - 1) / 2;
+            if g1 > i {
+                break;
+            }
+            let mut t = p[i - g1] as u128;
+            if g1 + k <= i {
+                t += p[i - g1 - k] as u128;
+            }
+            if k % 2 == 1 {
+                plus += t;
+            } else {
+                minus += t;
+            }
+            k += 1;
+        }
+        p[i] = (plus - minus) as u64;
+        i += 1;
+    }
+    p[n]
+}
+
+/// Returns `p(0), …, p(n)` by the pentagonal number recurrence, with
+/// `None` from the first that does not fit.
+fn partition_numbers(n: usize) -> Vec<Option<u128>> {
+    let mut p: Vec<Option<u128>> = Vec::with_capacity(n + 1);
+    p.push(Some(1));
+    for i in 1..(n + 1) {
+        let (mut plus, mut minus) = (Some(0u128), Some(0u128));
+        let mut k = 1;
+        loop {
+            let g1 = k * (3 * k - 1) / 2;
+            if g1 > i {
+                break;
+            }
+            let mut t = p[i - g1];
+            if g1 + k <= i {
+                t = t.and_then(|t| t.checked_add(p[i - g1 - k]?));
+            }
+            let sum = if k % 2 == 1 { &mut plus } else { &mut minus };
+            *sum = sum.and_then(|s| s.checked_add(t?));
+            k += 1;
+        }
+        p.push(plus.and_then(|plus| Some(plus - minus?)));
+    }
+    p
+}
+
+/// Counts the partitions of `n`, by Euler's pentagonal number
+/// recurrence in `O(n^1.5)` steps.
+///
+/// Returns `None` from `n = 1417` on, where the sums in the recurrence
+/// outgrow a `u128` a little before `p(n)` itself does.
+/// `partition_count_big` goes further.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::count::partition_count;
+///
+/// assert_eq!(partition_count(100), Some(190_569_292));
+/// assert_eq!(partition_count(1000), Some(24_061_467_864_032_622_473_692_149_727_991));
+/// ```
+pub fn partition_count(n: usize) -> Option<u128> {
+    partition_numbers(n)[n]
+}
+
+/// Counts the partitions of `n` exactly, like `partition_count`.
+///
+/// This is only available with the `num-bigint` feature.
+#[cfg(feature = "num-bigint")]
+pub fn partition_count_big(n: usize) -> BigUint {
+    let mut p: Vec<BigUint> = Vec::with_capacity(n + 1);
+    p.push(BigUint::from(1u32));
+    for i in 1..(n + 1) {
+        let (mut plus, mut minus) = (BigUint::from(0u32), BigUint::from(0u32));
+        let mut k = 1;
+        loop {
+            let g1 = k * (3 * k - 1) / 2;
+            if g1 > i {
+                break;
+            }
+            let sum = if k % 2 == 1 { &mut plus } else { &mut minus };
+            *sum += &p[i - g1];
+            if g1 + k <= i {
+                *sum += &p[i - g1 - k];
+            }
+            k += 1;
+        }
+        p.push(plus - minus);
+    }
+    p.pop().unwrap()
+}
+
+/// Counts the partitions of `n` into at most `k` parts, in `O(nk)`
+/// steps.
+///
+/// These are as many as the partitions with largest part at most `k`,
+/// their conjugates, which `Partitions::builder` lists with `max_parts`
+/// and `max_part`. Returns `None` if the count does not fit in a
+/// `u128`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::count::partition_count_max_parts;
+///
+/// // 1 + 5, 2 + 4, 3 + 3 and 6.
+/// assert_eq!(partition_count_max_parts(6, 2), Some(4));
+/// ```
+pub fn partition_count_max_parts(n: usize, k: usize) -> Option<u128> {
+    let parts: Vec<usize> = (1..(k.min(n) + 1)).collect();
+    partitions_into(n, &parts)
+}
+
+/// Counts the partitions of `n` with largest part at most `m`, which
+/// is the same as `partition_count_max_parts(n, m)`.
+#[inline]
+pub fn partition_count_max_part(n: usize, m: usize) -> Option<u128> {
+    partition_count_max_parts(n, m)
+}
+
+/// Counts the partitions of `n` into at most `k` parts of at most `m`,
+/// those that fit in a `k` by `m` box, in `O(nk)` steps.
+///
+/// This is the coefficient of `q^n` in the Gaussian binomial
+/// `[k + m choose k]_q`, which is built up one factor
+/// `(1 - q^(m + i)) / (1 - q^i)` at a time. Returns `None` if the
+/// count, or one of the coefficients along the way, does not fit in a
+/// `u128`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::count::partition_count_in_box;
+///
+/// // 1 + 2 + 3, 2 + 2 + 2 and 3 + 3.
+/// assert_eq!(partition_count_in_box(6, 3, 3), Some(3));
+/// ```
+pub fn partition_count_in_box(n: usize, k: usize, m: usize) -> Option<u128> {
+    if m == 0 {
+        return Some((n == 0) as u128);
+    }
+    let mut f = vec![Some(0u128); n + 1];
+    f[0] = Some(1);
+    for i in 1..(k.min(n) + 1) {
+        for j in i..(n + 1) {
+            f[j] = f[j].and_then(|x| x.checked_add(f[j - i]?));
+        }
+        // What is taken away was added in above, so never goes below
+        // zero.
+        for j in ((m + i)..(n + 1)).rev() {
+            f[j] = f[j].and_then(|x| Some(x - f[j - m - i]?));
+        }
+    }
+    f[n]
+}
+
+/// Counts the partitions of `n` into distinct parts, in `O(n²)` steps.
+///
+/// Returns `None` if the count does not fit in a `u128`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::count::partition_count_distinct;
+///
+/// assert_eq!(partition_count_distinct(100), Some(444_793));
+/// ```
+pub fn partition_count_distinct(n: usize) -> Option<u128> {
+    let mut f = vec![Some(0u128); n + 1];
+    f[0] = Some(1);
+    for k in 1..(n + 1) {
+        for i in (k..(n + 1)).rev() {
+            f[i] = f[i].and_then(|x| x.checked_add(f[i - k]?));
+        }
+    }
+    f[n]
+}
+
+/// Returns the first `n + 1` coefficients of `∏ (1 + q^k)/(1 - q^k)`
+/// over the parts `k` that `part` accepts, with `None` from the first
+/// that does not fit.
+fn overpartition_counts<F: Fn(usize) -> bool>(n: usize, part: F) -> Vec<Option<u128>> {
+    let mut f = vec![Some(0u128); n + 1];
+    f[0] = Some(1);
+    for k in (1..(n + 1)).filter(|&k| part(k)) {
+        for i in (k..(n + 1)).rev() {
+            f[i] = f[i].and_then(|x| x.checked_add(f[i - k]?));
+        }
+        for i in k..(n + 1) {
+            f[i] = f[i].and_then(|x| x.checked_add(f[i - k]?));
+        }
+    }
+    f
+}
+
+/// Returns the numbers of overpartitions of `0, …, n`.
+///
+/// An overpartition is a partition in which the first occurrence of
+/// each part may be overlined, so there are `∏ (1 + q^k)/(1 - q^k)` of
+/// them. Each count is `None` if it does not fit in a `u128`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::count::overpartition_numbers;
+///
+/// let p: Vec<u128> = overpartition_numbers(6).into_iter().map(Option::unwrap).collect();
+/// assert_eq!(p, vec![1, 2, 4, 8, 14, 24, 40]);
+/// ```
+pub fn overpartition_numbers(n: usize) -> Vec<Option<u128>> {
+    overpartition_counts(n, |_| true)
+}
+
+/// Counts the overpartitions of `n`.
+pub fn overpartitions(n: usize) -> Option<u128> {
+    overpartition_numbers(n)[n]
+}
+
+/// Returns the numbers of overpartitions of `0, …, n` into odd parts.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::count::odd_overpartition_numbers;
+///
+/// let p: Vec<u128> = odd_overpartition_numbers(6).into_iter().map(Option::unwrap).collect();
+/// assert_eq!(p, vec![1, 2, 2, 4, 6, 8, 12]);
+/// ```
+pub fn odd_overpartition_numbers(n: usize) -> Vec<Option<u128>> {
+    overpartition_counts(n, |k| k % 2 == 1)
+}
+
+/// Counts the overpartitions of `n` into odd parts.
+pub fn odd_overpartitions(n: usize) -> Option<u128> {
+    odd_overpartition_numbers(n)[n]
+}
+
+/// Counts the smallest parts over all the partitions of `n`, Andrews'
+/// function `spt(n)`.
+///
+/// Small `n` are done by adding up over the partitions themselves.
+/// Beyond that this uses Andrews' generating function
+///
+/// ```text
+/// Σ spt(n) q^n = 1/(q)_∞ · ( Σ σ(k) q^k + Σ (-1)^k q^(k(3k+1)/2) (1 + q^k)/(1 - q^k)² ),
+/// ```
+///
+/// where `σ` is the sum of divisors, which takes `O(n²)` steps. The
+/// values modulo a number for all `n` up to the millions can be had
+/// from `series::spt_mod` instead.
+///
+/// Returns `None` if the count does not fit in a `u128`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::count::spt;
+///
+/// // 1 + 1 + 1 + 1, 1 + 1 + 2, 1 + 3, 2 + 2 and 4.
+/// assert_eq!(spt(4), Some(10));
+/// assert_eq!(spt(100), Some(1_545_832_615));
+/// ```
+pub fn spt(n: usize) -> Option<u128> {
+    if n <= 20 {
+        let mut total = 0;
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            total += p.iter().take_while(|&&x| x == p[0]).count() as u128;
+        }
+        return Some(total);
+    }
+
+    let num = spt_numerator(n);
+    let p = partition_numbers(n);
+    let mut total: i128 = 0;
+    for (i, &c) in num.iter().enumerate() {
+        let t = i128::try_from(p[n - i]?).ok()?.checked_mul(c as i128)?;
+        total = total.checked_add(t)?;
+    }
+    Some(total as u128)
+}
+
+/// Returns the first `n + 1` terms of the series that `1/(q)_∞`
+/// multiplies in the generating function of `spt`.
+pub(crate) fn spt_numerator(n: usize) -> Vec<i64> {
+    let mut num = vec![0i64; n + 1];
+    for d in 1..(n + 1) {
+        for k in (d..(n + 1)).step_by(d) {
+            num[k] += d as i64;
+        }
+    }
+    // (1 + q^k)/(1 - q^k)² = Σ (2j + 1) q^(kj)
+    let mut k = 1;
+    while k * (3 * k + 1) / 2 <= n {
+        let sign = if k % 2 == 1 { -1 } else { 1 };
+        for (j, e) in ((k * (3 * k + 1) / 2)..(n + 1)).step_by(k).enumerate() {
+            num[e] += sign * (2 * j as i64 + 1);
+        }
+        k += 1;
+    }
+    num
+}
+
+/// Returns the numbers of partitions of `n` with `0, 1, 2, …` corners,
+/// that is with that many distinct part sizes, as far as the largest
+/// number of corners possible.
+///
+/// This expands `∏ (1 + x q^k / (1 - q^k))` a part size at a time, which
+/// takes `O(n^2.5)` steps. Each count is `None` if it does not fit in a
+/// `u128`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::count::partitions_by_corners;
+///
+/// // 1+1+1+1+1+1, 2+2+2, 3+3 and 6 have one corner; 1+2+3 has three.
+/// let c: Vec<u128> = partitions_by_corners(6).into_iter().map(Option::unwrap).collect();
+/// assert_eq!(c, vec![0, 4, 6, 1]);
+/// ```
+pub fn partitions_by_corners(n: usize) -> Vec<Option<u128>> {
+    let mut most = 0;
+    while (most + 1) * (most + 2) / 2 <= n {
+        most += 1;
+    }
+
+    // f[c][m] counts the partitions of m into the part sizes so far with
+    // c of them used. A new size k adds Σ_(j ≥ 1) f[c - 1][m - jk] to
+    // f[c][m], a sum that is kept running along m.
+    let mut f = vec![vec![Some(0u128); n + 1]; most + 1];
+    f[0][0] = Some(1);
+    let mut run = vec![Some(0u128); n + 1];
+    for k in 1..(n + 1) {
+        for c in (1..(most + 1)).rev() {
+            for m in 0..(n + 1) {
+                run[m] = if m < k {
+                    Some(0)
+                } else {
+                    f[c - 1][m - k].and_then(|a| a.checked_add(run[m - k]?))
+                };
+            }
+            for m in k..(n + 1) {
+                f[c][m] = f[c][m].and_then(|a| a.checked_add(run[m]?));
+            }
+        }
+    }
+    f.iter().map(|row| row[n]).collect()
+}
+
+/// Returns the numbers of partitions of `n` whose Young diagrams have
+/// half-perimeter `0, 1, …, n + 1`, where the half-perimeter is the
+/// largest part plus the number of parts.
+///
+/// Taking off the outer hook of a partition with half-perimeter `s`
+/// leaves a partition of `n - s + 1` that fits in a box of `r` rows
+/// and `c` columns for each `r + c = s - 2` with room for it, so the
+/// counts follow from those for smaller `n`, in `O(n²)` steps and space.
+/// Each count is `None` if it, or a term along the way, does not fit in
+/// a `u128`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::count::partitions_by_half_perimeter;
+///
+/// // 2+2 has half-perimeter 4, the other partitions of 4 have 5.
+/// let c: Vec<u128> = partitions_by_half_perimeter(4).into_iter().map(Option::unwrap).collect();
+/// assert_eq!(c, vec![0, 0, 0, 0, 1, 4]);
+/// ```
+pub fn partitions_by_half_perimeter(n: usize) -> Vec<Option<u128>> {
+    let add = |a: Option<u128>, b: Option<u128>| a.and_then(|a| a.checked_add(b?));
+
+    // below[m][k] holds the number of partitions of m with half-perimeter
+    // at most k, and the sum of their half-perimeters.
+    let mut below: Vec<Vec<(Option<u128>, Option<u128>)>> = Vec::with_capacity(n + 1);
+    let mut counts = Vec::new();
+    for m in 0..(n + 1) {
+        counts.clear();
+        counts.resize(m + 2, Some(0u128));
+        if m == 0 {
+            counts[0] = Some(1);
+        }
+        for (s, count) in counts.iter_mut().enumerate().skip(2) {
+            // Each partition of the rest with half-perimeter h ≤ s - 2
+            // fits in s - 1 - h of the boxes.
+            let rest = m + 1 - s;
+            let k = (s - 2).min(rest + 1);
+            let (c0, c1) = below[rest][k];
+            *count = c0.and_then(|c0| (s as u128 - 1).checked_mul(c0)).and_then(|t| Some(t - c1?));
+        }
+
+        let mut row = Vec::with_capacity(m + 2);
+        let (mut c0, mut c1) = (Some(0u128), Some(0u128));
+        for (h, &c) in counts.iter().enumerate() {
+            c0 = add(c0, c);
+            c1 = add(c1, c.and_then(|c| c.checked_mul(h as u128)));
+            row.push((c0, c1));
+        }
+        below.push(row);
+    }
+    counts
+}
+
+#[test]
+fn brute_force() {
+    //! Compares against direct recursion over the vectors.
+
+    fn ways(vectors: &[&[usize]], target: &mut [usize]) -> u128 {
+        if target.iter().all(|&t| t == 0) {
+            return 1;
+        }
+        let v = match vectors.first() {
+            Some(v) => v,
+            None => return 0,
+        };
+
+        let mut total = ways(&vectors[1..], target);
+        let mut used = 0;
+        while target.iter().zip(v.iter()).all(|(t, c)| t >= c) {
+            for (t, c) in target.iter_mut().zip(v.iter()) {
+                *t -= c;
+            }
+            used += 1;
+            total += ways(&vectors[1..], target);
+        }
+        for (t, c) in target.iter_mut().zip(v.iter()) {
+            *t += used * c;
+        }
+        total
+    }
+
+    let roots = type_a_positive_roots(3);
+    let roots: Vec<&[usize]> = roots.iter().map(|r| &r[..]).collect();
+    let other: &[&[usize]] = &[&[1, 0, 2], &[0, 1, 0], &[2, 1, 0], &[1, 1, 1], &[0, 0, 1]];
+
+    for a in 0..5 {
+        for b in 0..5 {
+            for c in 0..5 {
+                let t = [a, b, c];
+                assert_eq!(kostant_type_a(&t), Some(ways(&roots, &mut t.clone())));
+                assert_eq!(kostant(other, &t), Some(ways(other, &mut t.clone())));
+            }
+        }
+    }
+
+    let ones: Vec<usize> = (1..61).collect();
+    assert_eq!(partitions_into(60, &ones), Some(966467));
+    for n in 0..61 {
+        assert_eq!(partitions_into(n, &ones[..n]).map(|c| c as u64), Some(p_const(n)));
+    }
+    assert_eq!(p_const(P_CONST_MAX), 17_873_792_969_689_876_004);
+}
+
+#[test]
+fn smallest_parts() {
+    //! Compares the generating function for `spt` against enumeration,
+    //! and the values modulo a number against the exact ones.
+
+    use series::spt_mod;
+
+    let mut exact = Vec::new();
+    for n in 0..60 {
+        let mut total = 0;
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            total += p.iter().take_while(|&&x| x == p[0]).count() as u128;
+        }
+        assert_eq!(spt(n), Some(total));
+        exact.push(total);
+    }
+
+    for &m in &[1, 11, 1 << 20] {
+        let got = spt_mod(59, m);
+        assert!(got.iter().zip(&exact).all(|(&a, &b)| a as u128 == b % m as u128));
+    }
+    // Andrews' congruence spt(5k + 4) ≡ 0 (mod 5).
+    assert!(spt_mod(20_000, 5).iter().skip(4).step_by(5).all(|&x| x == 0));
+}
+
+#[test]
+fn overpartition_counts_brute_force() {
+    //! Counts overpartitions by choosing which distinct parts of each
+    //! partition to overline, and compares the values modulo a number.
+
+    use series::{odd_overpartition_numbers_mod, overpartition_numbers_mod};
+
+    let (mut all, mut odd) = (Vec::new(), Vec::new());
+    for n in 0..40 {
+        let (mut a, mut o) = (0u128, 0u128);
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            let distinct = 1 + p.windows(2).filter(|w| w[0] < w[1]).count() - (n == 0) as usize;
+            a += 1 << distinct;
+            if p.iter().all(|x| x % 2 == 1) {
+                o += 1 << distinct;
+            }
+        }
+        assert_eq!(overpartitions(n), Some(a));
+        assert_eq!(odd_overpartitions(n), Some(o));
+        all.push(a);
+        odd.push(o);
+    }
+
+    for &m in &[1, 3, 1000, 1 << 30] {
+        let got = overpartition_numbers_mod(39, m);
+        assert!(got.iter().zip(&all).all(|(&a, &b)| a as u128 == b % m as u128));
+        let got = odd_overpartition_numbers_mod(39, m);
+        assert!(got.iter().zip(&odd).all(|(&a, &b)| a as u128 == b % m as u128));
+    }
+    // The counts for large n agree with the series method too.
+    let exact = overpartition_numbers(2000);
+    let got = overpartition_numbers_mod(2000, 1_000_003);
+    for (e, &g) in exact.iter().zip(&got) {
+        if let Some(e) = *e {
+            assert_eq!(e % 1_000_003, g as u128);
+        }
+    }
+}
+
+#[test]
+fn corners_and_perimeter() {
+    //! Compares the distributions against enumeration.
+
+    for n in 0..30 {
+        let (mut corners, mut half) = (Vec::new(), vec![0u128; n + 2]);
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            let c = p.len() - p.windows(2).filter(|w| w[0] == w[1]).count();
+            if corners.len() <= c {
+                corners.resize(c + 1, 0u128);
+            }
+            corners[c] += 1;
+            half[p.last().map_or(0, |&x| x + p.len())] += 1;
+        }
+        let got: Vec<u128> = partitions_by_corners(n).into_iter().map(Option::unwrap).collect();
+        assert_eq!(got, corners);
+        let got: Vec<u128> = partitions_by_half_perimeter(n).into_iter().map(Option::unwrap).collect();
+        assert_eq!(got, half);
+    }
+
+    let total = |v: Vec<Option<u128>>| v.into_iter().map(Option::unwrap).sum::<u128>();
+    let p = partition_numbers(400);
+    assert_eq!(Some(total(partitions_by_corners(400))), p[400]);
+    assert_eq!(Some(total(partitions_by_half_perimeter(400))), p[400]);
+}
+
+#[test]
+fn restricted_counts() {
+    //! Compares the counts against the iterators that list them.
+
+    use distinct::DistinctPartitions;
+
+    for n in 0..25 {
+        let mut c = 0;
+        let mut dd = DistinctPartitions::new(n);
+        while dd.next().is_some() {
+            c += 1;
+        }
+        assert_eq!(partition_count_distinct(n), Some(c));
+        assert_eq!(partition_count(n), Some(p_const(n) as u128));
+
+        for k in 0..(n + 2) {
+            for m in 0..(n + 2) {
+                let mut c = 0;
+                let mut rr = Partitions::builder(n).max_parts(k).max_part(m).build();
+                while rr.next().is_some() {
+                    c += 1;
+                }
+                assert_eq!(partition_count_in_box(n, k, m), Some(c));
+            }
+            let mut c = 0;
+            let mut rr = Partitions::builder(n).max_parts(k).build();
+            while rr.next().is_some() {
+                c += 1;
+            }
+            assert_eq!(partition_count_max_parts(n, k), Some(c));
+            assert_eq!(partition_count_max_part(n, k), Some(c));
+        }
+    }
+
+    assert!(partition_count(1416).is_some());
+    assert_eq!(partition_count(1417), None);
+
+    #[cfg(feature = "num-bigint")]
+    {
+        for n in (0..1417).step_by(7) {
+            assert_eq!(partition_count_big(n), BigUint::from(partition_count(n).unwrap()));
+        }
+        assert_eq!(partition_count_big(2000).to_string(), "4720819175619413888601432406799959512200344166");
+    }
+}
@@ -0,0 +1,321 @@
+//! Sharing one enumeration between several threads.
+
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use rank::RankTable;
+use streaming_iterator::StreamingIterator;
+use Partitions;
+
+/// A cursor over the partitions of `n` that many threads can draw from
+/// at once.
+///
+/// Each thread makes its own `Worker`, which claims blocks of
+/// consecutive partitions by atomically advancing a shared counter, and
+/// starts each block by unranking its first partition. Every partition
+/// goes to exactly one worker, and a thread that is given expensive
+/// partitions simply claims fewer blocks, so the load balances itself
+/// without any work-stealing queue.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::thread;
+/// use integer_partitions::cursor::SharedCursor;
+///
+/// let cursor = SharedCursor::new(30, 64);
+/// let total = AtomicUsize::new(0);
+///
+/// thread::scope(|s| {
+///     for _ in 0..4 {
+///         s.spawn(|| {
+///             let mut w = cursor.worker();
+///             while let Some(p) = w.next() {
+///                 total.fetch_add(p.len(), Ordering::Relaxed);
+///             }
+///         });
+///     }
+/// });
+///
+/// // The total number of parts over all partitions of 30.
+/// assert_eq!(total.into_inner(), 54563);
+/// ```
+#[derive(Debug)]
+pub struct SharedCursor {
+    table: RankTable,
+    block: usize,
+    next: AtomicUsize,
+}
+
+impl SharedCursor {
+    /// Makes a new cursor handing out blocks of `block` partitions.
+    ///
+    /// This builds a ranking table of `O(n²)` counts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block` is zero, or if `n` is greater than
+    /// `count::P_CONST_MAX`, past which the number of partitions does
+    /// not fit in a `u64`.
+    pub fn new(n: usize, block: usize) -> SharedCursor {
+        assert!(block > 0, "blocks must not be empty");
+        SharedCursor {
+            table: RankTable::new(n),
+            block,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// The number of partitions not yet claimed by any worker.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.table.count().saturating_sub(self.next.load(Ordering::Relaxed))
+    }
+
+    /// Makes a worker that draws blocks from this cursor.
+    #[inline]
+    pub fn worker<'a>(&'a self) -> Worker<'a> {
+        Worker {
+            cursor: self,
+            parts: None,
+            buf: Vec::new(),
+            rank: 0,
+            end: 0,
+        }
+    }
+}
+
+/// Iterates over the partitions claimed from a `SharedCursor`.
+///
+/// Within a block partitions come in the order of `Partitions`, but the
+/// blocks themselves may be spread among workers in any way.
+#[derive(Debug)]
+pub struct Worker<'a> {
+    cursor: &'a SharedCursor,
+    parts: Option<Partitions>,
+    buf: Vec<usize>,
+    rank: usize,
+    end: usize,
+}
+
+impl<'a> Worker<'a> {
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Returns the rank of the current partition among all the
+    /// partitions of `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator is not on a partition.
+    #[inline]
+    pub fn rank(&self) -> usize {
+        self.get().expect("no current partition");
+        self.rank
+    }
+}
+
+impl<'a> StreamingIterator for Worker<'a> {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        self.parts.as_ref().and_then(|p| p.get())
+    }
+
+    fn advance(&mut self) {
+        if let Some(ref mut parts) = self.parts {
+            if self.rank + 1 < self.end {
+                self.rank += 1;
+                parts.advance();
+                return;
+            }
+        }
+
+        let cursor = self.cursor;
+        let total = cursor.table.count();
+        let start = cursor.next.fetch_add(cursor.block, Ordering::Relaxed);
+        if start >= total {
+            // Put the counter back near the end, so that it cannot wrap
+            // around however often exhausted workers are advanced.
+            cursor.next.store(total, Ordering::Relaxed);
+            self.parts = None;
+            return;
+        }
+
+        cursor.table.unrank(start, &mut self.buf);
+        self.parts = Some(Partitions::at(&self.buf));
+        self.rank = start;
+        self.end = (start + cursor.block).min(total);
+    }
+}
+
+/// Iterates over a run of consecutive partitions of `n`, made by
+/// `Partitions::par_ranges`.
+///
+/// Each range starts by unranking its first partition and then goes on
+/// in the order of `Partitions`, so ranges share nothing and can be
+/// sent to different threads.
+#[derive(Debug)]
+pub struct PartitionRange {
+    parts: Partitions,
+    rank: usize,
+    end: usize,
+    state: State,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum State {
+    Fresh,
+    Going,
+    Done,
+}
+
+impl PartitionRange {
+    pub(crate) fn new(table: &RankTable, n: usize, start: usize, end: usize) -> PartitionRange {
+        let mut buf = Vec::new();
+        table.unrank_in(n, start, &mut buf);
+        PartitionRange {
+            parts: Partitions::at(&buf),
+            rank: start,
+            end,
+            state: State::Fresh,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Returns the ranks of the partitions not yet returned, together
+    /// with the current one, among all the partitions of `n`.
+    #[inline]
+    pub fn ranks(&self) -> ::core::ops::Range<usize> {
+        match self.state {
+            State::Done => self.end..self.end,
+            _ => self.rank..self.end,
+        }
+    }
+
+    /// Returns the rank of the current partition among all the
+    /// partitions of `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator is not on a partition.
+    #[inline]
+    pub fn rank(&self) -> usize {
+        self.get().expect("no current partition");
+        self.rank
+    }
+}
+
+impl StreamingIterator for PartitionRange {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        match self.state {
+            State::Going => self.parts.get(),
+            _ => None,
+        }
+    }
+
+    fn advance(&mut self) {
+        match self.state {
+            State::Fresh => self.state = State::Going,
+            State::Going if self.rank + 1 < self.end => {
+                self.rank += 1;
+                self.parts.advance();
+            }
+            _ => self.state = State::Done,
+        }
+    }
+}
+
+#[test]
+fn disjoint_blocks() {
+    //! Tests that the workers between them see each partition once.
+
+    use std::sync::Mutex;
+    use std::thread;
+
+    for &(n, block) in &[(0, 1), (1, 5), (12, 1), (20, 7), (25, 1000)] {
+        let cursor = SharedCursor::new(n, block);
+        let seen = Mutex::new(Vec::new());
+
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    let mut w = cursor.worker();
+                    let mut mine = Vec::new();
+                    while let Some(p) = w.next().map(|p| p.to_vec()) {
+                        mine.push((w.rank(), p));
+                    }
+                    assert_eq!(w.next(), None);
+                    seen.lock().unwrap().extend(mine);
+                });
+            }
+        });
+        assert_eq!(cursor.remaining(), 0);
+
+        let mut seen = seen.into_inner().unwrap();
+        seen.sort();
+        let mut pp = Partitions::new(n);
+        let mut i = 0;
+        while let Some(p) = pp.next() {
+            assert_eq!(seen[i], (i, p.to_vec()));
+            i += 1;
+        }
+        assert_eq!(seen.len(), i);
+    }
+}
+
+#[test]
+fn ranges() {
+    //! Tests that the ranges between them see each partition once, in
+    //! order.
+
+    use std::thread;
+
+    for &(n, chunks) in &[(0, 1), (0, 3), (1, 2), (12, 1), (12, 5), (20, 7), (6, 100)] {
+        let ranges = Partitions::par_ranges(n, chunks);
+        let seen: Vec<Vec<(usize, Vec<usize>)>> = thread::scope(|s| {
+            let handles: Vec<_> = ranges
+                .into_iter()
+                .map(|mut r| {
+                    s.spawn(move || {
+                        let mut mine = Vec::new();
+                        assert_eq!(r.get(), None);
+                        while let Some(p) = r.next().map(|p| p.to_vec()) {
+                            mine.push((r.rank(), p));
+                        }
+                        assert_eq!(r.next(), None);
+                        assert!(r.ranks().is_empty());
+                        mine
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert!(seen.len() <= chunks && seen.iter().all(|r| !r.is_empty()));
+        assert!(seen.iter().all(|r| r.len() + 1 >= seen[0].len() && r.len() <= seen[0].len() + 1));
+
+        let seen: Vec<_> = seen.into_iter().flatten().collect();
+        let mut pp = Partitions::new(n);
+        let mut i = 0;
+        while let Some(p) = pp.next() {
+            assert_eq!(seen[i], (i, p.to_vec()));
+            i += 1;
+        }
+        assert_eq!(seen.len(), i);
+    }
+}
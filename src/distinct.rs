@@ -0,0 +1,226 @@
+//! Partitions into distinct parts.
+//!
+//! `DistinctPartitions` lists the strict partitions of a number, those
+//! with no part repeated, in the same lexicographic order as
+//! `Partitions` and with their parts in increasing order. It makes them
+//! directly rather than by filtering, in constant amortized time for
+//! each: the lexicographic successor of a strict partition only ever
+//! changes its last two parts and what comes after them.
+//!
+//! # Examples
+//!
+//! ```
+//! use integer_partitions::distinct::DistinctPartitions;
+//!
+//! let mut pp = DistinctPartitions::new(6);
+//! assert_eq!(pp.next(), Some(&[1, 2, 3][..]));
+//! assert_eq!(pp.next(), Some(&[1, 5][..]));
+//! assert_eq!(pp.next(), Some(&[2, 4][..]));
+//! assert_eq!(pp.next(), Some(&[6][..]));
+//! assert_eq!(pp.next(), None);
+//! ```
+
+use alloc::vec::Vec;
+
+use streaming_iterator::StreamingIterator;
+#[cfg(feature = "validate")]
+use validate::{Order, Validator};
+use PartitionIterator;
+
+/// Iterates over the partitions of a given nonnegative integer into
+/// distinct parts.
+#[derive(Debug)]
+pub struct DistinctPartitions {
+    n: usize,
+    a: Vec<usize>,
+    state: State,
+    #[cfg(feature = "validate")]
+    check: Validator<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum State {
+    Fresh,
+    Going,
+    Done,
+}
+
+impl DistinctPartitions {
+    /// Makes a new iterator.
+    #[inline]
+    pub fn new(n: usize) -> DistinctPartitions {
+        DistinctPartitions::recycle(n, Vec::new())
+    }
+
+    /// Makes a new iterator, trying to avoid allocations.
+    ///
+    /// Any vector can be passed to this function, since its contents
+    /// will be cleared, but note that the vector will still reallocate
+    /// if its capacity is less than the number of parts needed.
+    pub fn recycle(n: usize, mut vec: Vec<usize>) -> DistinctPartitions {
+        vec.clear();
+        DistinctPartitions {
+            n,
+            a: vec,
+            state: State::Fresh,
+            #[cfg(feature = "validate")]
+            check: Validator::new("distinct::DistinctPartitions", n, Order::Lex).strict(),
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Restarts the iterator at the first partition of `n`.
+    ///
+    /// The existing vector is reused, and only reallocates if its
+    /// capacity is less than the number of parts needed.
+    pub fn reinit(&mut self, n: usize) {
+        self.n = n;
+        self.a.clear();
+        self.state = State::Fresh;
+        #[cfg(feature = "validate")]
+        {
+            self.check = Validator::new("distinct::DistinctPartitions", n, Order::Lex).strict();
+        }
+    }
+
+    /// Destroys the iterator and returns a vector for further use.
+    ///
+    /// You only need to call this function if you want to reuse the
+    /// vector for something else. Its contents will be in an undefined
+    /// state, and so cannot be relied upon.
+    #[inline]
+    pub fn end(self) -> Vec<usize> {
+        self.a
+    }
+
+    /// Appends the least increasing parts, all greater than `x - 1`,
+    /// that add up to `s`.
+    #[inline]
+    fn fill(&mut self, mut x: usize, mut s: usize) {
+        while 2 * x < s {
+            self.a.push(x);
+            s -= x;
+            x += 1;
+        }
+        if s > 0 {
+            self.a.push(s);
+        }
+    }
+}
+
+impl StreamingIterator for DistinctPartitions {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        match self.state {
+            State::Going => Some(&self.a),
+            _ => None,
+        }
+    }
+
+    fn advance(&mut self) {
+        match self.state {
+            State::Fresh => {
+                let n = self.n;
+                self.fill(1, n);
+                self.state = State::Going;
+            }
+            State::Going => {
+                let k = self.a.len();
+                if k < 2 {
+                    self.state = State::Done;
+                } else {
+                    // Grow the second last part by one if the last can
+                    // still be refilled above it, or else merge the two.
+                    let y = self.a[k - 2] + 1;
+                    let r = self.a[k - 1] - 1;
+                    self.a.pop();
+                    if r > y {
+                        self.a[k - 2] = y;
+                        self.fill(y + 1, r);
+                    } else {
+                        self.a[k - 2] = y + r;
+                    }
+                }
+            }
+            State::Done => {}
+        }
+
+        #[cfg(feature = "validate")]
+        self.check.check(self.get());
+    }
+}
+
+impl PartitionIterator for DistinctPartitions {
+    #[inline]
+    fn n(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn reinit(&mut self, n: usize) {
+        DistinctPartitions::reinit(self, n)
+    }
+
+    #[inline]
+    fn end(self) -> Vec<usize> {
+        DistinctPartitions::end(self)
+    }
+}
+
+#[test]
+fn filtered() {
+    //! Compares against filtering `Partitions`, and the counts with
+    //! A000009.
+
+    use Partitions;
+
+    for n in 0..40 {
+        let mut pp = Partitions::new(n);
+        let mut dd = DistinctPartitions::new(n);
+        while let Some(p) = pp.next() {
+            if p.windows(2).all(|w| w[0] < w[1]) {
+                assert_eq!(dd.next(), Some(p));
+            }
+        }
+        assert_eq!(dd.next(), None);
+        assert_eq!(dd.next(), None);
+    }
+
+    let mut dd = DistinctPartitions::new(100);
+    let mut c = 0;
+    while dd.next().is_some() {
+        c += 1;
+    }
+    assert_eq!(c, 444793);
+}
+
+#[test]
+fn reinit() {
+    //! Tests that a reinitialised or recycled iterator gives the same
+    //! partitions as a new one.
+
+    let mut dd = DistinctPartitions::new(0);
+    for &n in &[7, 20, 3, 0, 12] {
+        dd.reinit(n);
+        let mut fresh = DistinctPartitions::new(n);
+        while let Some(p) = fresh.next() {
+            assert_eq!(dd.next(), Some(p));
+        }
+        assert_eq!(dd.next(), None);
+    }
+
+    let mut dd = DistinctPartitions::recycle(10, dd.end());
+    let mut c = 0;
+    while dd.next().is_some() {
+        c += 1;
+    }
+    assert_eq!(c, 10);
+}
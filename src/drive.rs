@@ -0,0 +1,250 @@
+//! Long enumerations that can be stopped and picked up again.
+//!
+//! `drive` visits the partitions of a number in the order of
+//! `Partitions`, and every so often checks a `Budget`: a flag that
+//! another thread can set to cancel the run, a deadline, or both. When
+//! the budget runs out it returns what it has gathered so far with a
+//! `Checkpoint`, which names the first partition not yet visited and can
+//! be written out as bytes, so the run can be carried on later, perhaps
+//! in another process.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::atomic::AtomicBool;
+//! use integer_partitions::drive::{drive, Budget, Checkpoint, Outcome};
+//!
+//! let cancel = AtomicBool::new(false);
+//! let budget = Budget::new().cancel_on(&cancel).check_every(100);
+//!
+//! // Stop after about a thousand partitions, then carry on from the
+//! // saved bytes.
+//! let mut from = Checkpoint::start(30);
+//! let mut parts = 0;
+//! loop {
+//!     let mut seen = 0;
+//!     match drive(&from, &budget, 0, |total, p| {
+//!         *total += p.len();
+//!         seen += 1;
+//!         if seen == 1000 {
+//!             cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+//!         }
+//!     }) {
+//!         Outcome::Finished(total) => {
+//!             parts += total;
+//!             break;
+//!         }
+//!         Outcome::Stopped(total, at) => {
+//!             parts += total;
+//!             from = Checkpoint::from_bytes(&at.to_bytes()).unwrap();
+//!             cancel.store(false, std::sync::atomic::Ordering::Relaxed);
+//!         }
+//!     }
+//! }
+//! assert_eq!(parts, 54563);
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+use codec::{decode, encode, DecodeError};
+use streaming_iterator::StreamingIterator;
+use Partitions;
+
+/// When to stop a run of `drive`.
+///
+/// The budget is checked before the first partition and then once
+/// every `check_every` partitions, 1024 unless set, so a run may go a
+/// little past its deadline or a cancellation.
+#[derive(Debug, Clone)]
+pub struct Budget<'a> {
+    cancel: Option<&'a AtomicBool>,
+    deadline: Option<Instant>,
+    every: usize,
+}
+
+impl<'a> Budget<'a> {
+    /// Makes a budget that never runs out.
+    pub fn new() -> Budget<'a> {
+        Budget {
+            cancel: None,
+            deadline: None,
+            every: 1024,
+        }
+    }
+
+    /// Stops once `flag` is set.
+    pub fn cancel_on(mut self, flag: &'a AtomicBool) -> Budget<'a> {
+        self.cancel = Some(flag);
+        self
+    }
+
+    /// Stops once `at` has passed.
+    pub fn deadline(mut self, at: Instant) -> Budget<'a> {
+        self.deadline = Some(at);
+        self
+    }
+
+    /// Checks the budget once every `k` partitions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is zero.
+    pub fn check_every(mut self, k: usize) -> Budget<'a> {
+        assert!(k > 0, "must check at some interval");
+        self.every = k;
+        self
+    }
+
+    /// Returns whether the budget has run out.
+    pub fn is_spent(&self) -> bool {
+        self.cancel.is_some_and(|c| c.load(Ordering::Relaxed))
+            || self.deadline.is_some_and(|d| Instant::now() >= d)
+    }
+}
+
+impl<'a> Default for Budget<'a> {
+    fn default() -> Budget<'a> {
+        Budget::new()
+    }
+}
+
+/// Where a run of `drive` starts: the first partition it will visit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Checkpoint {
+    next: Vec<usize>,
+}
+
+impl Checkpoint {
+    /// Makes a checkpoint at the first partition of `n`.
+    pub fn start(n: usize) -> Checkpoint {
+        Checkpoint { next: vec![1; n] }
+    }
+
+    /// Makes a checkpoint at `p`, so that a run visits `p` and the
+    /// partitions after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not in non-decreasing order, or has a part of
+    /// zero.
+    pub fn at(p: &[usize]) -> Checkpoint {
+        assert!(p.first() != Some(&0), "parts must be positive");
+        assert!(p.windows(2).all(|w| w[0] <= w[1]), "parts must be in non-decreasing order");
+        Checkpoint { next: p.to_vec() }
+    }
+
+    /// Returns the number being partitioned.
+    #[inline]
+    pub fn n(&self) -> usize {
+        self.next.iter().sum()
+    }
+
+    /// Returns the first partition that a run from here visits.
+    #[inline]
+    pub fn next_partition(&self) -> &[usize] {
+        &self.next
+    }
+
+    /// Returns the checkpoint as bytes, which are the encoding of
+    /// `next_partition` by `codec`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        encode(&self.next, &mut out);
+        out
+    }
+
+    /// Reads a checkpoint written by `to_bytes` from the start of
+    /// `bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Checkpoint, DecodeError> {
+        let mut next = Vec::new();
+        decode(bytes, &mut next)?;
+        Ok(Checkpoint { next })
+    }
+}
+
+/// How a run of `drive` ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome<T> {
+    /// Every partition was visited, with this result.
+    Finished(T),
+    /// The budget ran out, with this result so far and the place to
+    /// carry on from.
+    Stopped(T, Checkpoint),
+}
+
+/// Visits the partitions of `from.n()` from `from` on, in the order of
+/// `Partitions`, folding each into `acc` with `f` until they run out or
+/// the budget does.
+pub fn drive<T, F>(from: &Checkpoint, budget: &Budget, mut acc: T, mut f: F) -> Outcome<T>
+where
+    F: FnMut(&mut T, &[usize]),
+{
+    let mut pp = Partitions::at(&from.next);
+    let mut count = 0;
+    while let Some(p) = pp.get() {
+        if count % budget.every == 0 && budget.is_spent() {
+            return Outcome::Stopped(acc, Checkpoint { next: p.to_vec() });
+        }
+        f(&mut acc, p);
+        count += 1;
+        pp.advance();
+    }
+    Outcome::Finished(acc)
+}
+
+#[test]
+fn stop_and_resume() {
+    //! Tests that runs stopped at every point between them visit each
+    //! partition once, and the other ways of stopping.
+
+    use std::time::Duration;
+
+    for n in 0..15 {
+        let mut all = Vec::new();
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            all.push(p.to_vec());
+        }
+
+        for every in 1..5 {
+            let cancel = AtomicBool::new(false);
+            let budget = Budget::new().cancel_on(&cancel).check_every(every);
+            let mut seen = Vec::new();
+            let mut from = Checkpoint::start(n);
+            let mut runs = 0;
+            loop {
+                cancel.store(false, Ordering::Relaxed);
+                let outcome = drive(&from, &budget, Vec::new(), |v, p| {
+                    v.push(p.to_vec());
+                    // Cancel after the first of every run, so that it
+                    // stops at the next check.
+                    cancel.store(true, Ordering::Relaxed);
+                });
+                runs += 1;
+                match outcome {
+                    Outcome::Finished(v) => {
+                        seen.extend(v);
+                        break;
+                    }
+                    Outcome::Stopped(v, at) => {
+                        assert_eq!(v.len(), every);
+                        seen.extend(v);
+                        assert_eq!(at.n(), n);
+                        assert_eq!(at.next_partition(), &all[seen.len()][..]);
+                        from = Checkpoint::from_bytes(&at.to_bytes()).unwrap();
+                        assert_eq!(from, at);
+                    }
+                }
+            }
+            assert_eq!(seen, all);
+            assert_eq!(runs, all.len().div_ceil(every));
+        }
+    }
+
+    let passed = Budget::new().deadline(Instant::now());
+    assert_eq!(drive(&Checkpoint::start(5), &passed, 0, |c, _| *c += 1), Outcome::Stopped(0, Checkpoint::start(5)));
+    let later = Budget::new().deadline(Instant::now() + Duration::from_secs(3600));
+    assert_eq!(drive(&Checkpoint::at(&[2, 3]), &later, 0, |c, _| *c += 1), Outcome::Finished(2));
+    assert_eq!(drive(&Checkpoint::start(0), &Budget::default(), 0, |c, _| *c += 1), Outcome::Finished(1));
+}
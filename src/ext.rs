@@ -0,0 +1,724 @@
+//! Combinators for streams of partitions.
+//!
+//! The adaptors of `streaming_iterator` work on any stream, and know
+//! nothing about partitions. The ones here apply to every streaming
+//! iterator over partitions in non-decreasing order, such as those of
+//! this crate, and make use of that structure.
+
+use alloc::vec::Vec;
+
+use rank::RankTable;
+use streaming_iterator::StreamingIterator;
+use young::conjugate;
+
+/// What to do with a partition met by `restrict`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Verdict {
+    /// Yield the partition.
+    Keep,
+    /// Drop the partition and carry on.
+    Skip,
+    /// Drop the partition and every one after it that starts with the
+    /// same first `len` parts, without asking about them.
+    ///
+    /// In the order of `Partitions` these are the partitions right
+    /// after it, so this prunes the whole subtree below that prefix. The
+    /// stream underneath still steps through them, but the predicate is
+    /// not called again until a partition leaves the subtree.
+    SkipPrefix(usize),
+    /// Drop the partition and end the stream, because no later one can
+    /// be kept either.
+    Stop,
+}
+
+/// Extra methods for streaming iterators over partitions.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::Partitions;
+/// use integer_partitions::ext::{PartitionStreamExt, Verdict};
+///
+/// // Partitions come in lexicographic order, so once the smallest part
+/// // passes 2 none of the rest can have one of at most 2.
+/// let mut pp = Partitions::new(6).restrict(|p| match p[0] {
+///     1 => Verdict::Skip,
+///     2 => Verdict::Keep,
+///     _ => Verdict::Stop,
+/// }).conjugated();
+///
+/// assert_eq!(pp.next(), Some(&[3, 3][..]));
+/// assert_eq!(pp.next(), Some(&[1, 1, 2, 2][..]));
+/// assert_eq!(pp.next(), None);
+/// ```
+pub trait PartitionStreamExt: StreamingIterator<Item = [usize]> + Sized {
+    /// Yields the conjugate of each partition instead.
+    #[inline]
+    fn conjugated(self) -> Conjugated<Self> {
+        Conjugated {
+            inner: self,
+            conj: Vec::new(),
+        }
+    }
+
+    /// Yields only the partitions for which `f` returns `Keep`, ending
+    /// the stream as soon as it returns `Stop`, and passing over the
+    /// partitions below a prefix when it returns `SkipPrefix`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use integer_partitions::Partitions;
+    /// use integer_partitions::ext::{PartitionStreamExt, Verdict};
+    ///
+    /// // Ask once about the partitions of 7 that start with two ones,
+    /// // which are the 7 partitions of 5 with 1 + 1 in front.
+    /// let mut asked = 0;
+    /// let mut pp = Partitions::new(7).restrict(|p| {
+    ///     asked += 1;
+    ///     if p.starts_with(&[1, 1]) {
+    ///         Verdict::SkipPrefix(2)
+    ///     } else {
+    ///         Verdict::Keep
+    ///     }
+    /// });
+    /// let mut kept = 0;
+    /// while pp.next().is_some() {
+    ///     kept += 1;
+    /// }
+    /// drop(pp);
+    /// assert_eq!((kept, asked), (8, 9));
+    /// ```
+    #[inline]
+    fn restrict<F>(self, f: F) -> Restrict<Self, F>
+    where
+        F: FnMut(&[usize]) -> Verdict,
+    {
+        Restrict {
+            inner: self,
+            f,
+            prefix: None,
+            stopped: false,
+        }
+    }
+
+    /// Computes a statistic of each partition as it is reached, which
+    /// can then be read with `Stat::value`.
+    #[inline]
+    fn stat<T, F>(self, f: F) -> Stat<Self, F, T>
+    where
+        F: FnMut(&[usize]) -> T,
+    {
+        Stat {
+            inner: self,
+            f,
+            value: None,
+        }
+    }
+
+    /// Keeps track of the position of each partition among all the
+    /// partitions of the same number, in the order of `Partitions`,
+    /// which can then be read with `Ranked::rank`.
+    ///
+    /// The stream panics on reaching a partition of a number greater
+    /// than `count::P_CONST_MAX`, past which ranks do not fit in a
+    /// `u64`.
+    #[inline]
+    fn ranked(self) -> Ranked<Self> {
+        Ranked {
+            inner: self,
+            table: None,
+            rank: 0,
+        }
+    }
+
+    /// Groups runs of partitions with the same smallest part, so that a
+    /// whole run can be told apart or skipped at once.
+    #[inline]
+    fn chunk_by_first_part(self) -> ChunkByFirstPart<Self> {
+        ChunkByFirstPart {
+            inner: self,
+            key: None,
+            starts: false,
+        }
+    }
+
+    /// Keeps the partition after the current one as well, which can
+    /// then be read with `Lookahead::peek_next` while the current one is
+    /// still borrowed.
+    #[inline]
+    fn lookahead(self) -> Lookahead<Self> {
+        Lookahead {
+            inner: self,
+            current: Vec::new(),
+            ahead: Vec::new(),
+            state: Ahead::Fresh,
+        }
+    }
+
+    /// Turns the stream into an `Iterator` over owned vectors, for use
+    /// with `for`, `collect` and the adaptors of `std` and other crates.
+    ///
+    /// Each partition is copied into a vector of its own. Vectors handed
+    /// back with `Owned::recycle` are reused for later partitions, so a
+    /// loop that is done with each one before the next need not allocate
+    /// at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use integer_partitions::Partitions;
+    /// use integer_partitions::ext::PartitionStreamExt;
+    ///
+    /// let all: Vec<Vec<usize>> = Partitions::new(4).into_iter_owned().collect();
+    /// assert_eq!(all, vec![vec![1, 1, 1, 1], vec![1, 1, 2], vec![1, 3], vec![2, 2], vec![4]]);
+    ///
+    /// let mut pp = Partitions::new(30).into_iter_owned();
+    /// let mut parts = 0;
+    /// while let Some(p) = pp.next() {
+    ///     parts += p.len();
+    ///     pp.recycle(p);
+    /// }
+    /// assert_eq!(parts, 54563);
+    /// ```
+    #[inline]
+    fn into_iter_owned(self) -> Owned<Self> {
+        Owned {
+            inner: self,
+            pool: Vec::new(),
+        }
+    }
+}
+
+impl<I: StreamingIterator<Item = [usize]>> PartitionStreamExt for I {}
+
+/// A stream of conjugate partitions, made by `conjugated`.
+#[derive(Debug)]
+pub struct Conjugated<I> {
+    inner: I,
+    conj: Vec<usize>,
+}
+
+impl<I: StreamingIterator<Item = [usize]>> Conjugated<I> {
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+}
+
+impl<I: StreamingIterator<Item = [usize]>> StreamingIterator for Conjugated<I> {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        self.inner.get().map(|_| &self.conj[..])
+    }
+
+    #[inline]
+    fn advance(&mut self) {
+        self.inner.advance();
+        if let Some(p) = self.inner.get() {
+            conjugate(p, &mut self.conj);
+        }
+    }
+}
+
+/// A stream of some of the partitions of another, made by `restrict`.
+#[derive(Debug)]
+pub struct Restrict<I, F> {
+    inner: I,
+    f: F,
+    /// The prefix whose partitions are being passed over.
+    prefix: Option<Vec<usize>>,
+    stopped: bool,
+}
+
+impl<I, F> Restrict<I, F>
+where
+    I: StreamingIterator<Item = [usize]>,
+    F: FnMut(&[usize]) -> Verdict,
+{
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+}
+
+impl<I, F> StreamingIterator for Restrict<I, F>
+where
+    I: StreamingIterator<Item = [usize]>,
+    F: FnMut(&[usize]) -> Verdict,
+{
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        if self.stopped {
+            None
+        } else {
+            self.inner.get()
+        }
+    }
+
+    fn advance(&mut self) {
+        if self.stopped {
+            return;
+        }
+        loop {
+            self.inner.advance();
+            let p = match self.inner.get() {
+                Some(p) => p,
+                None => return,
+            };
+            if let Some(ref q) = self.prefix {
+                if p.starts_with(q) {
+                    continue;
+                }
+            }
+            self.prefix = None;
+            match (self.f)(p) {
+                Verdict::Keep => return,
+                Verdict::Skip => {}
+                Verdict::SkipPrefix(len) => self.prefix = Some(p[..len.min(p.len())].to_vec()),
+                Verdict::Stop => {
+                    self.stopped = true;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// A stream of partitions with a statistic of each, made by `stat`.
+#[derive(Debug)]
+pub struct Stat<I, F, T> {
+    inner: I,
+    f: F,
+    value: Option<T>,
+}
+
+impl<I, F, T> Stat<I, F, T>
+where
+    I: StreamingIterator<Item = [usize]>,
+    F: FnMut(&[usize]) -> T,
+{
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Returns the statistic of the current partition, or `None` if the
+    /// iterator is not on a partition.
+    #[inline]
+    pub fn value(&self) -> Option<&T> {
+        self.value.as_ref()
+    }
+}
+
+impl<I, F, T> StreamingIterator for Stat<I, F, T>
+where
+    I: StreamingIterator<Item = [usize]>,
+    F: FnMut(&[usize]) -> T,
+{
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        self.inner.get()
+    }
+
+    #[inline]
+    fn advance(&mut self) {
+        self.inner.advance();
+        self.value = self.inner.get().map(&mut self.f);
+    }
+}
+
+/// A stream of partitions with their ranks, made by `ranked`.
+#[derive(Debug)]
+pub struct Ranked<I> {
+    inner: I,
+    table: Option<RankTable>,
+    rank: usize,
+}
+
+impl<I: StreamingIterator<Item = [usize]>> Ranked<I> {
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Returns the rank of the current partition.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator is not on a partition.
+    #[inline]
+    pub fn rank(&self) -> usize {
+        self.inner.get().expect("no current partition");
+        self.rank
+    }
+}
+
+impl<I: StreamingIterator<Item = [usize]>> StreamingIterator for Ranked<I> {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        self.inner.get()
+    }
+
+    fn advance(&mut self) {
+        self.inner.advance();
+        if let Some(p) = self.inner.get() {
+            // The table is only rebuilt if the stream moves on to
+            // partitions of a different number.
+            let n = p.iter().sum();
+            if self.table.as_ref().is_none_or(|t| t.n() != n) {
+                self.table = Some(RankTable::new(n));
+            }
+            self.rank = self.table.as_ref().unwrap().rank(p)
+                .expect("parts must be positive and in non-decreasing order");
+        }
+    }
+}
+
+/// A stream of partitions grouped by smallest part, made by
+/// `chunk_by_first_part`.
+///
+/// The empty partition is in a group of its own.
+#[derive(Debug)]
+pub struct ChunkByFirstPart<I> {
+    inner: I,
+    // The smallest part of the current partition, if there is one.
+    key: Option<Option<usize>>,
+    starts: bool,
+}
+
+impl<I: StreamingIterator<Item = [usize]>> ChunkByFirstPart<I> {
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Returns the smallest part shared by the current group, or `None`
+    /// for the empty partition.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator is not on a partition.
+    #[inline]
+    pub fn first_part(&self) -> Option<usize> {
+        self.key.expect("no current partition")
+    }
+
+    /// Returns whether the current partition is the first of its group.
+    #[inline]
+    pub fn starts_chunk(&self) -> bool {
+        self.starts
+    }
+
+    /// Advances to the first partition of the next group, and returns
+    /// it.
+    pub fn next_chunk(&mut self) -> Option<&[usize]> {
+        loop {
+            self.advance();
+            if self.key.is_none() || self.starts {
+                return self.inner.get();
+            }
+        }
+    }
+}
+
+impl<I: StreamingIterator<Item = [usize]>> StreamingIterator for ChunkByFirstPart<I> {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        self.inner.get()
+    }
+
+    #[inline]
+    fn advance(&mut self) {
+        let was = self.key;
+        self.inner.advance();
+        self.key = self.inner.get().map(|p| p.first().cloned());
+        self.starts = self.key.is_some() && self.key != was;
+    }
+}
+
+/// A stream of partitions that can see one ahead, made by `lookahead`.
+///
+/// Each partition is copied out of the inner stream into one of two
+/// buffers, which then swap roles, so the current partition and the
+/// next one are both held at once.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::Partitions;
+/// use integer_partitions::ext::PartitionStreamExt;
+///
+/// // The number of parts changes between consecutive partitions.
+/// let mut pp = Partitions::new(4).lookahead();
+/// let mut deltas = Vec::new();
+/// while let Some((p, Some(q))) = pp.next_pair() {
+///     deltas.push(q.len() as isize - p.len() as isize);
+/// }
+/// assert_eq!(deltas, vec![-1, -1, 0, -1]);
+/// ```
+#[derive(Debug)]
+pub struct Lookahead<I> {
+    inner: I,
+    current: Vec<usize>,
+    ahead: Vec<usize>,
+    state: Ahead,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Ahead {
+    Fresh,
+    /// Both buffers hold partitions.
+    Two,
+    /// Only the current one does, and the inner stream has ended.
+    Last,
+    Done,
+}
+
+impl<I: StreamingIterator<Item = [usize]>> Lookahead<I> {
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Returns the current partition, or `None` if the iterator is not
+    /// on one.
+    #[inline]
+    pub fn current(&self) -> Option<&[usize]> {
+        self.get()
+    }
+
+    /// Returns the partition that the next call to `next` will yield, or
+    /// `None` if there is none.
+    #[inline]
+    pub fn peek_next(&self) -> Option<&[usize]> {
+        match self.state {
+            Ahead::Two => Some(&self.ahead),
+            _ => None,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition together
+    /// with the one after it.
+    #[inline]
+    pub fn next_pair(&mut self) -> Option<(&[usize], Option<&[usize]>)> {
+        self.advance();
+        let this: &Self = self;
+        this.get().map(|p| (p, this.peek_next()))
+    }
+
+    /// Copies the next partition of the inner stream into `ahead`, and
+    /// returns whether there was one.
+    fn pull(&mut self) -> bool {
+        self.inner.advance();
+        match self.inner.get() {
+            Some(p) => {
+                self.ahead.clear();
+                self.ahead.extend_from_slice(p);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<I: StreamingIterator<Item = [usize]>> StreamingIterator for Lookahead<I> {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        match self.state {
+            Ahead::Two | Ahead::Last => Some(&self.current),
+            _ => None,
+        }
+    }
+
+    fn advance(&mut self) {
+        let have = match self.state {
+            Ahead::Fresh => self.pull(),
+            Ahead::Two => true,
+            Ahead::Last | Ahead::Done => false,
+        };
+        if !have {
+            self.state = Ahead::Done;
+            return;
+        }
+        ::core::mem::swap(&mut self.current, &mut self.ahead);
+        self.state = if self.pull() { Ahead::Two } else { Ahead::Last };
+    }
+}
+
+/// An iterator over owned copies of a stream's partitions, made by
+/// `into_iter_owned`.
+#[derive(Debug)]
+pub struct Owned<I> {
+    inner: I,
+    pool: Vec<Vec<usize>>,
+}
+
+impl<I> Owned<I> {
+    /// Hands a vector back to be reused for a later partition.
+    #[inline]
+    pub fn recycle(&mut self, v: Vec<usize>) {
+        self.pool.push(v);
+    }
+
+    /// Destroys the iterator and returns the stream.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+
+impl<I: StreamingIterator<Item = [usize]>> Iterator for Owned<I> {
+    type Item = Vec<usize>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Vec<usize>> {
+        let p = self.inner.next()?;
+        let mut v = self.pool.pop().unwrap_or_default();
+        v.clear();
+        v.extend_from_slice(p);
+        Some(v)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[test]
+fn combinators() {
+    //! Tests each combinator against doing the same by hand.
+
+    use std::collections::BTreeSet;
+    use Partitions;
+
+    for n in 0..20 {
+        let mut all = Vec::new();
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            all.push(p.to_vec());
+        }
+
+        let mut conj = Vec::new();
+        let mut cc = Partitions::new(n).conjugated();
+        let mut seen = BTreeSet::new();
+        for p in &all {
+            conjugate(p, &mut conj);
+            assert_eq!(cc.next(), Some(&conj[..]));
+            seen.insert(conj.clone());
+        }
+        assert_eq!(cc.next(), None);
+        assert_eq!(seen.len(), all.len());
+
+        let mut rr = Partitions::new(n).restrict(|p| {
+            if p.len() % 2 == 1 {
+                Verdict::Skip
+            } else if p.first() > Some(&2) {
+                Verdict::Stop
+            } else {
+                Verdict::Keep
+            }
+        });
+        let kept = all.iter().take_while(|p| p.first() <= Some(&2) || p.len() % 2 == 1);
+        for p in kept.filter(|p| p.len() % 2 == 0) {
+            assert_eq!(rr.next(), Some(&p[..]));
+        }
+        assert_eq!(rr.next(), None);
+        assert_eq!(rr.next(), None);
+
+        // Pass over everything below 1 + 2 with more parts to come,
+        // asking only once.
+        let mut asked = Vec::new();
+        let mut rr = Partitions::new(n).restrict(|p: &[usize]| {
+            asked.push(p.to_vec());
+            if p.len() > 2 && p[..2] == [1, 2] {
+                Verdict::SkipPrefix(2)
+            } else {
+                Verdict::Keep
+            }
+        });
+        let mut kept = Vec::new();
+        while let Some(p) = rr.next() {
+            kept.push(p.to_vec());
+        }
+        drop(rr);
+        let below = |p: &Vec<usize>| p.len() > 2 && p[..2] == [1, 2];
+        assert_eq!(kept, all.iter().filter(|p| !below(p)).cloned().collect::<Vec<_>>());
+        assert_eq!(asked.iter().filter(|p| below(p)).count(), (n >= 5) as usize);
+
+        let mut ss = Partitions::new(n).stat(|p| p.len()).ranked();
+        for (i, p) in all.iter().enumerate() {
+            assert_eq!(ss.next(), Some(&p[..]));
+            assert_eq!(ss.rank(), i);
+        }
+        assert_eq!(ss.next(), None);
+
+        let mut st = Partitions::new(n).stat(|p| p.len());
+        while let Some(p) = st.next() {
+            let len = p.len();
+            assert_eq!(st.value(), Some(&len));
+        }
+        assert_eq!(st.value(), None);
+
+        let mut chunks = Vec::new();
+        let mut ch = Partitions::new(n).chunk_by_first_part();
+        while ch.next_chunk().is_some() {
+            chunks.push(ch.first_part());
+            assert!(ch.starts_chunk());
+        }
+        let mut firsts: Vec<_> = all.iter().map(|p| p.first().cloned()).collect();
+        firsts.dedup();
+        assert_eq!(chunks, firsts);
+
+        let mut la = Partitions::new(n).lookahead();
+        assert_eq!(la.peek_next(), None);
+        for (i, p) in all.iter().enumerate() {
+            assert_eq!(la.next(), Some(&p[..]));
+            assert_eq!(la.current(), Some(&p[..]));
+            assert_eq!(la.peek_next(), all.get(i + 1).map(|q| &q[..]));
+        }
+        assert_eq!(la.next(), None);
+        assert_eq!(la.peek_next(), None);
+        assert_eq!(la.next(), None);
+
+        let mut la = Partitions::new(n).lookahead();
+        for (i, p) in all.iter().enumerate() {
+            assert_eq!(la.next_pair(), Some((&p[..], all.get(i + 1).map(|q| &q[..]))));
+        }
+        assert_eq!(la.next_pair(), None);
+
+        let mut owned = Partitions::new(n).into_iter_owned();
+        for p in &all {
+            let q = owned.next().unwrap();
+            assert_eq!(&q, p);
+            owned.recycle(q);
+        }
+        assert_eq!(owned.next(), None);
+        assert_eq!(Partitions::new(n).into_iter_owned().collect::<Vec<_>>(), all);
+    }
+}
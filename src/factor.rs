@@ -0,0 +1,176 @@
+//! Exact products of fractions, kept as exponents of primes.
+//!
+//! Product formulas such as MacMahon's have integer values but
+//! fractional factors, and their intermediate products can be far
+//! larger than the result. Keeping the exponent of each prime instead
+//! makes every step exact, and only the final value can overflow.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+#[cfg(feature = "num-bigint")]
+use num_bigint::BigUint;
+
+/// A positive rational number, as the exponents of its prime factors.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Product {
+    /// The exponent of each prime `p` that has been seen, which may
+    /// have come back to zero. A dense vector indexed by `p` would cost
+    /// memory in proportion to the largest prime factor.
+    exps: BTreeMap<usize, i64>,
+}
+
+impl Product {
+    /// Makes the empty product, 1.
+    #[inline]
+    pub(crate) fn new() -> Product {
+        Product::default()
+    }
+
+    /// Multiplies by `k`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is zero.
+    #[inline]
+    pub(crate) fn times(&mut self, k: usize) {
+        self.add(k, 1);
+    }
+
+    /// Divides by `k`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is zero.
+    #[inline]
+    pub(crate) fn divide(&mut self, k: usize) {
+        self.add(k, -1);
+    }
+
+    /// Multiplies by `k!`.
+    pub(crate) fn times_factorial(&mut self, k: usize) {
+        for i in 2..(k + 1) {
+            self.times(i);
+        }
+    }
+
+    /// Divides by `k!`.
+    pub(crate) fn divide_factorial(&mut self, k: usize) {
+        for i in 2..(k + 1) {
+            self.divide(i);
+        }
+    }
+
+    /// Multiplies by a number given as its prime factors, raised to the
+    /// power `e`.
+    pub(crate) fn times_factored(&mut self, f: &[(usize, i64)], e: i64) {
+        for &(p, x) in f {
+            self.bump(p, x * e);
+        }
+    }
+
+    fn add(&mut self, k: usize, e: i64) {
+        for (p, x) in factorise(k) {
+            self.bump(p, x * e);
+        }
+    }
+
+    #[inline]
+    fn bump(&mut self, p: usize, e: i64) {
+        *self.exps.entry(p).or_insert(0) += e;
+    }
+
+    /// Returns the value, or `None` if it is not an integer or does not
+    /// fit in a `u128`.
+    pub(crate) fn to_u128(&self) -> Option<u128> {
+        let mut r: u128 = 1;
+        for (&p, &e) in &self.exps {
+            if e < 0 {
+                return None;
+            }
+            for _ in 0..e {
+                r = r.checked_mul(p as u128)?;
+            }
+        }
+        Some(r)
+    }
+
+    /// Splits the value into a numerator and a denominator in lowest
+    /// terms, or returns `None` if either does not fit in a `u128`.
+    pub(crate) fn to_fraction(&self) -> Option<(u128, u128)> {
+        let (mut num, mut den): (u128, u128) = (1, 1);
+        for (&p, &e) in &self.exps {
+            let r = if e > 0 { &mut num } else { &mut den };
+            for _ in 0..e.abs() {
+                *r = r.checked_mul(p as u128)?;
+            }
+        }
+        Some((num, den))
+    }
+
+    /// Splits the value into a numerator and a denominator in lowest
+    /// terms.
+    #[cfg(feature = "num-bigint")]
+    pub(crate) fn to_big_fraction(&self) -> (BigUint, BigUint) {
+        let (mut pos, mut neg) = (Product::new(), Product::new());
+        for (&p, &e) in &self.exps {
+            if e > 0 {
+                pos.bump(p, e);
+            } else if e < 0 {
+                neg.bump(p, -e);
+            }
+        }
+        (pos.to_biguint().unwrap(), neg.to_biguint().unwrap())
+    }
+
+    /// Returns the value, or `None` if it is not an integer.
+    #[cfg(feature = "num-bigint")]
+    pub(crate) fn to_biguint(&self) -> Option<BigUint> {
+        if self.exps.values().any(|&e| e < 0) {
+            return None;
+        }
+        // Gather factors in a word until it would overflow, since
+        // multiplying a big integer by a word is the costly step.
+        let mut r = BigUint::from(1u32);
+        let mut w: u64 = 1;
+        for (&p, &e) in &self.exps {
+            for _ in 0..e {
+                match w.checked_mul(p as u64) {
+                    Some(x) => w = x,
+                    None => {
+                        r *= w;
+                        w = p as u64;
+                    }
+                }
+            }
+        }
+        Some(r * w)
+    }
+}
+
+/// Returns the prime factors of `k` with their exponents, in increasing
+/// order.
+///
+/// # Panics
+///
+/// Panics if `k` is zero.
+pub(crate) fn factorise(mut k: usize) -> Vec<(usize, i64)> {
+    assert!(k > 0, "zero factor");
+    let mut out = Vec::new();
+    let mut p = 2;
+    while p * p <= k {
+        let mut x = 0;
+        while k.is_multiple_of(p) {
+            k /= p;
+            x += 1;
+        }
+        if x > 0 {
+            out.push((p, x));
+        }
+        p += 1;
+    }
+    if k > 1 {
+        out.push((k, 1));
+    }
+    out
+}
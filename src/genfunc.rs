@@ -0,0 +1,168 @@
+//! Generating polynomials of statistics on partitions.
+
+use alloc::vec::Vec;
+use core::ops::AddAssign;
+#[cfg(feature = "std")]
+use std::thread;
+
+#[cfg(feature = "std")]
+use rank::RankTable;
+#[cfg(feature = "std")]
+use streaming_iterator::StreamingIterator;
+use Partitions;
+
+/// A Laurent polynomial in `q` with nonnegative integer coefficients.
+///
+/// Exponents may be negative, so that signed statistics such as the
+/// rank of a partition can be summarised too.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Laurent {
+    /// The exponent of the first coefficient.
+    pub low: isize,
+    /// The coefficients of `q^low`, `q^(low + 1)`, and so on.
+    pub coeffs: Vec<u64>,
+}
+
+impl Laurent {
+    /// Makes the zero polynomial.
+    #[inline]
+    pub fn new() -> Laurent {
+        Laurent::default()
+    }
+
+    /// Returns the coefficient of `q^e`.
+    #[inline]
+    pub fn coeff(&self, e: isize) -> u64 {
+        if e < self.low {
+            return 0;
+        }
+        self.coeffs.get((e - self.low) as usize).cloned().unwrap_or(0)
+    }
+
+    /// Adds `c q^e` to the polynomial.
+    pub fn add_term(&mut self, e: isize, c: u64) {
+        if c == 0 {
+            return;
+        }
+
+        if self.coeffs.is_empty() {
+            self.low = e;
+        } else if e < self.low {
+            let shift = (self.low - e) as usize;
+            self.coeffs.splice(0..0, ::core::iter::repeat_n(0, shift));
+            self.low = e;
+        }
+
+        let i = (e - self.low) as usize;
+        if i >= self.coeffs.len() {
+            self.coeffs.resize(i + 1, 0);
+        }
+        self.coeffs[i] += c;
+    }
+}
+
+impl<'a> AddAssign<&'a Laurent> for Laurent {
+    fn add_assign(&mut self, other: &'a Laurent) {
+        for (i, &c) in other.coeffs.iter().enumerate() {
+            self.add_term(other.low + i as isize, c);
+        }
+    }
+}
+
+/// Sums `q^stat(λ)` over all partitions `λ` of `n`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::genfunc::generating_polynomial;
+///
+/// // Partitions of 5 by number of parts.
+/// let poly = generating_polynomial(5, |p| p.len() as isize);
+/// assert_eq!(poly.low, 1);
+/// assert_eq!(poly.coeffs, vec![1, 2, 2, 1, 1]);
+/// ```
+pub fn generating_polynomial<F>(n: usize, mut stat: F) -> Laurent
+where
+    F: FnMut(&[usize]) -> isize,
+{
+    let mut poly = Laurent::new();
+    let mut pp = Partitions::new(n);
+    while let Some(p) = pp.next() {
+        poly.add_term(stat(p), 1);
+    }
+    poly
+}
+
+/// Sums `q^stat(λ)` over all partitions `λ` of `n`, using `threads`
+/// threads.
+///
+/// The partitions are split into contiguous blocks of equal size, one
+/// per thread, and each thread starts its block by unranking it.
+///
+/// # Panics
+///
+/// Panics if `n` is greater than `count::P_CONST_MAX`, past which the
+/// number of partitions does not fit in a `u64`.
+#[cfg(feature = "std")]
+pub fn generating_polynomial_par<F>(n: usize, threads: usize, stat: F) -> Laurent
+where
+    F: Fn(&[usize]) -> isize + Sync,
+{
+    let threads = threads.max(1) as u128;
+    let table = RankTable::new(n);
+    let total = table.count() as u128;
+    let (table, stat) = (&table, &stat);
+
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..threads).map(|t| {
+            let start = (total * t / threads) as usize;
+            let end = (total * (t + 1) / threads) as usize;
+
+            s.spawn(move || {
+                let mut poly = Laurent::new();
+                if start == end {
+                    return poly;
+                }
+
+                let mut buf = Vec::new();
+                table.unrank(start, &mut buf);
+                let mut pp = Partitions::at(&buf);
+                for i in start..end {
+                    if i > start {
+                        pp.advance();
+                    }
+                    poly.add_term(stat(pp.get().unwrap()), 1);
+                }
+                poly
+            })
+        }).collect();
+
+        let mut poly = Laurent::new();
+        for h in handles {
+            poly += &h.join().unwrap();
+        }
+        poly
+    })
+}
+
+#[test]
+fn parallel() {
+    //! Tests that the parallel fold agrees with the sequential one.
+
+    let rank = |p: &[usize]| *p.last().unwrap_or(&0) as isize - p.len() as isize;
+
+    for n in 0..25 {
+        let seq = generating_polynomial(n, rank);
+        #[cfg(feature = "std")]
+        for &threads in &[1, 2, 7] {
+            assert_eq!(generating_polynomial_par(n, threads, rank), seq);
+        }
+
+        // Conjugation negates the rank.
+        let mut symmetric = true;
+        for e in seq.low..(seq.low + seq.coeffs.len() as isize) {
+            symmetric &= seq.coeff(e) == seq.coeff(-e);
+        }
+        assert!(symmetric);
+    }
+}
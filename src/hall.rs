@@ -0,0 +1,347 @@
+//! Hall polynomials and the subgroups of finite abelian `p`-groups.
+//!
+//! The abelian `p`-group of type `λ` is `Z/p^λ_1 × ⋯ × Z/p^λ_k`. Its
+//! Hall number `g^λ_μν(p)` is the number of subgroups `H` of type `μ`
+//! for which `G/H` has type `ν`. For fixed partitions this is a
+//! polynomial in `p` with integer coefficients, the Hall polynomial, and
+//! the functions here evaluate it at a given `p`. The values only count
+//! subgroups when `p` is prime.
+//!
+//! The computation takes place in the Hall algebra, in which
+//! `u_μ u_ν = Σ g^λ_μν u_λ`. Products with the elementary groups of type
+//! `(1^m)` have a closed form (Macdonald, *Symmetric Functions and Hall
+//! Polynomials*, II (4.6)), and the product of those for the columns of
+//! `μ` is `u_μ` plus multiples of `u_κ` for `κ` below `μ` in the
+//! dominance order, which are peeled off one at a time.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use young::conjugate;
+
+/// Returns the column lengths of `p`, longest first.
+fn columns(p: &[usize]) -> Vec<usize> {
+    let mut c = Vec::new();
+    conjugate(p, &mut c);
+    c.reverse();
+    c
+}
+
+#[inline]
+fn col(c: &[usize], i: usize) -> usize {
+    c.get(i).cloned().unwrap_or(0)
+}
+
+/// Returns whether the diagram with columns `b` contains the one with
+/// columns `a`.
+fn within(a: &[usize], b: &[usize]) -> bool {
+    a.len() <= b.len() && a.iter().zip(b).all(|(x, y)| x <= y)
+}
+
+/// Returns `n(λ) = Σ (i - 1) λ_i` from the columns of `λ`.
+fn n_of(c: &[usize]) -> u64 {
+    c.iter().map(|&x| (x * x.saturating_sub(1) / 2) as u64).sum()
+}
+
+/// The Gaussian binomial coefficient `[n, k]` at `p`.
+fn gaussian(n: usize, k: usize, p: u128) -> Option<u128> {
+    if k > n {
+        return Some(0);
+    }
+    let k = k.min(n - k);
+    // The row of [i, j] for j ≤ k, by [i, j] = [i - 1, j - 1] + p^j [i - 1, j].
+    let mut row = vec![0u128; k + 1];
+    row[0] = 1;
+    for i in 1..(n + 1) {
+        for j in (1..(k.min(i) + 1)).rev() {
+            row[j] = p.checked_pow(j as u32)?.checked_mul(row[j])?.checked_add(row[j - 1])?;
+        }
+    }
+    Some(row[k])
+}
+
+/// Returns `g^λ_ν(1^m)(p)` for the columns `big` of `λ` and `small` of
+/// `ν`, where `λ / ν` is a vertical strip of `m` cells.
+fn elementary(big: &[usize], small: &[usize], m: usize, p: u128) -> Option<u128> {
+    let mut e = n_of(big) as i64 - n_of(small) as i64 - (m * m.saturating_sub(1) / 2) as i64;
+    let mut g: u128 = 1;
+    for i in 0..big.len() {
+        let (n, k) = (big[i] - col(big, i + 1), big[i] - col(small, i));
+        e -= (k * (n - k)) as i64;
+        g = g.checked_mul(gaussian(n, k, p)?)?;
+    }
+    debug_assert!(e >= 0);
+    g.checked_mul(p.checked_pow(u32::try_from(e).ok()?)?)
+}
+
+/// Pushes to `out` the columns of every diagram that is the one with
+/// columns `base` and a vertical strip of `m` more cells, and fits
+/// inside `bound` if there is one.
+fn strips(base: &[usize], m: usize, bound: Option<&[usize]>, out: &mut Vec<Vec<usize>>) {
+    fn go(base: &[usize], i: usize, left: usize, bound: Option<&[usize]>, cur: &mut Vec<usize>, out: &mut Vec<Vec<usize>>) {
+        // No row gets two new cells, so no column grows past the old
+        // length of the one before it.
+        let mut cap = if i == 0 { usize::MAX } else { base[i - 1] };
+        if let Some(b) = bound {
+            cap = cap.min(col(b, i));
+        }
+        if i == base.len() {
+            if left == 0 {
+                out.push(cur.clone());
+            } else if left <= cap {
+                cur.push(left);
+                out.push(cur.clone());
+                cur.pop();
+            }
+            return;
+        }
+        for a in 0..(left + 1) {
+            if base[i] + a > cap {
+                break;
+            }
+            cur.push(base[i] + a);
+            go(base, i + 1, left - a, bound, cur, out);
+            cur.pop();
+        }
+    }
+    go(base, 0, m, bound, &mut Vec::with_capacity(base.len() + 1), out);
+}
+
+/// Multiplies `u_start` by the elementary elements of sizes `sizes`,
+/// keeping only the terms inside `bound`.
+fn spread(start: &[usize], sizes: &[usize], bound: Option<&[usize]>, p: u128) -> Option<BTreeMap<Vec<usize>, i128>> {
+    let mut terms = BTreeMap::new();
+    terms.insert(start.to_vec(), 1i128);
+    let mut next = Vec::new();
+    for &m in sizes {
+        let mut after = BTreeMap::new();
+        for (x, &c) in &terms {
+            next.clear();
+            strips(x, m, bound, &mut next);
+            for y in &next {
+                let g = i128::try_from(elementary(y, x, m, p)?).ok()?;
+                let t = after.entry(y.clone()).or_insert(0i128);
+                *t = c.checked_mul(g)?.checked_add(*t)?;
+            }
+        }
+        terms = after;
+    }
+    Some(terms)
+}
+
+/// Hall numbers `g^λ_κν` for a fixed `λ` and `ν`, by the type `κ`.
+struct Hall {
+    p: u128,
+    lambda: Vec<usize>,
+    nu: Vec<usize>,
+    memo: BTreeMap<Vec<usize>, i128>,
+}
+
+impl Hall {
+    fn get(&mut self, mu: &[usize]) -> Option<i128> {
+        if !within(mu, &self.lambda) {
+            return Some(0);
+        }
+        if let Some(&g) = self.memo.get(mu) {
+            return Some(g);
+        }
+        let direct = spread(&self.nu, mu, Some(&self.lambda), self.p)?;
+        let mut g = direct.get(&self.lambda).cloned().unwrap_or(0);
+        for (kappa, a) in spread(&[], mu, None, self.p)? {
+            if kappa[..] == *mu {
+                debug_assert_eq!(a, 1);
+            } else {
+                g = g.checked_sub(a.checked_mul(self.get(&kappa)?)?)?;
+            }
+        }
+        self.memo.insert(mu.to_vec(), g);
+        Some(g)
+    }
+}
+
+/// Evaluates the Hall polynomial `g^λ_μν` at `p`: the number of
+/// subgroups of type `mu` with quotient of type `nu` in the abelian
+/// `p`-group of type `lambda`, when `p` is prime.
+///
+/// Returns `None` if the value, or a term along the way, does not fit in
+/// a `u128`. The work grows with the number of partitions of the size
+/// of `mu`.
+///
+/// # Panics
+///
+/// Panics if `p` is less than 2.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::hall::hall_number;
+///
+/// // Z/4 × Z/2 has three subgroups of order 2. The quotient by 2G is
+/// // Z/2 × Z/2, and by the other two it is Z/4.
+/// assert_eq!(hall_number(&[1, 2], &[1], &[1, 1], 2), Some(1));
+/// assert_eq!(hall_number(&[1, 2], &[1], &[2], 2), Some(2));
+/// ```
+pub fn hall_number(lambda: &[usize], mu: &[usize], nu: &[usize], p: usize) -> Option<u128> {
+    assert!(p >= 2, "p must be at least 2");
+    let size = |q: &[usize]| q.iter().sum::<usize>();
+    if size(mu) + size(nu) != size(lambda) {
+        return Some(0);
+    }
+    let lambda = columns(lambda);
+    let nu = columns(nu);
+    if !within(&nu, &lambda) {
+        return Some(0);
+    }
+    let mut hall = Hall {
+        p: p as u128,
+        lambda,
+        nu,
+        memo: BTreeMap::new(),
+    };
+    let g = hall.get(&columns(mu))?;
+    debug_assert!(g >= 0);
+    Some(g as u128)
+}
+
+/// Counts the subgroups of type `mu` in the abelian `p`-group of type
+/// `lambda`, when `p` is prime, whatever their quotients.
+///
+/// This is Birkhoff's formula
+/// `∏_i p^(μ'_(i+1) (λ'_i - μ'_i)) [λ'_i - μ'_(i+1), μ'_i - μ'_(i+1)]`
+/// in Gaussian binomial coefficients at `p`, over the columns of the
+/// diagrams. Returns `None` if the count does not fit in a `u128`.
+///
+/// # Panics
+///
+/// Panics if `p` is less than 2.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::hall::count_subgroups;
+///
+/// // The lines through the origin in a plane over the field of 3.
+/// assert_eq!(count_subgroups(&[1, 1], &[1], 3), Some(4));
+/// ```
+pub fn count_subgroups(lambda: &[usize], mu: &[usize], p: usize) -> Option<u128> {
+    assert!(p >= 2, "p must be at least 2");
+    let (lambda, mu) = (columns(lambda), columns(mu));
+    if !within(&mu, &lambda) {
+        return Some(0);
+    }
+    let p = p as u128;
+    let mut count: u128 = 1;
+    for (i, &l) in lambda.iter().enumerate() {
+        let (m, m1) = (col(&mu, i), col(&mu, i + 1));
+        let e = u32::try_from(m1 * (l - m)).ok()?;
+        count = count.checked_mul(p.checked_pow(e)?)?.checked_mul(gaussian(l - m1, m - m1, p)?)?;
+    }
+    Some(count)
+}
+
+#[test]
+fn subgroup_lattice() {
+    //! Tests against the types of all subgroups, and their quotients, of
+    //! small groups.
+
+    use Partitions;
+
+    /// Returns the type of a group from the sizes of its `p^k`-torsion,
+    /// as logarithms to the base `p` for `k` from 1.
+    fn type_of(torsion: &[usize]) -> Vec<usize> {
+        let mut cols: Vec<usize> = torsion.windows(2).map(|w| w[1] - w[0]).filter(|&c| c > 0).collect();
+        if let Some(&t) = torsion.first() {
+            if t > 0 {
+                cols.insert(0, t);
+            }
+        }
+        cols.reverse();
+        let mut parts = Vec::new();
+        conjugate(&cols, &mut parts);
+        parts
+    }
+
+    fn log(mut x: usize, p: usize) -> usize {
+        let mut l = 0;
+        while x > 1 {
+            x /= p;
+            l += 1;
+        }
+        l
+    }
+
+    for &(p, max) in &[(2usize, 5), (3, 3)] {
+        for n in 0..(max + 1) {
+            let mut pp = Partitions::new(n);
+            while let Some(lambda) = pp.next() {
+                // Elements are numbered in mixed radix, subgroups are sets
+                // of elements.
+                let moduli: Vec<usize> = lambda.iter().map(|&x| p.pow(x as u32)).collect();
+                let order: usize = moduli.iter().product();
+                let digits = |mut x: usize| -> Vec<usize> {
+                    moduli.iter().map(|&m| { let d = x % m; x /= m; d }).collect()
+                };
+                let number = |d: &[usize]| d.iter().zip(&moduli).rev().fold(0, |x, (&d, &m)| x * m + d);
+                let add = |x: usize, y: usize| {
+                    let s: Vec<usize> = digits(x).iter().zip(digits(y)).zip(&moduli).map(|((a, b), m)| (a + b) % m).collect();
+                    number(&s)
+                };
+                let times = |k: usize, x: usize| {
+                    let s: Vec<usize> = digits(x).iter().zip(&moduli).map(|(a, m)| a * k % m).collect();
+                    number(&s)
+                };
+
+                let mut subgroups = vec![1u128];
+                let mut i = 0;
+                while i < subgroups.len() {
+                    let h = subgroups[i];
+                    for g in 0..order {
+                        let mut join = h;
+                        let mut c = g;
+                        loop {
+                            for x in (0..order).filter(|&x| h >> x & 1 == 1) {
+                                join |= 1 << add(x, c);
+                            }
+                            c = add(c, g);
+                            if c == g {
+                                break;
+                            }
+                        }
+                        if !subgroups.contains(&join) {
+                            subgroups.push(join);
+                        }
+                    }
+                    i += 1;
+                }
+
+                let mut counts = BTreeMap::new();
+                for &h in &subgroups {
+                    let size = h.count_ones() as usize;
+                    let inner: Vec<usize> = (1..(n + 1))
+                        .map(|k| log((0..order).filter(|&x| h >> x & 1 == 1 && times(p.pow(k as u32), x) == 0).count(), p))
+                        .collect();
+                    let outer: Vec<usize> = (1..(n + 1))
+                        .map(|k| log((0..order).filter(|&x| h >> times(p.pow(k as u32), x) & 1 == 1).count() / size, p))
+                        .collect();
+                    *counts.entry((type_of(&inner), type_of(&outer))).or_insert(0u128) += 1;
+                }
+
+                for m in 0..(n + 1) {
+                    let mut mus = Partitions::new(m);
+                    while let Some(mu) = mus.next() {
+                        let mut total = 0;
+                        let mut nus = Partitions::new(n - m);
+                        while let Some(nu) = nus.next() {
+                            let g = hall_number(lambda, mu, nu, p).unwrap();
+                            assert_eq!(g, counts.get(&(mu.to_vec(), nu.to_vec())).cloned().unwrap_or(0), "{:?} {:?} {:?}", lambda, mu, nu);
+                            assert_eq!(g, hall_number(lambda, nu, mu, p).unwrap());
+                            total += g;
+                        }
+                        assert_eq!(Some(total), count_subgroups(lambda, mu, p));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! Checking partition identities by enumeration.
+//!
+//! A partition identity says that two families of partitions have the
+//! same number of members of each size, and often that some statistic
+//! is carried from one family to the other as well. The functions here
+//! test such a claim for every size up to a bound and report where it
+//! first fails.
+//!
+//! Each family is given as a function from `n` to a streaming iterator
+//! over its partitions of `n`, such as `Partitions::new` followed by
+//! `filter`, or any of the restricted iterators of the crate.
+
+use core::error::Error;
+use core::fmt;
+
+use genfunc::Laurent;
+use streaming_iterator::StreamingIterator;
+
+/// The first place where two families disagree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Discrepancy {
+    /// The number being partitioned.
+    pub n: usize,
+    /// The value of the statistic, when one was compared.
+    pub stat: Option<isize>,
+    /// The number of partitions in the first family.
+    pub left: u64,
+    /// The number of partitions in the second family.
+    pub right: u64,
+}
+
+impl fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.stat {
+            Some(s) => write!(
+                f,
+                "{} against {} partitions of {} with statistic {}",
+                self.left, self.right, self.n, s,
+            ),
+            None => write!(f, "{} against {} partitions of {}", self.left, self.right, self.n),
+        }
+    }
+}
+
+impl Error for Discrepancy {}
+
+/// Checks that two families have the same number of partitions of each
+/// `n` up to and including `max`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate streaming_iterator;
+/// # extern crate integer_partitions;
+/// use integer_partitions::Partitions;
+/// use integer_partitions::identity::{equinumerous, Discrepancy};
+/// use streaming_iterator::StreamingIterator;
+///
+/// # fn main() {
+/// // Euler: as many partitions into distinct parts as into odd parts.
+/// let distinct = |n| Partitions::new(n).filter(|p: &[usize]| p.windows(2).all(|w| w[0] < w[1]));
+/// let odd = |n| Partitions::new(n).filter(|p: &[usize]| p.iter().all(|x| x % 2 == 1));
+/// assert_eq!(equinumerous(30, distinct, odd), Ok(()));
+///
+/// // But not as many as into parts of 1 or 4 modulo 5.
+/// let mod5 = |n| Partitions::new(n).filter(|p: &[usize]| p.iter().all(|x| x % 5 == 1 || x % 5 == 4));
+/// assert_eq!(
+///     equinumerous(30, distinct, mod5),
+///     Err(Discrepancy { n: 3, stat: None, left: 2, right: 1 }),
+/// );
+/// # }
+/// ```
+pub fn equinumerous<F, G, I, J>(max: usize, mut left: F, mut right: G) -> Result<(), Discrepancy>
+where
+    F: FnMut(usize) -> I,
+    G: FnMut(usize) -> J,
+    I: StreamingIterator<Item = [usize]>,
+    J: StreamingIterator<Item = [usize]>,
+{
+    for n in 0..(max + 1) {
+        let (l, r) = (left(n).count() as u64, right(n).count() as u64);
+        if l != r {
+            return Err(Discrepancy { n, stat: None, left: l, right: r });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that two families have the same number of partitions of each
+/// `n` up to and including `max` with each value of a statistic, which
+/// may be computed differently on either side.
+///
+/// When the families disagree, the discrepancy is reported for the
+/// smallest value of the statistic at the smallest `n`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate streaming_iterator;
+/// # extern crate integer_partitions;
+/// use integer_partitions::Partitions;
+/// use integer_partitions::identity::equidistributed;
+///
+/// # fn main() {
+/// // Conjugation swaps the number of parts with the largest part.
+/// let parts = |p: &[usize]| p.len() as isize;
+/// let largest = |p: &[usize]| p.last().map_or(0, |&x| x as isize);
+/// assert_eq!(equidistributed(30, Partitions::new, Partitions::new, parts, largest), Ok(()));
+/// # }
+/// ```
+pub fn equidistributed<F, G, I, J, S, T>(
+    max: usize,
+    mut left: F,
+    mut right: G,
+    mut left_stat: S,
+    mut right_stat: T,
+) -> Result<(), Discrepancy>
+where
+    F: FnMut(usize) -> I,
+    G: FnMut(usize) -> J,
+    I: StreamingIterator<Item = [usize]>,
+    J: StreamingIterator<Item = [usize]>,
+    S: FnMut(&[usize]) -> isize,
+    T: FnMut(&[usize]) -> isize,
+{
+    fn tally<I, S>(mut it: I, stat: &mut S) -> Laurent
+    where
+        I: StreamingIterator<Item = [usize]>,
+        S: FnMut(&[usize]) -> isize,
+    {
+        let mut poly = Laurent::new();
+        while let Some(p) = it.next() {
+            poly.add_term(stat(p), 1);
+        }
+        poly
+    }
+
+    for n in 0..(max + 1) {
+        let l = tally(left(n), &mut left_stat);
+        let r = tally(right(n), &mut right_stat);
+        if l == r {
+            continue;
+        }
+
+        let end = |p: &Laurent| p.low + p.coeffs.len() as isize;
+        let lo = match (l.coeffs.is_empty(), r.coeffs.is_empty()) {
+            (false, false) => l.low.min(r.low),
+            (false, true) => l.low,
+            _ => r.low,
+        };
+        let stat = (lo..end(&l).max(end(&r)))
+            .find(|&s| l.coeff(s) != r.coeff(s))
+            .unwrap();
+        return Err(Discrepancy { n, stat: Some(stat), left: l.coeff(stat), right: r.coeff(stat) });
+    }
+    Ok(())
+}
+
+#[test]
+fn classical_identities() {
+    //! Tests Euler's and the first Rogers–Ramanujan identity, and some
+    //! false ones.
+
+    use Partitions;
+
+    let distinct = |n| Partitions::new(n).filter(|p: &[usize]| p.windows(2).all(|w| w[0] < w[1]));
+    let odd = |n| Partitions::new(n).filter(|p: &[usize]| p.iter().all(|x| x % 2 == 1));
+    let gaps = |n| Partitions::new(n).filter(|p: &[usize]| p.windows(2).all(|w| w[0] + 2 <= w[1]));
+    let mod5 = |n| Partitions::new(n).filter(|p: &[usize]| p.iter().all(|x| x % 5 == 1 || x % 5 == 4));
+
+    assert_eq!(equinumerous(40, distinct, odd), Ok(()));
+    assert_eq!(equinumerous(40, gaps, mod5), Ok(()));
+    assert_eq!(equinumerous(40, gaps, odd), Err(Discrepancy { n: 3, stat: None, left: 1, right: 2 }));
+
+    // The number of parts is not carried by Euler's identity: 2 has one
+    // part, against the two of 1 + 1.
+    let parts = |p: &[usize]| p.len() as isize;
+    assert_eq!(
+        equidistributed(40, distinct, odd, parts, parts),
+        Err(Discrepancy { n: 2, stat: Some(1), left: 1, right: 0 }),
+    );
+    let largest = |p: &[usize]| p.last().map_or(0, |&x| x as isize);
+    assert_eq!(equidistributed(30, Partitions::new, Partitions::new, parts, largest), Ok(()));
+
+    let err = Discrepancy { n: 3, stat: Some(2), left: 1, right: 0 };
+    assert_eq!(err.to_string(), "1 against 0 partitions of 3 with statistic 2");
+}
@@ -0,0 +1,52 @@
+//! Unsigned integer types usable as parts.
+
+use core::convert::TryFrom;
+use core::fmt::Debug;
+use core::ops::{Add, Div, Mul, Rem, Sub};
+
+/// An unsigned primitive integer type that parts can be stored in.
+///
+/// This is implemented for `u8`, `u16`, `u32`, `u64`, `u128` and
+/// `usize`.
+pub trait Int:
+    Copy
+    + Ord
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    /// The value `0`.
+    const ZERO: Self;
+    /// The value `1`.
+    const ONE: Self;
+
+    /// Converts from a `usize`, if the value fits.
+    fn from_usize(n: usize) -> Option<Self>;
+
+    /// Converts to a `usize`, if the value fits.
+    fn to_usize(self) -> Option<usize>;
+}
+
+macro_rules! impl_int {
+    ($($t:ty)*) => {$(
+        impl Int for $t {
+            const ZERO: $t = 0;
+            const ONE: $t = 1;
+
+            #[inline]
+            fn from_usize(n: usize) -> Option<$t> {
+                <$t>::try_from(n).ok()
+            }
+
+            #[inline]
+            fn to_usize(self) -> Option<usize> {
+                usize::try_from(self).ok()
+            }
+        }
+    )*}
+}
+
+impl_int!(u8 u16 u32 u64 u128 usize);
@@ -0,0 +1,381 @@
+//! Streaming partitions to other processes through shared memory.
+//!
+//! A `RingWriter` fills fixed-size pages with partitions in the format of
+//! `codec`, and publishes them into a ring of pages in a region of memory
+//! that another process can map too, such as a POSIX shared memory
+//! object or a memory-mapped file. The region is seen as a slice of
+//! `AtomicU64`, so any mapping of suitable size and alignment can be
+//! turned into one with `slice::from_raw_parts`, and the reader on the
+//! other side only needs 64-bit atomic loads and stores. A `RingReader`
+//! for the other end is included, for Rust consumers and for testing.
+//!
+//! # Layout
+//!
+//! The region is a sequence of little-endian 64-bit words, aligned to 8
+//! bytes. The first 8 words are a header:
+//!
+//! | word | contents                                                    |
+//! |------|-------------------------------------------------------------|
+//! | 0    | `MAGIC`, the bytes `IPARTRNG`                               |
+//! | 1    | `VERSION`, currently 1                                      |
+//! | 2    | the size of each page in bytes, a multiple of 8             |
+//! | 3    | the number of pages in the ring                             |
+//! | 4    | the number of pages written so far, set by the writer       |
+//! | 5    | the number of pages read so far, set by the reader          |
+//! | 6    | 1 once the writer has finished, 0 before                    |
+//! | 7    | reserved, 0                                                 |
+//!
+//! Page `i` starts at word `8 + i * page_size / 8`, and the `k`th page
+//! written, counting from zero, goes to page `k % pages`. Each page
+//! begins with two little-endian 32-bit numbers, the count of partitions
+//! on it and the number of bytes they take, and the encoded partitions
+//! follow one after another.
+//!
+//! The writer fills a page while `written - read < pages`, then stores
+//! `written + 1` with release ordering. The reader waits for `written`
+//! to pass `read`, with acquire ordering, copies the page out, and then
+//! stores `read + 1` with release ordering to hand the page back. When
+//! `finished` is set, with release ordering after the last page, every
+//! page has been written. There is one writer and one reader.
+//!
+//! Both ends wait by spinning and yielding, so a writer whose reader has
+//! gone away waits forever once the ring is full.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::atomic::AtomicU64;
+//! use std::thread;
+//! use integer_partitions::Partitions;
+//! use integer_partitions::codec::decode;
+//! use integer_partitions::ipc::{RingReader, RingWriter};
+//!
+//! let region: Vec<AtomicU64> = (0..1024).map(|_| AtomicU64::new(0)).collect();
+//! let mut writer = RingWriter::new(&region, 256).unwrap();
+//!
+//! thread::scope(|s| {
+//!     s.spawn(|| {
+//!         writer.extend(&mut Partitions::new(20)).unwrap();
+//!         writer.finish();
+//!     });
+//!
+//!     let mut reader = RingReader::attach(&region).unwrap();
+//!     let (mut page, mut p) = (Vec::new(), Vec::new());
+//!     let mut count = 0;
+//!     while let Some(k) = reader.read_page(&mut page) {
+//!         let mut pos = 0;
+//!         for _ in 0..k {
+//!             pos += decode(&page[pos..], &mut p).unwrap();
+//!             assert_eq!(p.iter().sum::<usize>(), 20);
+//!             count += 1;
+//!         }
+//!     }
+//!     assert_eq!(count, 627);
+//! });
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+
+use codec::{encode, encoded_len};
+use streaming_iterator::StreamingIterator;
+
+/// The first word of a ring, the bytes `IPARTRNG` read little-endian.
+pub const MAGIC: u64 = 0x474e_5254_5241_5049;
+
+/// The version of the layout.
+pub const VERSION: u64 = 1;
+
+const HEADER: usize = 8;
+const PAGE_SIZE: usize = 2;
+const PAGES: usize = 3;
+const WRITTEN: usize = 4;
+const READ: usize = 5;
+const FINISHED: usize = 6;
+
+/// The ways that setting up or writing to a ring can fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RingError {
+    /// The page size is not a multiple of 8, or too small to hold the
+    /// page header and a partition.
+    BadPageSize,
+    /// The region has no room for a single page after the header.
+    RegionTooSmall,
+    /// The region does not start with `MAGIC` and `VERSION`.
+    NotARing,
+    /// A partition does not fit on a page.
+    PartitionTooLarge,
+}
+
+impl fmt::Display for RingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            RingError::BadPageSize => "page size is not a multiple of 8 of at least 16",
+            RingError::RegionTooSmall => "region has no room for a page",
+            RingError::NotARing => "region does not hold a ring of this version",
+            RingError::PartitionTooLarge => "partition does not fit on a page",
+        })
+    }
+}
+
+impl Error for RingError {}
+
+/// Writes partitions into a ring of pages in shared memory.
+#[derive(Debug)]
+pub struct RingWriter<'a> {
+    region: &'a [AtomicU64],
+    page_words: usize,
+    pages: u64,
+    written: u64,
+    /// The page being filled, with room for its header at the start.
+    page: Vec<u8>,
+    count: u32,
+}
+
+impl<'a> RingWriter<'a> {
+    /// Sets up a ring in `region`, with pages of `page_size` bytes and as
+    /// many of them as fit, and makes a writer for it.
+    ///
+    /// The header is written last, so a reader that attaches while this
+    /// runs may see a stale one. The reader should not be started until
+    /// this has returned.
+    pub fn new(region: &'a [AtomicU64], page_size: usize) -> Result<RingWriter<'a>, RingError> {
+        if page_size < 16 || !page_size.is_multiple_of(8) {
+            return Err(RingError::BadPageSize);
+        }
+        let page_words = page_size / 8;
+        let pages = region.len().saturating_sub(HEADER) / page_words;
+        if pages == 0 {
+            return Err(RingError::RegionTooSmall);
+        }
+
+        for w in &region[1..HEADER] {
+            w.store(0, Ordering::Relaxed);
+        }
+        region[PAGE_SIZE].store(page_size as u64, Ordering::Relaxed);
+        region[PAGES].store(pages as u64, Ordering::Relaxed);
+        region[1].store(VERSION, Ordering::Relaxed);
+        region[0].store(MAGIC, Ordering::Release);
+
+        let mut page = Vec::with_capacity(page_size);
+        page.resize(8, 0);
+        Ok(RingWriter {
+            region,
+            page_words,
+            pages: pages as u64,
+            written: 0,
+            page,
+            count: 0,
+        })
+    }
+
+    /// Returns the number of pages published so far.
+    #[inline]
+    pub fn pages_written(&self) -> u64 {
+        self.written
+    }
+
+    /// Adds `p` to the current page, first publishing the page if `p`
+    /// does not fit, which waits for the reader if the ring is full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not in non-decreasing order, or has a part of
+    /// zero.
+    pub fn push(&mut self, p: &[usize]) -> Result<(), RingError> {
+        let len = encoded_len(p);
+        if 8 + len > self.page_words * 8 {
+            return Err(RingError::PartitionTooLarge);
+        }
+        if self.page.len() + len > self.page_words * 8 {
+            self.flush();
+        }
+        encode(p, &mut self.page);
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Pushes every remaining partition of `parts`.
+    ///
+    /// On an error the partition that did not fit is the current one of
+    /// `parts`, and all those before it have been pushed.
+    pub fn extend<I>(&mut self, parts: &mut I) -> Result<(), RingError>
+    where
+        I: StreamingIterator<Item = [usize]>,
+    {
+        while let Some(p) = parts.next() {
+            self.push(p)?;
+        }
+        Ok(())
+    }
+
+    /// Publishes the current page, if it holds any partitions, waiting
+    /// for the reader to free one if the ring is full.
+    pub fn flush(&mut self) {
+        if self.count == 0 {
+            return;
+        }
+        let read = &self.region[READ];
+        while self.written - read.load(Ordering::Acquire) >= self.pages {
+            thread::yield_now();
+        }
+
+        let used = (self.page.len() - 8) as u32;
+        self.page[..4].copy_from_slice(&self.count.to_le_bytes());
+        self.page[4..8].copy_from_slice(&used.to_le_bytes());
+        self.page.resize(self.page.len().next_multiple_of(8), 0);
+
+        let start = HEADER + (self.written % self.pages) as usize * self.page_words;
+        for (w, chunk) in self.region[start..].iter().zip(self.page.chunks(8)) {
+            let mut b = [0; 8];
+            b.copy_from_slice(chunk);
+            w.store(u64::from_le_bytes(b), Ordering::Relaxed);
+        }
+        self.written += 1;
+        self.region[WRITTEN].store(self.written, Ordering::Release);
+
+        self.page.truncate(8);
+        self.count = 0;
+    }
+
+    /// Publishes the current page and marks the ring as finished.
+    pub fn finish(&mut self) {
+        self.flush();
+        self.region[FINISHED].store(1, Ordering::Release);
+    }
+}
+
+/// Reads pages from a ring set up by a `RingWriter`.
+#[derive(Debug)]
+pub struct RingReader<'a> {
+    region: &'a [AtomicU64],
+    page_words: usize,
+    pages: u64,
+    read: u64,
+}
+
+impl<'a> RingReader<'a> {
+    /// Makes a reader for the ring in `region`, checking its header.
+    pub fn attach(region: &'a [AtomicU64]) -> Result<RingReader<'a>, RingError> {
+        if region.len() < HEADER
+            || region[0].load(Ordering::Acquire) != MAGIC
+            || region[1].load(Ordering::Relaxed) != VERSION
+        {
+            return Err(RingError::NotARing);
+        }
+        let page_size = region[PAGE_SIZE].load(Ordering::Relaxed) as usize;
+        let pages = region[PAGES].load(Ordering::Relaxed);
+        if page_size < 16 || !page_size.is_multiple_of(8) {
+            return Err(RingError::BadPageSize);
+        }
+        let page_words = page_size / 8;
+        if pages == 0 || (region.len() - HEADER) / page_words < pages as usize {
+            return Err(RingError::RegionTooSmall);
+        }
+        Ok(RingReader {
+            region,
+            page_words,
+            pages,
+            read: region[READ].load(Ordering::Relaxed),
+        })
+    }
+
+    /// Waits for the next page and copies its encoded partitions into
+    /// `out`, which is cleared first, returning how many there are.
+    /// Returns `None` once the writer has finished and every page has
+    /// been read.
+    pub fn read_page(&mut self, out: &mut Vec<u8>) -> Option<usize> {
+        loop {
+            // The writer sets finished after its last page, so once it
+            // is seen the count of pages written is final.
+            let finished = self.region[FINISHED].load(Ordering::Acquire) == 1;
+            if self.region[WRITTEN].load(Ordering::Acquire) > self.read {
+                break;
+            }
+            if finished {
+                return None;
+            }
+            thread::yield_now();
+        }
+
+        let start = HEADER + (self.read % self.pages) as usize * self.page_words;
+        let page = &self.region[start..(start + self.page_words)];
+        let head = page[0].load(Ordering::Relaxed).to_le_bytes();
+        let mut count = [0; 4];
+        let mut used = [0; 4];
+        count.copy_from_slice(&head[..4]);
+        used.copy_from_slice(&head[4..]);
+        let used = u32::from_le_bytes(used) as usize;
+
+        out.clear();
+        for w in &page[1..] {
+            if out.len() >= used {
+                break;
+            }
+            out.extend_from_slice(&w.load(Ordering::Relaxed).to_le_bytes());
+        }
+        out.truncate(used);
+
+        self.read += 1;
+        self.region[READ].store(self.read, Ordering::Release);
+        Some(u32::from_le_bytes(count) as usize)
+    }
+}
+
+#[test]
+fn ring() {
+    //! Tests streaming partitions through a small ring, so that the
+    //! writer keeps waiting on the reader, and the errors.
+
+    use codec::decode;
+    use Partitions;
+
+    let region: Vec<AtomicU64> = (0..(HEADER + 3 * 4)).map(|_| AtomicU64::new(0)).collect();
+    assert_eq!(RingReader::attach(&region).unwrap_err(), RingError::NotARing);
+    assert_eq!(RingWriter::new(&region, 12).unwrap_err(), RingError::BadPageSize);
+    assert_eq!(RingWriter::new(&region[..11], 32).unwrap_err(), RingError::RegionTooSmall);
+
+    let mut writer = RingWriter::new(&region, 32).unwrap();
+    assert_eq!(writer.push(&(1..40).collect::<Vec<_>>()), Err(RingError::PartitionTooLarge));
+
+    let mut pages = 0;
+    thread::scope(|s| {
+        s.spawn(|| {
+            for n in 0..18 {
+                writer.extend(&mut Partitions::new(n)).unwrap();
+            }
+            writer.finish();
+            pages = writer.pages_written();
+        });
+
+        let mut reader = RingReader::attach(&region).unwrap();
+        let (mut page, mut p) = (Vec::new(), Vec::new());
+        let mut n = 0;
+        let mut pp = Partitions::new(0);
+        let mut read = 0;
+        while let Some(k) = reader.read_page(&mut page) {
+            assert!(k > 0 && page.len() <= 24);
+            let mut pos = 0;
+            for _ in 0..k {
+                pos += decode(&page[pos..], &mut p).unwrap();
+                let q = match pp.next() {
+                    Some(q) => q,
+                    None => {
+                        n += 1;
+                        pp = Partitions::new(n);
+                        pp.next().unwrap()
+                    }
+                };
+                assert_eq!(&p[..], q);
+            }
+            assert_eq!(pos, page.len());
+            read += 1;
+        }
+        assert_eq!((n, pp.next()), (17, None));
+        assert_eq!(reader.read_page(&mut page), None);
+        assert!(read > 3);
+    });
+    assert_eq!(region[WRITTEN].load(Ordering::Relaxed), pages);
+}
@@ -14,49 +14,430 @@
 //!     println!("{:?}", p)
 //! }
 //! ```
+//!
+//! # Features
+//!
+//! - `std`, on by default, adds what needs threads, floating point
+//!   functions, hash maps or the clock: `drive`, `ipc`, `profile`,
+//!   `sample`, the parallel functions of `aggregate` and `genfunc`, and
+//!   the floating point ones of `plancherel`. Without it
+//!   the crate is `no_std` and only needs `alloc`, and the table behind
+//!   `Partitions::rank` is built afresh for each call instead of kept.
+//! - `rand` adds the random samplers of `sample`, with `std`.
+//! - `num-bigint` adds exact counts that do not fit in a `u128`.
+//! - `validate` checks every partition that `Partitions`, the
+//!   iterators of `distinct`, `odd`, `restricted`, `sparse` and
+//!   `supply`, and the functions of `successor` produce against their
+//!   documented contract, and panics with a description of the first
+//!   fault. It is slow, and meant for running tests under.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+#[cfg_attr(not(any(feature = "std", test)), macro_use)]
+extern crate alloc;
+#[cfg(any(feature = "std", test))]
+extern crate core;
 pub extern crate streaming_iterator;
+#[cfg(feature = "num-bigint")]
+pub extern crate num_bigint;
+#[cfg(feature = "rand")]
+extern crate rand;
+use alloc::vec::Vec;
+#[cfg(any(feature = "std", test))]
+use core::cell::RefCell;
+
+use int::Int;
+use rank::RankTable;
 use streaming_iterator::StreamingIterator;
 
+pub mod abelian;
+pub mod aggregate;
+pub mod codec;
+pub mod compositions;
+pub mod conjugacy;
+pub mod count;
+pub mod cursor;
+pub mod distinct;
+#[cfg(feature = "std")]
+pub mod drive;
+pub mod ext;
+mod factor;
+pub mod genfunc;
+pub mod hall;
+pub mod identity;
+pub mod int;
+#[cfg(feature = "std")]
+pub mod ipc;
+pub mod loopless;
+pub mod map;
+pub mod odd;
+pub mod optimize;
+pub mod partition;
+pub mod packed;
+pub mod plancherel;
+pub mod plane;
+pub mod poset;
+#[cfg(feature = "std")]
+pub mod profile;
+pub mod semigroup;
+pub mod series;
+pub mod set_partitions;
+pub mod shifted;
+mod rank;
+pub mod restricted;
+#[cfg(all(feature = "rand", feature = "std"))]
+pub mod sample;
+pub mod sparse;
+pub mod successor;
+pub mod supply;
+pub mod tableau;
+#[cfg(feature = "validate")]
+mod validate;
+pub mod young;
+
 /// Iterates over the partitions of a given nonnegative integer.
+///
+/// Parts are stored as `usize` unless another `Int` type is chosen, which
+/// only needs to hold `n` itself: `Partitions::<u8>::of(n)` keeps the
+/// parts of a partition of `n ≤ 255` in an eighth of the memory. The
+/// constructors that rank or skip, and the other iterators of the crate,
+/// only work with `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::Partitions;
+///
+/// let mut pp = Partitions::<u8>::of(4);
+/// assert_eq!(pp.next(), Some(&[1u8, 1, 1, 1][..]));
+/// assert_eq!(pp.next(), Some(&[1u8, 1, 2][..]));
+/// ```
 #[derive(Debug)]
-pub struct Partitions {
-    a: Vec<usize>,
+pub struct Partitions<T = usize> {
+    n: T,
+    a: Vec<T>,
     k: usize,
-    y: usize,
-    next: State,
+    y: T,
+    next: State<T>,
+    desc: Option<Desc>,
+    #[cfg(feature = "validate")]
+    check: validate::Validator<T>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
-enum State {
+enum State<T> {
     A,
-    B { x: usize, l: usize },
+    B { x: T, l: usize },
+}
+
+/// Where the reverse lexicographic order has got to: `m` parts, of
+/// which the first `h` are greater than one.
+#[derive(Debug, PartialEq, Eq)]
+enum Desc {
+    Fresh,
+    Going { m: usize, h: usize },
+    Done,
+}
+
+/// The orders that `Partitions` can list partitions in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Order {
+    /// Parts in non-decreasing order, in lexicographic order, from all
+    /// ones to `n` alone. This is Kelleher's order, and the one used
+    /// throughout the crate.
+    Lex,
+    /// Parts in non-increasing order, in reverse lexicographic order,
+    /// from `n` alone to all ones, as in SageMath and TAOCP 7.2.1.4.
+    RevLex,
+}
+
+/// Iterates over the partitions of `n` into exactly `k` parts, in the
+/// order of `Partitions`.
+///
+/// These are made directly by Hindenburg's algorithm, which changes the
+/// two largest parts in most steps, so none are filtered out however
+/// small `k` is. This is `sparse::Exact` with parts stored as `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::PartitionsExact;
+///
+/// let mut pp = PartitionsExact::new(7, 3);
+/// assert_eq!(pp.next(), Some(&[1, 1, 5][..]));
+/// assert_eq!(pp.next(), Some(&[1, 2, 4][..]));
+/// assert_eq!(pp.next(), Some(&[1, 3, 3][..]));
+/// assert_eq!(pp.next(), Some(&[2, 2, 3][..]));
+/// assert_eq!(pp.next(), None);
+///
+/// pp.reinit(5, 5);
+/// assert_eq!(pp.next(), Some(&[1, 1, 1, 1, 1][..]));
+/// assert_eq!(pp.next(), None);
+/// ```
+pub type PartitionsExact = sparse::Exact<usize>;
+
+/// Where a `Partitions` has got to, saved by `Partitions::save_state`.
+///
+/// This is plain data, so it can be written out in any format and read
+/// back to carry on a long enumeration later, with `Partitions::resume`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PartitionsState {
+    /// Not started: the next partition is the first of `n` whose parts
+    /// are all at least `min_part`, which is one in `Order::RevLex`.
+    Start { n: usize, min_part: usize, order: Order },
+    /// On `current`, with its parts in the order's own direction, and
+    /// going on to the partitions after it.
+    At { current: Vec<usize>, order: Order },
+    /// Exhausted.
+    Done { n: usize, order: Order },
+}
+
+/// What the partition iterators of the crate have in common, so that code
+/// can be written once for any of them.
+///
+/// Each keeps its own options, such as the number of parts or the order,
+/// through `reinit`, which only changes the number being partitioned.
+///
+/// It is implemented by every iterator over the partitions of one number
+/// `n` listed as slices of parts: `Partitions`, those of `distinct`,
+/// `odd`, `restricted` and `supply`, `sparse::Exact` (which is
+/// `PartitionsExact`), `sparse::AtMost` and `sparse::Distinct` over
+/// `usize`, `successor::Constrained`, `tableau::Dimensions`, and
+/// `young::UpToConjugation` and `young::DurfeePartitions`. It is not
+/// implemented by `poset::Dominated`, which yields the partitions below
+/// a given one and so has no `n` to change alone, nor by iterators whose
+/// items are something else, such as the multiplicity form of
+/// `loopless::LooplessPartitions` or the permutations of
+/// `conjugacy::ConjugacyClasses`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::{Partitions, PartitionIterator, PartitionsExact};
+/// use integer_partitions::odd::OddParts;
+///
+/// fn counts<I: PartitionIterator>(mut pp: I, top: usize) -> Vec<usize> {
+///     (0..(top + 1)).map(|n| {
+///         pp.reinit(n);
+///         let mut c = 0;
+///         while pp.next().is_some() {
+///             c += 1;
+///         }
+///         c
+///     }).collect()
+/// }
+///
+/// assert_eq!(counts(Partitions::new(0), 6), [1, 1, 2, 3, 5, 7, 11]);
+/// assert_eq!(counts(OddParts::new(0), 6), [1, 1, 1, 2, 2, 3, 4]);
+/// assert_eq!(counts(PartitionsExact::new(0, 2), 6), [0, 0, 1, 1, 2, 2, 3]);
+/// ```
+pub trait PartitionIterator: StreamingIterator<Item = [usize]> {
+    /// Returns the number being partitioned.
+    fn n(&self) -> usize;
+
+    /// Restarts the iterator at the first partition of `n`, reusing its
+    /// vector.
+    fn reinit(&mut self, n: usize);
+
+    /// Destroys the iterator and returns a vector for further use.
+    fn end(self) -> Vec<usize>
+    where
+        Self: Sized;
 }
 
 impl Partitions {
     /// Makes a new iterator.
     #[inline]
     pub fn new(n: usize) -> Partitions {
+        Partitions::with_min_part(n, 1)
+    }
+
+    /// Makes a new iterator over the partitions of `n` whose parts are
+    /// all at least `m`, in the same order as `new`.
+    ///
+    /// These are the ascending compositions of `n` with first part at
+    /// least `m`, and the recursion behind Kelleher's algorithm is
+    /// really over them: `new` is the case `m = 1`. A part of `m = 0`
+    /// is treated like `m = 1`, and the empty partition of zero is
+    /// always included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use integer_partitions::Partitions;
+    ///
+    /// let mut pp = Partitions::with_min_part(8, 3);
+    /// assert_eq!(pp.next(), Some(&[3, 5][..]));
+    /// assert_eq!(pp.next(), Some(&[4, 4][..]));
+    /// assert_eq!(pp.next(), Some(&[8][..]));
+    /// assert_eq!(pp.next(), None);
+    /// ```
+    #[inline]
+    pub fn with_min_part(n: usize, m: usize) -> Partitions {
+        let mut p = Partitions::empty(n, Vec::new());
+        p.reinit_with_min_part(n, m);
+        p
+    }
+
+    /// Makes a new iterator over the partitions of `n` in the given
+    /// order.
+    ///
+    /// In `Order::RevLex` each partition has its parts in non-increasing
+    /// order, unlike everywhere else in the crate, and they are made
+    /// directly by the algorithm of Zoghbi and Stojmenović in constant
+    /// amortized time each.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use integer_partitions::{Order, Partitions};
+    ///
+    /// let mut pp = Partitions::with_order(4, Order::RevLex);
+    /// assert_eq!(pp.next(), Some(&[4][..]));
+    /// assert_eq!(pp.next(), Some(&[3, 1][..]));
+    /// assert_eq!(pp.next(), Some(&[2, 2][..]));
+    /// assert_eq!(pp.next(), Some(&[2, 1, 1][..]));
+    /// assert_eq!(pp.next(), Some(&[1, 1, 1, 1][..]));
+    /// assert_eq!(pp.next(), None);
+    /// ```
+    pub fn with_order(n: usize, order: Order) -> Partitions {
+        let mut p = Partitions::new(n);
+        if order == Order::RevLex {
+            p.reinit_rev_lex(n);
+        }
+        p
+    }
+
+    /// Makes an iterator over the partitions of `n` whose parts all come
+    /// from `allowed`, in the same order as `new`.
+    ///
+    /// This is a `SupplyPartitions` with an unlimited supply of each
+    /// allowed part, a count of `usize::MAX`, so it never tries a
+    /// partition it cannot finish, after setting up a table of `O(n)`
+    /// bits for each allowed part.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `allowed` contains a zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use integer_partitions::Partitions;
+    ///
+    /// let mut pp = Partitions::with_parts(10, &[3, 4]);
+    /// assert_eq!(pp.next(), Some(&[3, 3, 4][..]));
+    /// assert_eq!(pp.next(), None);
+    /// ```
+    pub fn with_parts(n: usize, allowed: &[usize]) -> supply::SupplyPartitions {
+        let supply: Vec<(usize, usize)> = allowed.iter().map(|&x| (x, usize::MAX)).collect();
+        supply::SupplyPartitions::new(n, &supply)
+    }
+
+    /// Makes an iterator over the partitions of `n` into odd parts, in
+    /// the same order as `new`.
+    ///
+    /// Unlike `with_parts` this needs no table, since the parity of
+    /// what is left says directly which part can come next.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use integer_partitions::Partitions;
+    ///
+    /// let mut pp = Partitions::odd_parts(5);
+    /// assert_eq!(pp.next(), Some(&[1, 1, 1, 1, 1][..]));
+    /// assert_eq!(pp.next(), Some(&[1, 1, 3][..]));
+    /// assert_eq!(pp.next(), Some(&[5][..]));
+    /// assert_eq!(pp.next(), None);
+    /// ```
+    #[inline]
+    pub fn odd_parts(n: usize) -> odd::OddParts {
+        odd::OddParts::new(n)
+    }
+
+    /// Calls `f` on each partition of `n`, in the order of `new`.
+    ///
+    /// This runs Kelleher's loop directly, with no iterator state to
+    /// save and restore between partitions, so it is the fastest way to
+    /// visit every partition when nothing needs to stop early.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use integer_partitions::Partitions;
+    ///
+    /// let mut parts = 0;
+    /// Partitions::for_each_partition(30, |p| parts += p.len());
+    /// assert_eq!(parts, 54563);
+    /// ```
+    #[inline]
+    pub fn for_each_partition<F: FnMut(&[usize])>(n: usize, mut f: F) {
+        Partitions::fold_partitions(n, (), |(), p| f(p))
+    }
+
+    /// Folds `f` over the partitions of `n`, in the order of `new`,
+    /// starting from `init`, like `for_each_partition`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use integer_partitions::Partitions;
+    ///
+    /// let distinct = Partitions::fold_partitions(10, 0, |c, p| {
+    ///     c + p.windows(2).all(|w| w[0] < w[1]) as usize
+    /// });
+    /// assert_eq!(distinct, 10);
+    /// ```
+    pub fn fold_partitions<T, F: FnMut(T, &[usize]) -> T>(n: usize, init: T, mut f: F) -> T {
         if n == 0 {
-            return Partitions {
-                a: vec!(1),
-                k: 0,
-                y: 0,
-                next: State::A,
-            }
+            return f(init, &[]);
         }
-        Partitions {
-            a: vec![0; n + 1],
-            k: 1,
-            y: n - 1,
-            next: State::A,
+        let mut acc = init;
+        let mut a = vec![0; n + 1];
+        let mut k = 1;
+        let mut y = n - 1;
+        while k != 0 {
+            let mut x = a[k - 1] + 1;
+            k -= 1;
+            while 2 * x <= y {
+                a[k] = x;
+                y -= x;
+                k += 1;
+            }
+            let l = k + 1;
+            while x <= y {
+                a[k] = x;
+                a[l] = y;
+                acc = f(acc, &a[..(k + 2)]);
+                x += 1;
+                y -= 1;
+            }
+            a[k] = x + y;
+            y = x + y - 1;
+            acc = f(acc, &a[..(k + 1)]);
         }
+        acc
     }
 
-    /// Advances the iterator and returns the next partition.
+    /// Starts setting limits on the parts of the partitions of `n`, for
+    /// an iterator over just those within them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use integer_partitions::Partitions;
+    ///
+    /// let mut pp = Partitions::builder(7).max_parts(2).build();
+    /// assert_eq!(pp.next(), Some(&[1, 6][..]));
+    /// assert_eq!(pp.next(), Some(&[2, 5][..]));
+    /// assert_eq!(pp.next(), Some(&[3, 4][..]));
+    /// assert_eq!(pp.next(), Some(&[7][..]));
+    /// assert_eq!(pp.next(), None);
+    /// ```
     #[inline]
-    pub fn next(&mut self) -> Option<&[usize]> {
-        StreamingIterator::next(self)
+    pub fn builder(n: usize) -> restricted::PartitionsBuilder {
+        restricted::PartitionsBuilder::new(n)
     }
 
     /// Makes a new iterator, trying to avoid allocations.
@@ -66,49 +447,465 @@ impl Partitions {
     /// that the vector will still reallocate if its capacity is less
     /// than `n + 1`.
     #[inline]
-    pub fn recycle(n: usize, mut vec: Vec<usize>) -> Partitions {
-        vec.clear();
-        
-        if n == 0 {
-            vec.push(1);
-            return Partitions {
-                a: vec,
-                k: 0,
-                y: 0,
-                next: State::A,
+    pub fn recycle(n: usize, vec: Vec<usize>) -> Partitions {
+        let mut p = Partitions::empty(n, vec);
+        p.reinit(n);
+        p
+    }
+
+    /// Makes an iterator whose current partition is `p`, so that `get`
+    /// returns `p` and advancing continues from there.
+    ///
+    /// `p` must be a partition in non-decreasing order.
+    pub(crate) fn at(p: &[usize]) -> Partitions {
+        let mut pp = Partitions::new(p.iter().sum());
+        pp.set_current(p);
+        pp
+    }
+
+    /// Makes `p`, a partition of the number the iterator was started at
+    /// in lexicographic order, the current one.
+    fn set_current(&mut self, p: &[usize]) {
+        #[cfg(feature = "validate")]
+        self.check.resume(p);
+
+        if self.n == 0 {
+            self.a[0] = 2;
+            return;
+        }
+
+        self.a[..p.len()].copy_from_slice(p);
+        self.k = p.len() - 1;
+        self.y = p[p.len() - 1] - 1;
+        self.next = State::A;
+    }
+
+    /// Returns the position of `p` among the partitions of the same
+    /// number, in the order of `new`.
+    ///
+    /// This uses a table of counts that is built once for the largest
+    /// number ranked so far on the thread, in `O(n²)` time and space,
+    /// and kept for later calls, or without the `std` feature built
+    /// afresh for each call.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `p` has positive parts in non-decreasing order, or
+    /// if they add up to more than `count::P_CONST_MAX`, past which the
+    /// number of partitions does not fit in a `u64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use integer_partitions::Partitions;
+    ///
+    /// assert_eq!(Partitions::rank(&[1, 1, 1, 1]), 0);
+    /// assert_eq!(Partitions::rank(&[2, 2]), 3);
+    /// assert_eq!(Partitions::unrank(4, 3), vec![2, 2]);
+    /// ```
+    pub fn rank(p: &[usize]) -> usize {
+        let n = p.iter().sum();
+        with_table(n, |t| t.rank_in(n, p)).expect("not a partition in non-decreasing order")
+    }
+
+    /// Returns the partition of `n` at position `i`, in the order of
+    /// `new`, using the same table as `rank`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is not less than the number of partitions of `n`,
+    /// or if `n` is greater than `count::P_CONST_MAX`.
+    pub fn unrank(n: usize, i: usize) -> Vec<usize> {
+        let mut out = Vec::new();
+        with_table(n, |t| t.unrank_in(n, i, &mut out));
+        out
+    }
+
+    /// Saves where the iterator has got to, so that `resume` can make an
+    /// iterator that carries on from exactly there.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use integer_partitions::{Order, Partitions, PartitionsState};
+    ///
+    /// let mut pp = Partitions::new(4);
+    /// pp.next();
+    /// pp.next();
+    /// let state = pp.save_state();
+    /// assert_eq!(state, PartitionsState::At { current: vec![1, 1, 2], order: Order::Lex });
+    ///
+    /// let mut qq = Partitions::resume(&state);
+    /// assert_eq!(qq.next(), Some(&[1, 3][..]));
+    /// assert_eq!(qq.next(), Some(&[2, 2][..]));
+    /// ```
+    pub fn save_state(&self) -> PartitionsState {
+        let order = self.order();
+        let fresh = match self.desc {
+            Some(ref d) => *d == Desc::Fresh,
+            // Before the first partition the last slot is still empty.
+            None => self.next == State::A && if self.n == 0 { self.a[0] == 1 } else { self.k > 0 && self.a[self.k] == 0 },
+        };
+        if fresh {
+            let min_part = if self.desc.is_none() && self.n > 0 { self.a[0] + 1 } else { 1 };
+            return PartitionsState::Start { n: self.n, min_part, order };
+        }
+        match self.get() {
+            Some(p) => PartitionsState::At { current: p.to_vec(), order },
+            None => PartitionsState::Done { n: self.n, order },
+        }
+    }
+
+    /// Makes an iterator from a state saved by `save_state`, which goes
+    /// on as the saved one would have.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a current partition has a part of zero, or its parts
+    /// are not in the order's direction.
+    pub fn resume(state: &PartitionsState) -> Partitions {
+        match *state {
+            PartitionsState::Start { n, min_part, order: Order::Lex } => Partitions::with_min_part(n, min_part),
+            PartitionsState::Start { n, order: Order::RevLex, .. } => Partitions::with_order(n, Order::RevLex),
+            PartitionsState::At { ref current, order } => {
+                assert!(!current.contains(&0), "parts must be positive");
+                let n = current.iter().sum();
+                let mut pp = Partitions::with_order(n, order);
+                if order == Order::Lex {
+                    assert!(current.windows(2).all(|w| w[0] <= w[1]), "parts must be in non-decreasing order");
+                    pp.set_current(current);
+                } else {
+                    assert!(current.windows(2).all(|w| w[0] >= w[1]), "parts must be in non-increasing order");
+                    #[cfg(feature = "validate")]
+                    pp.check.resume(current);
+                    // The parts past the current ones stay ones.
+                    pp.a[..current.len()].copy_from_slice(current);
+                    let m = current.len();
+                    let h = current.iter().take_while(|&&x| x > 1).count();
+                    pp.desc = Some(Desc::Going { m, h });
+                }
+                pp
+            }
+            PartitionsState::Done { n, order } => {
+                let mut pp = Partitions::with_order(n, order);
+                if order == Order::Lex {
+                    // A lone 0 stands for an iterator that is already
+                    // exhausted.
+                    pp.a.clear();
+                    pp.a.push(0);
+                    pp.k = 0;
+                    pp.y = 0;
+                } else {
+                    pp.desc = Some(Desc::Done);
+                }
+                pp
             }
         }
-        
-        vec.reserve(n + 1);
-        for _ in 0..(n + 1) {
-            vec.push(0);
+    }
+
+    /// Splits the partitions of `n` into at most `chunks` runs of
+    /// consecutive ranks, as even in length as can be, each of which is
+    /// an independent iterator that can be driven on its own thread.
+    ///
+    /// Run in order, the ranges give every partition once, in the order
+    /// of `new`. There are fewer than `chunks` of them only when there
+    /// are fewer partitions than that. This uses the same table as
+    /// `rank`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunks` is zero, or if `n` is greater than
+    /// `count::P_CONST_MAX`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use integer_partitions::Partitions;
+    ///
+    /// let counts: Vec<usize> = Partitions::par_ranges(30, 4)
+    ///     .into_iter()
+    ///     .map(|mut r| thread::spawn(move || {
+    ///         let mut c = 0;
+    ///         while r.next().is_some() {
+    ///             c += 1;
+    ///         }
+    ///         c
+    ///     }))
+    ///     .map(|h| h.join().unwrap())
+    ///     .collect();
+    ///
+    /// // 5604 partitions of 30, split evenly.
+    /// assert_eq!(counts, vec![1401; 4]);
+    /// ```
+    pub fn par_ranges(n: usize, chunks: usize) -> Vec<cursor::PartitionRange> {
+        assert!(chunks > 0, "there must be at least one range");
+        with_table(n, |t| {
+            let total = t.at_least(n, 1);
+            let chunks = chunks.min(total);
+            let bound = |i: usize| (i as u128 * total as u128 / chunks as u128) as usize;
+            (0..chunks)
+                .map(|i| cursor::PartitionRange::new(t, n, bound(i), bound(i + 1)))
+                .collect()
+        })
+    }
+
+    /// Restarts the iterator so that the next partition it returns is
+    /// the one at position `rank`, using the same table as `rank`, or
+    /// so that it is exhausted if there are not that many.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator is in reverse lexicographic order, or if
+    /// `n` is greater than `count::P_CONST_MAX`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use integer_partitions::Partitions;
+    ///
+    /// let mut pp = Partitions::new(4);
+    /// pp.skip_to(3);
+    /// assert_eq!(pp.next(), Some(&[2, 2][..]));
+    /// assert_eq!(pp.next(), Some(&[4][..]));
+    /// assert_eq!(pp.next(), None);
+    /// ```
+    pub fn skip_to(&mut self, rank: usize) {
+        assert!(self.desc.is_none(), "can only skip in lexicographic order");
+        let n = self.n;
+        self.reinit(n);
+        if rank == 0 {
+            return;
         }
+        let mut prev = Vec::new();
+        let found = with_table(n, |t| {
+            let ok = rank < t.at_least(n, 1);
+            if ok {
+                t.unrank_in(n, rank - 1, &mut prev);
+            }
+            ok
+        });
+        if found {
+            self.set_current(&prev);
+        } else {
+            // A lone 0 stands for an iterator that is already exhausted.
+            self.a.clear();
+            self.a.push(0);
+            self.k = 0;
+            self.y = 0;
+        }
+    }
 
+}
+
+impl<T: Int> Partitions<T> {
+    /// Makes a new iterator, with parts of the type of `n`.
+    ///
+    /// This is `new` for any `Int` type, and lists the same partitions
+    /// in the same order.
+    #[inline]
+    pub fn of(n: T) -> Partitions<T> {
+        let mut p = Partitions::empty(n, Vec::new());
+        p.reinit(n);
+        p
+    }
+
+    fn empty(n: T, a: Vec<T>) -> Partitions<T> {
         Partitions {
-            a: vec,
-            k: 1,
-            y: n - 1,
+            n,
+            a,
+            k: 0,
+            y: T::ZERO,
             next: State::A,
+            desc: None,
+            #[cfg(feature = "validate")]
+            check: validate::Validator::new("Partitions", n, validate::Order::Lex),
+        }
+    }
+
+    /// Returns the order that the iterator lists partitions in.
+    #[inline]
+    pub fn order(&self) -> Order {
+        if self.desc.is_some() {
+            Order::RevLex
+        } else {
+            Order::Lex
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[T]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Restarts the iterator at the first partition of `n`, in the same
+    /// order as before.
+    ///
+    /// The existing vector is reused, and only reallocates if its
+    /// capacity is less than `n + 1`.
+    #[inline]
+    pub fn reinit(&mut self, n: T) {
+        if self.desc.is_some() {
+            self.reinit_rev_lex(n);
+        } else {
+            self.reinit_with_min_part(n, T::ONE);
         }
     }
 
+    /// Restarts the iterator at the first partition of `n` whose parts
+    /// are all at least `m`, like `with_min_part`, and so in
+    /// lexicographic order.
+    ///
+    /// The existing vector is reused, and only reallocates if its
+    /// capacity is less than `n + 1`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n + 1` does not fit in a `usize`.
+    pub fn reinit_with_min_part(&mut self, n: T, m: T) {
+        let m = m.max(T::ONE);
+        self.n = n;
+        self.a.clear();
+        self.next = State::A;
+        self.desc = None;
+        #[cfg(feature = "validate")]
+        {
+            self.check = validate::Validator::new("Partitions", n, validate::Order::Lex).min_part(m);
+        }
+
+        if n == T::ZERO || m > n {
+            // A lone 1 stands for the empty partition, and a lone 0 for
+            // an iterator that is already exhausted.
+            self.a.push(if n == T::ZERO { T::ONE } else { T::ZERO });
+            self.k = 0;
+            self.y = T::ZERO;
+            return;
+        }
+
+        self.a.reserve(len(n) + 1);
+        for _ in 0..(len(n) + 1) {
+            self.a.push(T::ZERO);
+        }
+        self.a[0] = m - T::ONE;
+        self.k = 1;
+        self.y = n - m;
+    }
+
+    /// Restarts the iterator at `n` alone, to go on in reverse
+    /// lexicographic order.
+    fn reinit_rev_lex(&mut self, n: T) {
+        // The parts beyond the first start out, and are always left,
+        // as ones.
+        self.n = n;
+        self.a.clear();
+        self.a.resize(len(n).max(1), T::ONE);
+        self.a[0] = n;
+        self.desc = Some(Desc::Fresh);
+        #[cfg(feature = "validate")]
+        {
+            self.check = validate::Validator::new("Partitions", n, validate::Order::RevLex);
+        }
+    }
+
+    /// Moves on to the next partition in reverse lexicographic order.
+    fn advance_rev_lex(&mut self) {
+        let a = &mut self.a;
+        let two = T::ONE + T::ONE;
+        let next = match self.desc {
+            Some(Desc::Fresh) => {
+                let m = if a[0] == T::ZERO { 0 } else { 1 };
+                Desc::Going { m, h: if a[0] > T::ONE { 1 } else { 0 } }
+            }
+            Some(Desc::Going { m, .. }) if m == 0 || a[0] == T::ONE => Desc::Done,
+            Some(Desc::Going { mut m, mut h }) => {
+                if a[h - 1] == two {
+                    // Split the last 2 into two ones.
+                    m += 1;
+                    a[h - 1] = T::ONE;
+                    h -= 1;
+                } else {
+                    // Take one from the last part greater than one, and
+                    // share it and the ones after it out in copies of
+                    // the smaller part. They add up to at most n, so the
+                    // count fits in T.
+                    let r = a[h - 1] - T::ONE;
+                    let mut t = T::from_usize(m - h + 1).unwrap();
+                    a[h - 1] = r;
+                    while t >= r {
+                        h += 1;
+                        a[h - 1] = r;
+                        t = t - r;
+                    }
+                    if t == T::ZERO {
+                        m = h;
+                    } else {
+                        m = h + 1;
+                        if t > T::ONE {
+                            h += 1;
+                            a[h - 1] = t;
+                        }
+                    }
+                }
+                Desc::Going { m, h }
+            }
+            _ => Desc::Done,
+        };
+        self.desc = Some(next);
+    }
+
     /// Destroys the iterator and returns a vector for further use.
     ///
     /// You only need to call this function if you want to reuse the
     /// vector for something else. Its contents will be in an undefined
     /// state, and so cannot be relied upon.
     #[inline]
-    pub fn end(self) -> Vec<usize> {
+    pub fn end(self) -> Vec<T> {
         self.a
     }
 }
 
-impl StreamingIterator for Partitions {
-    type Item = [usize];
+/// Converts `n` to a length.
+#[inline]
+fn len<T: Int>(n: T) -> usize {
+    n.to_usize().expect("n must fit in a usize")
+}
+
+#[cfg(any(feature = "std", test))]
+thread_local! {
+    /// The largest ranking table built on this thread, which serves
+    /// every number up to its own.
+    static TABLE: RefCell<Option<RankTable>> = const { RefCell::new(None) };
+}
+
+#[cfg(any(feature = "std", test))]
+fn with_table<R, F: FnOnce(&RankTable) -> R>(n: usize, f: F) -> R {
+    TABLE.with(|t| {
+        let mut t = t.borrow_mut();
+        if t.as_ref().is_none_or(|t| t.n() < n) {
+            *t = Some(RankTable::new(n));
+        }
+        f(t.as_ref().unwrap())
+    })
+}
+
+#[cfg(not(any(feature = "std", test)))]
+fn with_table<R, F: FnOnce(&RankTable) -> R>(n: usize, f: F) -> R {
+    f(&RankTable::new(n))
+}
+
+impl<T: Int> StreamingIterator for Partitions<T> {
+    type Item = [T];
 
     fn get(&self) -> Option<&Self::Item> {
-        if self.next == State::A && self.k == 0 && (self.a[0] == 0 || self.a.len() == 1) {
-            if self.a[0] == 0 {
+        match self.desc {
+            Some(Desc::Going { m, .. }) => return Some(&self.a[..m]),
+            Some(_) => return None,
+            None => {}
+        }
+        if self.next == State::A && self.k == 0 && (self.a[0] == T::ZERO || self.a.len() == 1) {
+            if self.a[0] == T::ZERO {
                 None
             } else {
                 Some(&[])
@@ -123,28 +920,37 @@ impl StreamingIterator for Partitions {
 
     #[inline]
     fn advance(&mut self) {
+        if self.desc.is_some() {
+            self.advance_rev_lex();
+            #[cfg(feature = "validate")]
+            self.check.check(self.get());
+            return;
+        }
+
         let Partitions {
             ref mut a,
             ref mut k,
             ref mut y,
-            ref mut next
+            ref mut next,
+            ..
         } = *self;
 
         match *next {
             State::A => {
                 if *k == 0 {
-                    if a.len() == 1 && a[0] == 1 {
-                        a[0] = 2;
+                    if a.len() == 1 && a[0] == T::ONE {
+                        a[0] = T::ONE + T::ONE;
                     } else {
-                        a[0] = 0;
+                        a[0] = T::ZERO;
                     }
                 } else {
                     *k -= 1;
-                    let x = a[*k] + 1;
+                    let x = a[*k] + T::ONE;
 
-                    while 2 * x <= *y {
+                    // 2x ≤ y, without overflowing near the top of T.
+                    while x <= *y / (T::ONE + T::ONE) {
                         a[*k] = x;
-                        *y -= x;
+                        *y = *y - x;
                         *k += 1;
                     }
 
@@ -156,13 +962,13 @@ impl StreamingIterator for Partitions {
                         *next = State::B { x, l };
                     } else {
                         a[*k] = x + *y;
-                        *y = x + *y - 1;
+                        *y = x + *y - T::ONE;
                     }
                 }
             },
             State::B { mut x, l } => {
-                x += 1;
-                *y -= 1;
+                x = x + T::ONE;
+                *y = *y - T::ONE;
 
                 if x <= *y {
                     a[*k] = x;
@@ -170,11 +976,31 @@ impl StreamingIterator for Partitions {
                     *next = State::B { x, l };
                 } else {
                     a[*k] = x + *y;
-                    *y = x + *y - 1;
+                    *y = x + *y - T::ONE;
                     *next = State::A;
                 }
             },
         }
+
+        #[cfg(feature = "validate")]
+        self.check.check(self.get());
+    }
+}
+
+impl PartitionIterator for Partitions {
+    #[inline]
+    fn n(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn reinit(&mut self, n: usize) {
+        Partitions::reinit(self, n)
+    }
+
+    #[inline]
+    fn end(self) -> Vec<usize> {
+        Partitions::end(self)
     }
 }
 
@@ -206,6 +1032,109 @@ fn oeis() {
     }
 }
 
+#[test]
+fn min_part() {
+    //! Tests restricting the smallest part against filtering.
+
+    for n in 0..25 {
+        for m in 0..(n + 3) {
+            let mut all = Partitions::new(n);
+            let mut some = Partitions::with_min_part(n, m);
+            while let Some(x) = all.next() {
+                if x.first().is_none_or(|&x| x >= m) {
+                    assert_eq!(some.next(), Some(x));
+                }
+            }
+            assert_eq!(some.next(), None);
+            assert_eq!(some.next(), None);
+        }
+    }
+}
+
+#[test]
+fn rev_lex() {
+    //! Tests the reverse lexicographic order against reversing the
+    //! lexicographic one, and reusing it with `reinit`.
+
+    let mut p = Partitions::with_order(0, Order::RevLex);
+    for n in 0..30 {
+        let mut all = Vec::new();
+        let mut q = Partitions::new(n);
+        while let Some(x) = q.next() {
+            let mut x = x.to_vec();
+            x.reverse();
+            all.push(x);
+        }
+        all.sort();
+        all.reverse();
+
+        p.reinit(n);
+        assert_eq!(p.order(), Order::RevLex);
+        for x in &all {
+            assert_eq!(p.next(), Some(&x[..]));
+        }
+        assert_eq!(p.next(), None);
+        assert_eq!(p.next(), None);
+    }
+
+    p.reinit_with_min_part(6, 2);
+    assert_eq!(p.order(), Order::Lex);
+    assert_eq!(p.next(), Some(&[2, 2, 2][..]));
+}
+
+#[test]
+fn ranks() {
+    //! Tests ranking, unranking and skipping against enumeration.
+
+    let mut all = Vec::new();
+    for &n in [0, 1, 7, 12, 3, 20].iter() {
+        all.clear();
+        let mut p = Partitions::new(n);
+        while let Some(x) = p.next() {
+            all.push(x.to_vec());
+        }
+        for (i, x) in all.iter().enumerate() {
+            assert_eq!(Partitions::rank(x), i);
+            assert_eq!(&Partitions::unrank(n, i), x);
+        }
+        for i in 0..(all.len() + 2) {
+            p.skip_to(i);
+            for x in all.iter().skip(i) {
+                assert_eq!(p.next(), Some(&x[..]));
+            }
+            assert_eq!(p.next(), None);
+        }
+    }
+}
+
+#[test]
+fn allowed_parts() {
+    //! Tests restricting the parts to a set against filtering.
+
+    let sets: &[&[usize]] = &[&[], &[1], &[2, 5], &[3, 1, 3], &[4, 6, 7, 30], &[1, 2, 4, 8, 16]];
+    for n in 0..25 {
+        for &set in sets {
+            let mut all = Partitions::new(n);
+            let mut some = Partitions::with_parts(n, set);
+            while let Some(x) = all.next() {
+                if x.iter().all(|y| set.contains(y)) {
+                    assert_eq!(some.next(), Some(x));
+                }
+            }
+            assert_eq!(some.next(), None);
+        }
+
+        let mut all = Partitions::new(n);
+        let mut odd = Partitions::odd_parts(n);
+        while let Some(x) = all.next() {
+            if x.iter().all(|y| y % 2 == 1) {
+                assert_eq!(odd.next(), Some(x));
+            }
+        }
+        assert_eq!(odd.next(), None);
+    }
+}
+
 #[test]
 fn n0() {
     //! Tests the special case n == 0.
@@ -214,3 +1143,191 @@ fn n0() {
     assert_eq!(p.next().unwrap().len(), 0);
     assert_eq!(p.next(), None);
 }
+
+#[test]
+fn reinit() {
+    //! Tests reusing one iterator across a sweep of n, in both directions.
+
+    let mut p = Partitions::new(3);
+    p.next();
+
+    for &n in [0, 7, 12, 1, 0, 5, 20, 2].iter() {
+        p.reinit(n);
+        let mut q = Partitions::new(n);
+
+        while let Some(x) = q.next() {
+            assert_eq!(p.next(), Some(x));
+        }
+        assert_eq!(p.next(), None);
+    }
+}
+
+#[test]
+fn part_types() {
+    //! Compares narrower part types against `usize`, up to the largest
+    //! `n` that fits.
+
+    for n in 0..40 {
+        let mut pp = Partitions::new(n);
+        let mut qq = Partitions::<u8>::of(n as u8);
+        let mut rr = Partitions::<u16>::of(n as u16);
+        while let Some(p) = pp.next() {
+            let q: Vec<usize> = qq.next().unwrap().iter().map(|&x| x as usize).collect();
+            let r: Vec<usize> = rr.next().unwrap().iter().map(|&x| x as usize).collect();
+            assert_eq!((&q[..], &r[..]), (p, p));
+        }
+        assert_eq!((qq.next(), rr.next()), (None, None));
+    }
+
+    let mut pp = Partitions::new(255);
+    let mut qq = Partitions::<u8>::of(255);
+    for _ in 0..10000 {
+        let q: Vec<usize> = qq.next().unwrap().iter().map(|&x| x as usize).collect();
+        assert_eq!(pp.next(), Some(&q[..]));
+    }
+    for m in (60..256).step_by(13) {
+        let mut pp = Partitions::with_min_part(255, m);
+        qq.reinit_with_min_part(255, m as u8);
+        while let Some(p) = pp.next() {
+            let q: Vec<usize> = qq.next().unwrap().iter().map(|&x| x as usize).collect();
+            assert_eq!(p, &q[..]);
+        }
+        assert_eq!(qq.next(), None);
+    }
+    qq.reinit_with_min_part(255, 128);
+    assert_eq!(qq.next(), Some(&[255u8][..]));
+    assert_eq!(qq.next(), None);
+}
+
+#[test]
+fn save_and_resume() {
+    //! Tests that an iterator resumed from a state saved at any point
+    //! goes on like the original.
+
+    fn rest(pp: &mut Partitions) -> Vec<Vec<usize>> {
+        let mut out = Vec::new();
+        while let Some(p) = pp.next() {
+            out.push(p.to_vec());
+        }
+        out
+    }
+
+    for n in 0..12 {
+        for m in 0..(n + 3) {
+            for &order in &[Order::Lex, Order::RevLex] {
+                let start = || if order == Order::Lex { Partitions::with_min_part(n, m) } else { Partitions::with_order(n, order) };
+                let all = rest(&mut start());
+                let mut pp = start();
+                for c in 0..(all.len() + 2) {
+                    let state = pp.save_state();
+                    let mut qq = Partitions::resume(&state);
+                    assert_eq!(qq.save_state(), state);
+                    assert_eq!(rest(&mut qq), &all[c.min(all.len())..], "{:?}", state);
+                    pp.next();
+                }
+                assert_eq!(pp.save_state(), PartitionsState::Done { n, order });
+            }
+        }
+    }
+
+    let mut pp = Partitions::with_min_part(9, 4);
+    assert_eq!(pp.save_state(), PartitionsState::Start { n: 9, min_part: 4, order: Order::Lex });
+    pp.next();
+    assert_eq!(pp.save_state(), PartitionsState::At { current: vec![4, 5], order: Order::Lex });
+}
+
+#[test]
+fn internal_iteration() {
+    //! Compares `fold_partitions` against the iterator.
+
+    for n in 0..30 {
+        let mut pp = Partitions::new(n);
+        let mut all = Vec::new();
+        while let Some(p) = pp.next() {
+            all.push(p.to_vec());
+        }
+        let seen = Partitions::fold_partitions(n, Vec::new(), |mut v, p| {
+            v.push(p.to_vec());
+            v
+        });
+        assert_eq!(seen, all);
+
+        let mut c = 0;
+        Partitions::for_each_partition(n, |_| c += 1);
+        assert_eq!(c, all.len());
+    }
+}
+
+#[test]
+fn partition_iterators() {
+    //! Tests every `PartitionIterator` through the trait alone, against
+    //! filtering `Partitions`.
+
+    use distinct::DistinctPartitions;
+    use odd::{OddParts, SelfConjugate};
+    use young::is_self_conjugate;
+
+    fn check<I: PartitionIterator, F: Fn(&[usize]) -> bool>(mut it: I, keep: F) -> Vec<usize> {
+        for n in 0..20 {
+            it.reinit(n);
+            assert_eq!(it.n(), n);
+            let mut got = Vec::new();
+            while let Some(p) = it.next() {
+                got.push(p.to_vec());
+            }
+            got.sort();
+            let mut expected = Vec::new();
+            let mut pp = Partitions::new(n);
+            while let Some(p) = pp.next() {
+                if keep(p) {
+                    expected.push(p.to_vec());
+                }
+            }
+            assert_eq!(got, expected, "n = {}", n);
+        }
+        it.end()
+    }
+
+    let v = check(Partitions::new(3), |_| true);
+    let v = check(DistinctPartitions::recycle(0, v), |p| p.windows(2).all(|w| w[0] < w[1]));
+    let v = check(PartitionsExact::recycle(0, 3, v), |p| p.len() == 3);
+    check(Partitions::builder(0).max_part(4).build(), |p| p.iter().all(|&x| x <= 4));
+    let v = check(supply::SupplyPartitions::recycle(0, &[(2, 3), (3, 2)], v), |p| {
+        p.iter().all(|&x| x == 2 || x == 3)
+            && p.iter().filter(|&&x| x == 2).count() <= 3
+            && p.iter().filter(|&&x| x == 3).count() <= 2
+    });
+    let v = check(OddParts::recycle(0, v), |p| p.iter().all(|&x| x % 2 == 1));
+    check(SelfConjugate::recycle(0, v), is_self_conjugate);
+    check(sparse::AtMost::new(0, 3), |p| p.len() <= 3);
+    check(sparse::Distinct::new(0, 3), |p| p.len() <= 3 && p.windows(2).all(|w| w[0] < w[1]));
+    check(successor::Constrained::new(0, successor::Multiplicities::new().at_most(1, 2)), |p| {
+        p.iter().filter(|&&x| x == 1).count() <= 2
+    });
+    check(tableau::Dimensions::new(0), |_| true);
+    check(young::UpToConjugation::new(0), |p| {
+        let mut conj = Vec::new();
+        young::conjugate(p, &mut conj);
+        p.last() > conj.last() || (p.last() == conj.last() && p <= &conj[..])
+    });
+    check(young::DurfeePartitions::new(0), |_| true);
+}
+
+#[test]
+fn reinit_larger() {
+    //! Tests that `with_parts` and `odd_parts` restart through the trait
+    //! at a larger `n` with all the partitions they allow there.
+
+    fn count<I: PartitionIterator>(mut it: I, n: usize) -> usize {
+        it.reinit(n);
+        let mut c = 0;
+        while it.next().is_some() {
+            c += 1;
+        }
+        c
+    }
+
+    assert_eq!(count(Partitions::odd_parts(1), 5), 3);
+    assert_eq!(count(Partitions::with_parts(2, &[1]), 5), 1);
+    assert_eq!(count(Partitions::with_parts(0, &[1, 2]), 6), 4);
+}
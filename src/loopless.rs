@@ -0,0 +1,163 @@
+//! Enumeration with a worst-case constant amount of work per step.
+
+use alloc::vec::Vec;
+
+use streaming_iterator::StreamingIterator;
+
+/// Iterates over the partitions of a given nonnegative integer, in
+/// multiplicity form.
+///
+/// Each partition is yielded as a list of `(part, multiplicity)` pairs
+/// in increasing order of part, and the partitions come in the same
+/// order as they do from `Partitions`. Unlike `Partitions`, which only
+/// takes constant time per partition on average, every call to
+/// `advance` does a bounded amount of work: it touches at most the last
+/// two pairs and never loops.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::loopless::LooplessPartitions;
+///
+/// let mut pp = LooplessPartitions::new(4);
+/// assert_eq!(pp.next(), Some(&[(1, 4)][..]));
+/// assert_eq!(pp.next(), Some(&[(1, 2), (2, 1)][..]));
+/// assert_eq!(pp.next(), Some(&[(1, 1), (3, 1)][..]));
+/// assert_eq!(pp.next(), Some(&[(2, 2)][..]));
+/// assert_eq!(pp.next(), Some(&[(4, 1)][..]));
+/// assert_eq!(pp.next(), None);
+/// ```
+#[derive(Debug)]
+pub struct LooplessPartitions {
+    a: Vec<(usize, usize)>,
+    state: State,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum State {
+    Fresh,
+    Going,
+    Done,
+}
+
+impl LooplessPartitions {
+    /// Makes a new iterator.
+    ///
+    /// The buffer is allocated up front with room for the largest
+    /// possible number of distinct parts, so stepping never allocates.
+    pub fn new(n: usize) -> LooplessPartitions {
+        let mut d = 0;
+        while (d + 1) * (d + 2) / 2 <= n {
+            d += 1;
+        }
+
+        let mut a = Vec::with_capacity(d + 2);
+        if n > 0 {
+            a.push((1, n));
+        }
+
+        LooplessPartitions {
+            a,
+            state: State::Fresh,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[(usize, usize)]> {
+        StreamingIterator::next(self)
+    }
+}
+
+impl StreamingIterator for LooplessPartitions {
+    type Item = [(usize, usize)];
+
+    #[inline]
+    fn get(&self) -> Option<&Self::Item> {
+        match self.state {
+            State::Going => Some(&self.a),
+            _ => None,
+        }
+    }
+
+    fn advance(&mut self) {
+        let a = &mut self.a;
+
+        match self.state {
+            State::Fresh => {
+                self.state = State::Going;
+                return;
+            },
+            State::Done => return,
+            State::Going => {},
+        }
+
+        let d = a.len();
+        if d == 0 || (d == 1 && a[0].1 == 1) {
+            self.state = State::Done;
+            return;
+        }
+
+        // Take away the last two parts, x <= y.
+        let (y, m) = a[d - 1];
+        let x = if m >= 2 {
+            a[d - 1].1 -= 2;
+            if a[d - 1].1 == 0 {
+                a.pop();
+            }
+            y
+        } else {
+            a.pop();
+            let (x, m) = a[d - 2];
+            if m == 1 {
+                a.pop();
+            } else {
+                a[d - 2].1 -= 1;
+            }
+            x
+        };
+
+        // Redistribute them as copies of x + 1, with the remainder
+        // absorbed by a final part between x + 1 and 2x + 1.
+        let s = x + y;
+        let c = s / (x + 1) - 1;
+        let f = s - c * (x + 1);
+
+        if f == x + 1 {
+            a.push((x + 1, c + 1));
+        } else {
+            if c > 0 {
+                a.push((x + 1, c));
+            }
+            a.push((f, 1));
+        }
+    }
+}
+
+#[test]
+fn matches_partitions() {
+    //! Tests that the expanded output agrees with `Partitions`.
+
+    use Partitions;
+
+    for n in 0..30 {
+        let mut p = Partitions::new(n);
+        let mut q = LooplessPartitions::new(n);
+        let mut buf = Vec::new();
+
+        while let Some(x) = p.next() {
+            let y = q.next().unwrap();
+            buf.clear();
+            for &(part, mult) in y {
+                assert!(mult > 0);
+                for _ in 0..mult {
+                    buf.push(part);
+                }
+            }
+            assert_eq!(x, &buf[..]);
+        }
+
+        assert_eq!(q.next(), None);
+    }
+}
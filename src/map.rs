@@ -0,0 +1,188 @@
+//! Dense storage of one value per partition.
+
+use alloc::vec::Vec;
+use core::ops::{Index, IndexMut};
+use core::slice;
+
+use rank::RankTable;
+use Partitions;
+
+/// A map holding one value for every partition of `n`.
+///
+/// Values are stored contiguously in enumeration order, and a partition
+/// is looked up by computing its rank, which takes time proportional to
+/// its number of parts. Building the map also builds a ranking table of
+/// `O(n²)` counts.
+///
+/// Partitions are given as slices of parts in non-decreasing order,
+/// exactly as `Partitions` yields them.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::map::PartitionMap;
+///
+/// let mut m = PartitionMap::from_fn(5, |p| p.len());
+/// assert_eq!(m.len(), 7);
+/// assert_eq!(m[&[1, 2, 2][..]], 3);
+///
+/// m[&[5][..]] = 100;
+/// assert_eq!(m.get(&[5]), Some(&100));
+/// assert_eq!(m.get(&[2, 2]), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PartitionMap<V> {
+    table: RankTable,
+    values: Vec<V>,
+}
+
+impl<V> PartitionMap<V> {
+    /// Makes a new map, calling `f` on each partition in turn.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than `count::P_CONST_MAX`, past which
+    /// the number of partitions does not fit in a `u64`.
+    pub fn from_fn<F: FnMut(&[usize]) -> V>(n: usize, mut f: F) -> PartitionMap<V> {
+        let table = RankTable::new(n);
+        let mut values = Vec::with_capacity(table.count());
+
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            values.push(f(p));
+        }
+
+        PartitionMap { table, values }
+    }
+
+    /// The number that the keys are partitions of.
+    #[inline]
+    pub fn n(&self) -> usize {
+        self.table.n()
+    }
+
+    /// The number of partitions, and so of values.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Always `false`, since every number has at least one partition.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the rank of a partition, which is its index in `values`.
+    #[inline]
+    pub fn rank(&self, p: &[usize]) -> Option<usize> {
+        self.table.rank(p)
+    }
+
+    /// Returns the value for a partition, or `None` if `p` is not a
+    /// partition of `n` in non-decreasing order.
+    #[inline]
+    pub fn get(&self, p: &[usize]) -> Option<&V> {
+        self.rank(p).map(|i| &self.values[i])
+    }
+
+    /// Returns the value for a partition mutably.
+    #[inline]
+    pub fn get_mut(&mut self, p: &[usize]) -> Option<&mut V> {
+        match self.rank(p) {
+            Some(i) => Some(&mut self.values[i]),
+            None => None,
+        }
+    }
+
+    /// Returns all values, in enumeration order.
+    #[inline]
+    pub fn values(&self) -> &[V] {
+        &self.values
+    }
+
+    /// Returns all values mutably, in enumeration order.
+    #[inline]
+    pub fn values_mut(&mut self) -> &mut [V] {
+        &mut self.values
+    }
+
+    /// Iterates over the partitions and their values, in enumeration order.
+    pub fn iter<'a>(&'a self) -> Iter<'a, V> {
+        Iter {
+            parts: Partitions::new(self.n()),
+            values: self.values.iter(),
+        }
+    }
+}
+
+impl<V: Clone> PartitionMap<V> {
+    /// Makes a new map with every value set to `v`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than `count::P_CONST_MAX`.
+    pub fn filled(n: usize, v: V) -> PartitionMap<V> {
+        let table = RankTable::new(n);
+        let values = vec![v; table.count()];
+        PartitionMap { table, values }
+    }
+}
+
+impl<'a, V> Index<&'a [usize]> for PartitionMap<V> {
+    type Output = V;
+
+    /// # Panics
+    ///
+    /// Panics if `p` is not a partition of `n` in non-decreasing order.
+    #[inline]
+    fn index(&self, p: &'a [usize]) -> &V {
+        self.get(p).expect("not a partition of n")
+    }
+}
+
+impl<'a, V> IndexMut<&'a [usize]> for PartitionMap<V> {
+    #[inline]
+    fn index_mut(&mut self, p: &'a [usize]) -> &mut V {
+        self.get_mut(p).expect("not a partition of n")
+    }
+}
+
+/// Iterates over the entries of a `PartitionMap`.
+#[derive(Debug)]
+pub struct Iter<'a, V: 'a> {
+    parts: Partitions,
+    values: slice::Iter<'a, V>,
+}
+
+impl<'a, V> Iter<'a, V> {
+    /// Advances the iterator and returns the next partition and its value.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<(&[usize], &'a V)> {
+        match self.values.next() {
+            Some(v) => self.parts.next().map(|p| (p, v)),
+            None => None,
+        }
+    }
+}
+
+#[test]
+fn lookup() {
+    //! Tests that lookups agree with enumeration order.
+
+    for n in 0..20 {
+        let m = PartitionMap::from_fn(n, |p| p.to_vec());
+        let mut it = m.iter();
+        let mut i = 0;
+
+        while let Some((p, v)) = it.next() {
+            assert_eq!(p, &v[..]);
+            assert_eq!(&m[p], v);
+            assert_eq!(m.rank(p), Some(i));
+            i += 1;
+        }
+
+        assert_eq!(i, m.len());
+    }
+}
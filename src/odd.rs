@@ -0,0 +1,370 @@
+//! Partitions into odd parts, and self-conjugate partitions.
+//!
+//! These are the two families that odd parts classically describe: by
+//! Euler's theorem there are as many partitions of `n` into odd parts as
+//! into distinct parts, and a self-conjugate partition is determined by
+//! the hooks on its diagonal, which are distinct odd numbers adding up
+//! to `n`. `OddParts` lists the first directly, and `SelfConjugate`
+//! builds the second from the distinct odd partitions of `n`.
+//!
+//! # Examples
+//!
+//! ```
+//! use integer_partitions::odd::{OddParts, SelfConjugate};
+//!
+//! let mut pp = OddParts::new(6);
+//! assert_eq!(pp.next(), Some(&[1, 1, 1, 1, 1, 1][..]));
+//! assert_eq!(pp.next(), Some(&[1, 1, 1, 3][..]));
+//! assert_eq!(pp.next(), Some(&[1, 5][..]));
+//! assert_eq!(pp.next(), Some(&[3, 3][..]));
+//! assert_eq!(pp.next(), None);
+//!
+//! // Hooks 1 + 7 and 3 + 5.
+//! let mut ss = SelfConjugate::new(8);
+//! assert_eq!(ss.next(), Some(&[1, 1, 2, 4][..]));
+//! assert_eq!(ss.next(), Some(&[2, 3, 3][..]));
+//! assert_eq!(ss.next(), None);
+//! ```
+
+use alloc::vec::Vec;
+
+use streaming_iterator::StreamingIterator;
+use successor::{start, step, Constraint, Family};
+#[cfg(feature = "validate")]
+use validate::{Order, Validator};
+use PartitionIterator;
+
+/// Odd parts, distinct or not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Odd {
+    strict: bool,
+}
+
+impl Odd {
+    /// Returns whether `t` is made up of odd parts above `x`, or at least
+    /// `x` unless they must be distinct.
+    #[inline]
+    fn fits(&self, x: usize, t: usize) -> bool {
+        // One part makes up any large enough odd amount, and two parts
+        // any large enough even one: y + (t - y), or y + 2 + (t - y - 2)
+        // if they must differ.
+        let y = if self.strict { x + 2 } else { x };
+        let two = if self.strict { 2 * y + 2 } else { 2 * y };
+        t == 0 || if t % 2 == 1 { t >= y } else { t >= two }
+    }
+}
+
+impl Constraint for Odd {
+    #[inline]
+    fn allows(&self, prefix: &[usize], x: usize, rest: usize) -> bool {
+        x % 2 == 1 && !(self.strict && prefix.last() == Some(&x)) && self.fits(x, rest)
+    }
+
+    fn first(&self, prefix: &[usize], lo: usize, s: usize) -> Option<usize> {
+        let lo = match prefix.last() {
+            Some(&y) if self.strict => lo.max(y + 1),
+            _ => lo,
+        };
+        // The least odd part that leaves a possible rest, or else the
+        // whole of s as the last part.
+        let x = lo | 1;
+        if x <= s && self.fits(x, s - x) {
+            Some(x)
+        } else if s % 2 == 1 && x <= s {
+            Some(s)
+        } else {
+            None
+        }
+    }
+}
+
+impl Family for Odd {
+    #[cfg(feature = "validate")]
+    fn validator(&self, n: usize) -> Validator<usize> {
+        let v = Validator::new("odd::OddParts", n, Order::Lex);
+        if self.strict {
+            v.strict()
+        } else {
+            v
+        }
+    }
+}
+
+/// Iterates over the partitions of `n` into odd parts, in the order of
+/// `Partitions`.
+///
+/// Each next part is found from the parity of what is left, so there is
+/// no table of reachable sums to build first, as there is for
+/// `Partitions::with_parts`. `Partitions::odd_parts` makes one of these.
+#[derive(Debug)]
+pub struct OddParts {
+    n: usize,
+    odd: Odd,
+    p: Vec<usize>,
+    state: State,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum State {
+    Fresh,
+    Going,
+    Done,
+}
+
+impl OddParts {
+    /// Makes a new iterator.
+    #[inline]
+    pub fn new(n: usize) -> OddParts {
+        OddParts::recycle(n, Vec::new())
+    }
+
+    /// Makes a new iterator over the partitions of `n` into distinct odd
+    /// parts, in the same order.
+    #[inline]
+    pub fn distinct(n: usize) -> OddParts {
+        let mut pp = OddParts::new(n);
+        pp.odd.strict = true;
+        pp
+    }
+
+    /// Makes a new iterator, trying to avoid allocations.
+    ///
+    /// Any vector can be passed to this function, since its contents
+    /// will be cleared, but note that the vector will still reallocate
+    /// if its capacity is less than `n`.
+    #[inline]
+    pub fn recycle(n: usize, vec: Vec<usize>) -> OddParts {
+        OddParts {
+            n,
+            odd: Odd { strict: false },
+            p: vec,
+            state: State::Fresh,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Restarts the iterator at the first partition of `n`, with or
+    /// without distinct parts as before.
+    #[inline]
+    pub fn reinit(&mut self, n: usize) {
+        self.n = n;
+        self.p.clear();
+        self.state = State::Fresh;
+    }
+
+    /// Destroys the iterator and returns a vector for further use.
+    ///
+    /// You only need to call this function if you want to reuse the
+    /// vector for something else. Its contents will be in an undefined
+    /// state, and so cannot be relied upon.
+    #[inline]
+    pub fn end(self) -> Vec<usize> {
+        self.p
+    }
+}
+
+impl StreamingIterator for OddParts {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        match self.state {
+            State::Going => Some(&self.p),
+            _ => None,
+        }
+    }
+
+    fn advance(&mut self) {
+        let found = match self.state {
+            State::Fresh => start(&self.odd, self.n, &mut self.p),
+            State::Going => step(&self.odd, &mut self.p),
+            State::Done => false,
+        };
+        self.state = if found { State::Going } else { State::Done };
+    }
+}
+
+impl PartitionIterator for OddParts {
+    #[inline]
+    fn n(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn reinit(&mut self, n: usize) {
+        OddParts::reinit(self, n)
+    }
+
+    #[inline]
+    fn end(self) -> Vec<usize> {
+        OddParts::end(self)
+    }
+}
+
+/// Iterates over the self-conjugate partitions of `n`.
+///
+/// They come in the order of their diagonal hooks, read as partitions
+/// into distinct odd parts in the order of `Partitions`, which is not
+/// the order of `Partitions` itself. Each is written out afresh from its
+/// hooks, in time linear in its number of parts.
+#[derive(Debug)]
+pub struct SelfConjugate {
+    hooks: OddParts,
+    a: Vec<usize>,
+}
+
+impl SelfConjugate {
+    /// Makes a new iterator.
+    #[inline]
+    pub fn new(n: usize) -> SelfConjugate {
+        SelfConjugate::recycle(n, Vec::new())
+    }
+
+    /// Makes a new iterator, trying to avoid allocations.
+    ///
+    /// Any vector can be passed to this function, since its contents
+    /// will be cleared, but note that the vector will still reallocate
+    /// if its capacity is less than `n`.
+    #[inline]
+    pub fn recycle(n: usize, vec: Vec<usize>) -> SelfConjugate {
+        SelfConjugate {
+            hooks: OddParts::distinct(n),
+            a: vec,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Returns the hooks on the diagonal of the current partition, from
+    /// the smallest.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no current partition.
+    #[inline]
+    pub fn hooks(&self) -> &[usize] {
+        self.hooks.get().expect("no current partition")
+    }
+
+    /// Restarts the iterator at the first self-conjugate partition of
+    /// `n`.
+    #[inline]
+    pub fn reinit(&mut self, n: usize) {
+        self.hooks.reinit(n);
+        self.a.clear();
+    }
+
+    /// Destroys the iterator and returns a vector for further use.
+    ///
+    /// You only need to call this function if you want to reuse the
+    /// vector for something else. Its contents will be in an undefined
+    /// state, and so cannot be relied upon.
+    #[inline]
+    pub fn end(self) -> Vec<usize> {
+        self.a
+    }
+}
+
+impl StreamingIterator for SelfConjugate {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        self.hooks.get().map(|_| &self.a[..])
+    }
+
+    fn advance(&mut self) {
+        self.hooks.advance();
+        let h = match self.hooks.get() {
+            Some(h) => h,
+            None => return,
+        };
+        // Row i, counting from one at the largest, is i + (h_i - 1) / 2
+        // on the diagonal, and below it counts the rows above that reach
+        // its column. Both are written from the top and then reversed.
+        let a = &mut self.a;
+        a.clear();
+        let d = h.len();
+        a.extend(h.iter().rev().enumerate().map(|(i, &x)| i + 1 + x / 2));
+        let mut j = d;
+        for i in (d + 1)..(a.first().map_or(0, |&x| x) + 1) {
+            while a[j - 1] < i {
+                j -= 1;
+            }
+            a.push(j);
+        }
+        a.reverse();
+    }
+}
+
+impl PartitionIterator for SelfConjugate {
+    #[inline]
+    fn n(&self) -> usize {
+        self.hooks.n
+    }
+
+    #[inline]
+    fn reinit(&mut self, n: usize) {
+        SelfConjugate::reinit(self, n)
+    }
+
+    #[inline]
+    fn end(self) -> Vec<usize> {
+        SelfConjugate::end(self)
+    }
+}
+
+#[test]
+fn filtered() {
+    //! Compares against filtering `Partitions`, and the hooks with
+    //! `young::hook`.
+
+    use young::{hook, is_self_conjugate};
+    use Partitions;
+
+    let mut oo = OddParts::new(0);
+    let mut dd = OddParts::distinct(0);
+    let mut ss = SelfConjugate::new(0);
+    for n in 0..40 {
+        oo.reinit(n);
+        dd.reinit(n);
+        ss.reinit(n);
+        let mut conj = Vec::new();
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            if p.iter().all(|&x| x % 2 == 1) {
+                assert_eq!(oo.next(), Some(p));
+                if p.windows(2).all(|w| w[0] < w[1]) {
+                    assert_eq!(dd.next(), Some(p));
+                }
+            }
+            if is_self_conjugate(p) {
+                conj.push(p.to_vec());
+            }
+        }
+        assert_eq!((oo.next(), dd.next()), (None, None));
+
+        let mut seen = Vec::new();
+        while let Some(p) = ss.next().map(|p| p.to_vec()) {
+            let d = ss.hooks().len();
+            let diagonal: Vec<usize> = (0..d).rev().map(|i| hook(&p, i, i)).collect();
+            assert_eq!(ss.hooks(), &diagonal[..]);
+            seen.push(p);
+        }
+        assert_eq!(ss.next(), None);
+        seen.sort();
+        conj.sort();
+        assert_eq!(seen, conj);
+    }
+}
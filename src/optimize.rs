@@ -0,0 +1,239 @@
+//! Searching for the best partition without enumerating all of them.
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// Finds the partition of `n` that maximises `objective`.
+///
+/// The search builds partitions part by part, in the non-decreasing
+/// order used by `Partitions`, and calls both callbacks with the
+/// current prefix and the amount still to be distributed:
+///
+/// - `constraints(prefix, rest)` returns `false` to discard the prefix
+///   together with every partition that starts with it. It is also
+///   called on complete partitions, with `rest == 0`.
+/// - `bound(prefix, rest)` must return an upper bound of `objective`
+///   over all partitions that start with `prefix`. Prefixes whose bound
+///   does not beat the best value found so far are not explored.
+///
+/// Returns the best partition together with its value, or `None` if no
+/// partition satisfies the constraints. Ties are resolved in favour of
+/// the partition that comes first in enumeration order.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::optimize::optimize;
+///
+/// // The partition of 90 with the largest product of parts.
+/// let product = |p: &[usize]| p.iter().map(|&x| x as f64).product::<f64>();
+/// let (best, value) = optimize(
+///     90,
+///     |_, _| true,
+///     product,
+///     |p, rest| product(p) * 3f64.powf(rest as f64 / 3.0),
+/// ).unwrap();
+///
+/// assert_eq!(best, vec![3; 30]);
+/// assert_eq!(value, 3f64.powi(30));
+/// ```
+pub fn optimize<T, C, F, B>(
+    n: usize,
+    mut constraints: C,
+    mut objective: F,
+    mut bound: B,
+) -> Option<(Vec<usize>, T)>
+where
+    T: PartialOrd,
+    C: FnMut(&[usize], usize) -> bool,
+    F: FnMut(&[usize]) -> T,
+    B: FnMut(&[usize], usize) -> T,
+{
+    let mut a = Vec::new();
+    let mut rest = n;
+    let mut best: Option<(Vec<usize>, T)> = None;
+
+    'visit: loop {
+        let prune = if rest == 0 {
+            if constraints(&a, 0) {
+                let value = objective(&a);
+                let better = match best {
+                    Some((_, ref b)) => value > *b,
+                    None => true,
+                };
+                if better {
+                    best = Some((a.clone(), value));
+                }
+            }
+            true
+        } else if !constraints(&a, rest) {
+            true
+        } else {
+            match best {
+                Some((_, ref b)) => bound(&a, rest).partial_cmp(b) != Some(Ordering::Greater),
+                None => false,
+            }
+        };
+
+        if !prune {
+            // Descend to the first child: the smallest part that still
+            // leaves room for parts at least as large, or everything.
+            let m = a.last().cloned().unwrap_or(1);
+            let x = if 2 * m <= rest { m } else { rest };
+            a.push(x);
+            rest -= x;
+            continue;
+        }
+
+        // Backtrack to the next sibling of the deepest unfinished node.
+        loop {
+            let x = match a.pop() {
+                Some(x) => x,
+                None => return best,
+            };
+            rest += x;
+
+            if x < rest {
+                let x = if 2 * (x + 1) <= rest { x + 1 } else { rest };
+                a.push(x);
+                rest -= x;
+                continue 'visit;
+            }
+        }
+    }
+}
+
+/// Computes Landau's function `g(n)`, the largest order of a
+/// permutation of `n` things, which is the largest least common multiple
+/// of the parts of a partition of `n`. Returns it together with a
+/// partition that attains it.
+///
+/// Some best partition is always made of powers of distinct primes,
+/// padded out with ones, so rather than searching all partitions this
+/// only packs prime powers into a budget of `n`, one prime at a time.
+///
+/// Returns `None` if `g(n)` does not fit in a `u128`, which first
+/// happens somewhere past `n = 1000`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::optimize::landau;
+///
+/// assert_eq!(landau(10), Some((30, vec![2, 3, 5])));
+/// assert_eq!(landau(12), Some((60, vec![3, 4, 5])));
+/// ```
+pub fn landau(n: usize) -> Option<(u128, Vec<usize>)> {
+    let mut sieve = vec![true; n + 1];
+    let mut primes = Vec::new();
+    for p in 2..(n + 1) {
+        if sieve[p] {
+            primes.push(p);
+            for q in ((p * p)..(n + 1)).step_by(p) {
+                sieve[q] = false;
+            }
+        }
+    }
+
+    // best[s] is the largest product of powers of distinct primes, from
+    // those seen so far, adding up to exactly s, and choice[i][s] is the
+    // power of the ith prime that it uses, or zero.
+    let mut best: Vec<Option<u128>> = vec![None; n + 1];
+    best[0] = Some(1);
+    let mut choice = Vec::with_capacity(primes.len());
+    for &p in &primes {
+        let mut row = vec![0; n + 1];
+        for s in (0..(n + 1)).rev() {
+            let mut q = p;
+            while q <= s {
+                if let Some(b) = best[s - q] {
+                    let v = b.checked_mul(q as u128)?;
+                    if best[s].is_none_or(|x| v > x) {
+                        best[s] = Some(v);
+                        row[s] = q;
+                    }
+                }
+                q *= p;
+            }
+        }
+        choice.push(row);
+    }
+
+    // The first sum to reach the maximum needs the fewest ones.
+    let mut s = 0;
+    for t in 0..(n + 1) {
+        if best[t] > best[s] {
+            s = t;
+        }
+    }
+    let g = best[s].unwrap();
+
+    let mut parts = vec![1; n - s];
+    for row in choice.iter().rev() {
+        if row[s] > 0 {
+            parts.push(row[s]);
+            s -= row[s];
+        }
+    }
+    parts.sort_unstable();
+    Some((g, parts))
+}
+
+#[test]
+fn brute_force() {
+    //! Compares the search against a maximum over all partitions.
+
+    use Partitions;
+
+    let objective = |p: &[usize]| {
+        p.iter().enumerate().map(|(i, &x)| (x * (i + 3)) % 7).sum::<usize>()
+    };
+
+    for n in 0..25 {
+        let mut expected = None;
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            if p.iter().all(|&x| x != 2) {
+                let v = objective(p);
+                if expected.as_ref().is_none_or(|&(_, e)| v > e) {
+                    expected = Some((p.to_vec(), v));
+                }
+            }
+        }
+
+        let got = optimize(
+            n,
+            |p, _| p.last() != Some(&2),
+            objective,
+            |p, rest| objective(p) + 6 * rest,
+        );
+        assert_eq!(got, expected);
+    }
+}
+
+#[test]
+fn landau_brute_force() {
+    //! Compares Landau's function against a maximum over all partitions.
+
+    use Partitions;
+
+    fn gcd(a: u128, b: u128) -> u128 {
+        if b == 0 { a } else { gcd(b, a % b) }
+    }
+
+    for n in 0..45 {
+        let mut g = 0;
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            let l = p.iter().fold(1, |l, &x| l / gcd(l, x as u128) * x as u128);
+            g = g.max(l);
+        }
+
+        let (got, parts) = landau(n).unwrap();
+        assert_eq!(got, g);
+        assert_eq!(parts.iter().sum::<usize>(), n);
+        assert_eq!(parts.iter().fold(1, |l, &x| l / gcd(l, x as u128) * x as u128), g);
+    }
+    assert!(landau(1000).is_some());
+    assert!(landau(3000).is_none());
+}
@@ -0,0 +1,269 @@
+//! Partitions packed into a single word.
+//!
+//! The boundary of a Young diagram, walked from the bottom left to the
+//! top right, is a path of `λ_1` steps east and `ℓ` steps north, where
+//! `λ_1` is the largest part and `ℓ` the number of parts. Writing east
+//! as 0 and north as 1, least significant bit first, with one more 1 on
+//! top to mark where the path ends, puts any partition with
+//! `λ_1 + ℓ ≤ 127` in a `u128`. That covers every partition of 126 or
+//! less.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A partition stored as its boundary path in a `u128`.
+///
+/// This takes 16 bytes whatever the partition. Size, number of parts,
+/// largest part and conjugation are found from the word with bit
+/// tricks, but containment walks the parts, in time linear in their
+/// number. Equality and hashing compare the words, and the order is
+/// that of the words, which is fixed but is not the order of
+/// `Partitions`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::packed::PackedPartition;
+///
+/// let p = PackedPartition::new(&[1, 2, 2]).unwrap();
+/// assert_eq!(p.size(), 5);
+/// assert_eq!(p.len(), 3);
+/// assert_eq!(p.largest_part(), 2);
+/// assert_eq!(p.conjugate().to_vec(), vec![2, 3]);
+/// assert!(p.contains(&PackedPartition::new(&[1, 2]).unwrap()));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PackedPartition {
+    bits: u128,
+}
+
+/// Bits whose position has bit `b` set, for `b` from 0 to 6.
+const POSITION_BITS: [u128; 7] = {
+    let mut masks = [0u128; 7];
+    let mut j = 0;
+    while j < 128 {
+        let mut b = 0;
+        while b < 7 {
+            if j >> b & 1 == 1 {
+                masks[b] |= 1 << j;
+            }
+            b += 1;
+        }
+        j += 1;
+    }
+    masks
+};
+
+impl PackedPartition {
+    /// The empty partition.
+    pub const EMPTY: PackedPartition = PackedPartition { bits: 1 };
+
+    /// Packs the partition `p`, or returns `None` if its largest part
+    /// and number of parts add up to more than 127.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not in non-decreasing order, or has a part of
+    /// zero.
+    pub fn new(p: &[usize]) -> Option<PackedPartition> {
+        assert!(p.first() != Some(&0), "parts must be positive");
+        assert!(p.windows(2).all(|w| w[0] <= w[1]), "parts must be in non-decreasing order");
+        if p.last().map_or(0, |&x| x) + p.len() > 127 {
+            return None;
+        }
+
+        let (mut bits, mut pos, mut last) = (0u128, 0, 0);
+        for &x in p {
+            pos += x - last;
+            bits |= 1 << pos;
+            pos += 1;
+            last = x;
+        }
+        Some(PackedPartition { bits: bits | 1 << pos })
+    }
+
+    /// Returns the word holding the path.
+    #[inline]
+    pub fn to_bits(self) -> u128 {
+        self.bits
+    }
+
+    /// Rebuilds a partition from the word holding its path, or returns
+    /// `None` if the word is not the path of any partition.
+    #[inline]
+    pub fn from_bits(bits: u128) -> Option<PackedPartition> {
+        if bits == 0 {
+            return None;
+        }
+        let p = PackedPartition { bits };
+        let len = p.path_len();
+        // The path starts east and ends north, unless it is empty.
+        let valid = bits == 1 || (bits & 1 == 0 && bits >> (len - 1) & 1 == 1);
+        if valid { Some(p) } else { None }
+    }
+
+    /// The length of the path, not counting the end marker.
+    #[inline]
+    fn path_len(self) -> u32 {
+        127 - self.bits.leading_zeros()
+    }
+
+    /// The path without its end marker.
+    #[inline]
+    fn path(self) -> u128 {
+        self.bits ^ 1 << self.path_len()
+    }
+
+    /// Returns whether this is the empty partition.
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.bits == 1
+    }
+
+    /// Returns the number of parts.
+    #[inline]
+    pub fn len(self) -> usize {
+        self.bits.count_ones() as usize - 1
+    }
+
+    /// Returns the largest part, or zero for the empty partition.
+    #[inline]
+    pub fn largest_part(self) -> usize {
+        self.path_len() as usize - self.len()
+    }
+
+    /// Returns the number being partitioned.
+    ///
+    /// Each step north passes a row as long as the number of steps east
+    /// before it, which is its position less the steps north before it.
+    /// The positions are added up a bit of the position at a time.
+    #[inline]
+    pub fn size(self) -> usize {
+        let path = self.path();
+        let sum: u32 = (0..7).map(|b| (path & POSITION_BITS[b]).count_ones() << b).sum();
+        let k = self.len();
+        sum as usize - k * (k.saturating_sub(1)) / 2
+    }
+
+    /// Returns the conjugate partition, whose path is the reverse of
+    /// this one with the steps turned.
+    #[inline]
+    pub fn conjugate(self) -> PackedPartition {
+        let len = self.path_len();
+        if len == 0 {
+            return self;
+        }
+        let turned = !self.bits & ((1 << len) - 1);
+        PackedPartition { bits: turned.reverse_bits() >> (128 - len) | 1 << len }
+    }
+
+    /// Returns whether the Young diagram of `other` fits inside this
+    /// one.
+    ///
+    /// Unlike the other queries this is not done on the word as a
+    /// whole: it compares the parts in turn, in time linear in the
+    /// number of parts.
+    pub fn contains(self, other: &PackedPartition) -> bool {
+        let (k, m) = (self.len(), other.len());
+        if m > k || other.largest_part() > self.largest_part() {
+            return false;
+        }
+        // Line up the largest parts of both.
+        self.parts().skip(k - m).zip(other.parts()).all(|(x, y)| y <= x)
+    }
+
+    /// Iterates over the parts in non-decreasing order.
+    #[inline]
+    pub fn parts(self) -> Parts {
+        Parts {
+            path: self.path(),
+            left: self.len(),
+            part: 0,
+        }
+    }
+
+    /// Returns the parts in non-decreasing order.
+    pub fn to_vec(self) -> Vec<usize> {
+        self.parts().collect()
+    }
+}
+
+impl fmt::Debug for PackedPartition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.parts()).finish()
+    }
+}
+
+/// Iterates over the parts of a `PackedPartition`, made by `parts`.
+#[derive(Debug, Clone)]
+pub struct Parts {
+    path: u128,
+    left: usize,
+    part: usize,
+}
+
+impl Iterator for Parts {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        if self.left == 0 {
+            return None;
+        }
+        let tz = self.path.trailing_zeros();
+        self.part += tz as usize;
+        self.path >>= tz;
+        self.path >>= 1;
+        self.left -= 1;
+        Some(self.part)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.left, Some(self.left))
+    }
+}
+
+impl ExactSizeIterator for Parts {}
+
+#[test]
+fn packing() {
+    //! Compares every operation against the unpacked partitions.
+
+    use young::conjugate;
+    use Partitions;
+
+    let mut conj = Vec::new();
+    let mut all = Vec::new();
+    for n in 0..13 {
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            let q = PackedPartition::new(p).unwrap();
+            assert_eq!(q.to_vec(), p);
+            assert_eq!(PackedPartition::from_bits(q.to_bits()), Some(q));
+            assert_eq!((q.size(), q.len(), q.is_empty()), (n, p.len(), n == 0));
+            assert_eq!(q.largest_part(), p.last().map_or(0, |&x| x));
+            conjugate(p, &mut conj);
+            assert_eq!(q.conjugate().to_vec(), conj);
+            all.push((p.to_vec(), q));
+        }
+    }
+
+    for (p, q) in &all {
+        for (r, s) in &all {
+            let inside = r.len() <= p.len() && r.iter().rev().zip(p.iter().rev()).all(|(a, b)| a <= b);
+            assert_eq!(q.contains(s), inside);
+        }
+    }
+
+    let big: Vec<usize> = vec![1; 126];
+    assert_eq!(PackedPartition::new(&big).unwrap().size(), 126);
+    assert_eq!(PackedPartition::new(&[126]).unwrap().conjugate().to_vec(), big);
+    assert_eq!(PackedPartition::new(&[1; 127]), None);
+    assert_eq!(PackedPartition::new(&[100; 28]), None);
+    assert_eq!(PackedPartition::from_bits(0), None);
+    assert_eq!(PackedPartition::from_bits(0b1010), None);
+    assert_eq!(PackedPartition::from_bits(0b101), None);
+    assert_eq!(PackedPartition::from_bits(0b110).map(|p| p.to_vec()), Some(vec![1]));
+    assert_eq!(PackedPartition::EMPTY.to_vec(), Vec::<usize>::new());
+}
@@ -0,0 +1,415 @@
+//! An owned partition type, and partitions in multiplicity form.
+//!
+//! A partition of a large number mostly repeats a few parts, so it can
+//! be far shorter written as pairs `(part, multiplicity)`, with the parts
+//! increasing. `PartitionMults` lists the partitions of `n` in that form,
+//! in the order of `Partitions`, and `Partition` converts between the two
+//! forms.
+//!
+//! # Examples
+//!
+//! ```
+//! use integer_partitions::partition::{Partition, PartitionMults};
+//!
+//! let mut pp = PartitionMults::new(4);
+//! assert_eq!(pp.next(), Some(&[(1, 4)][..]));
+//! assert_eq!(pp.next(), Some(&[(1, 2), (2, 1)][..]));
+//! assert_eq!(pp.next(), Some(&[(1, 1), (3, 1)][..]));
+//! assert_eq!(pp.next(), Some(&[(2, 2)][..]));
+//! assert_eq!(pp.next(), Some(&[(4, 1)][..]));
+//! assert_eq!(pp.next(), None);
+//!
+//! let p = Partition::from_mults(&[(1, 2), (3, 1)]);
+//! assert_eq!(p.parts(), &[1, 1, 3]);
+//! assert_eq!(p.to_mults(), vec![(1, 2), (3, 1)]);
+//! ```
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use poset::{self, Dominance};
+use streaming_iterator::StreamingIterator;
+use young::{self, conjugate};
+
+/// A partition that owns its parts, kept in non-decreasing order.
+///
+/// Partitions are compared by dominance, so `a <= b` means that `b`
+/// dominates `a`, some pairs are not comparable at all, and partitions
+/// of different numbers never are. `Lex` orders them totally instead,
+/// for sorting or as the keys of a map.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::partition::{Lex, Partition};
+///
+/// let a = Partition::new(&[1, 1, 4]);
+/// let b = Partition::new(&[3, 3]);
+/// assert!(Partition::new(&[2, 2, 2]) < b);
+/// assert_eq!(a.partial_cmp(&b), None);
+/// assert!(Lex(a) < Lex(b));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Partition {
+    parts: Vec<usize>,
+}
+
+impl Partition {
+    /// Makes a partition with the parts `p`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not in non-decreasing order, or has a part of
+    /// zero.
+    pub fn new(p: &[usize]) -> Partition {
+        assert!(p.first() != Some(&0), "parts must be positive");
+        assert!(p.windows(2).all(|w| w[0] <= w[1]), "parts must be in non-decreasing order");
+        Partition { parts: p.to_vec() }
+    }
+
+    /// Makes a partition from pairs `(part, multiplicity)`.
+    ///
+    /// Pairs with a multiplicity of zero are skipped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parts are not increasing, or one is zero.
+    pub fn from_mults(mults: &[(usize, usize)]) -> Partition {
+        assert!(mults.first().is_none_or(|m| m.0 > 0), "parts must be positive");
+        assert!(mults.windows(2).all(|w| w[0].0 < w[1].0), "parts must be increasing");
+        let mut parts = Vec::with_capacity(mults.iter().map(|m| m.1).sum());
+        for &(x, c) in mults {
+            parts.extend(::core::iter::repeat_n(x, c));
+        }
+        Partition { parts }
+    }
+
+    /// Returns the parts, in non-decreasing order.
+    #[inline]
+    pub fn parts(&self) -> &[usize] {
+        &self.parts
+    }
+
+    /// Returns the parts as a vector.
+    #[inline]
+    pub fn into_vec(self) -> Vec<usize> {
+        self.parts
+    }
+
+    /// Returns the pairs `(part, multiplicity)`, with the parts
+    /// increasing.
+    pub fn to_mults(&self) -> Vec<(usize, usize)> {
+        self.parts.chunk_by(|a, b| a == b).map(|w| (w[0], w.len())).collect()
+    }
+
+    /// Returns the number being partitioned.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.parts.iter().sum()
+    }
+
+    /// Returns the number of parts.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.parts.len()
+    }
+
+    /// Returns whether this is the empty partition.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// Returns the largest part, or zero for the empty partition.
+    #[inline]
+    pub fn largest_part(&self) -> usize {
+        self.parts.last().map_or(0, |&x| x)
+    }
+
+    /// Returns the number of different parts.
+    pub fn distinct_parts(&self) -> usize {
+        self.parts.chunk_by(|a, b| a == b).count()
+    }
+
+    /// Returns the conjugate partition.
+    pub fn conjugate(&self) -> Partition {
+        let mut parts = Vec::new();
+        conjugate(&self.parts, &mut parts);
+        Partition { parts }
+    }
+
+    /// Returns whether the partition is its own conjugate.
+    #[inline]
+    pub fn is_self_conjugate(&self) -> bool {
+        young::is_self_conjugate(&self.parts)
+    }
+
+    /// Returns the arm of the cell in row `i` and column `j`, like
+    /// `young::arm`.
+    #[inline]
+    pub fn arm(&self, i: usize, j: usize) -> usize {
+        young::arm(&self.parts, i, j)
+    }
+
+    /// Returns the leg of the cell in row `i` and column `j`, like
+    /// `young::leg`.
+    #[inline]
+    pub fn leg(&self, i: usize, j: usize) -> usize {
+        young::leg(&self.parts, i, j)
+    }
+
+    /// Returns the hook length of the cell in row `i` and column `j`,
+    /// like `young::hook`.
+    #[inline]
+    pub fn hook(&self, i: usize, j: usize) -> usize {
+        young::hook(&self.parts, i, j)
+    }
+
+    /// Returns the side of the Durfee square.
+    #[inline]
+    pub fn durfee_side(&self) -> usize {
+        young::durfee_side(&self.parts)
+    }
+
+    /// Returns whether `self ⊴ other` in the dominance order, that is
+    /// `poset::dominates(other, self)`, or `self <= other`.
+    #[inline]
+    pub fn is_dominated_by(&self, other: &Partition) -> bool {
+        poset::dominates(&other.parts, &self.parts)
+    }
+
+    /// Returns the meet of the two in the dominance order, like
+    /// `poset::dominance_meet`.
+    #[inline]
+    pub fn dominance_meet(&self, other: &Partition) -> Partition {
+        Partition { parts: poset::dominance_meet(&self.parts, &other.parts) }
+    }
+
+    /// Returns the join of the two in the dominance order, like
+    /// `poset::dominance_join`.
+    #[inline]
+    pub fn dominance_join(&self, other: &Partition) -> Partition {
+        Partition { parts: poset::dominance_join(&self.parts, &other.parts) }
+    }
+
+    /// Returns how many times `part` appears.
+    pub fn multiplicity(&self, part: usize) -> usize {
+        let lo = self.parts.partition_point(|&x| x < part);
+        let hi = self.parts.partition_point(|&x| x <= part);
+        hi - lo
+    }
+}
+
+impl AsRef<[usize]> for Partition {
+    #[inline]
+    fn as_ref(&self) -> &[usize] {
+        &self.parts
+    }
+}
+
+impl PartialOrd for Partition {
+    #[inline]
+    fn partial_cmp(&self, other: &Partition) -> Option<Ordering> {
+        Dominance(&self.parts).partial_cmp(&Dominance(&other.parts))
+    }
+}
+
+/// Orders partitions as `Partitions` lists them, which is the
+/// lexicographic order of their parts from the smallest.
+///
+/// This is a total order, for sorting or as the keys of a map, while
+/// `Partition` itself is only ordered by dominance. The two disagree:
+/// `[2, 2, 2]` comes after `[1, 1, 4]` here, but is dominated by it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Lex<P>(pub P);
+
+impl<P: AsRef<[usize]> + Eq> PartialOrd for Lex<P> {
+    #[inline]
+    fn partial_cmp(&self, other: &Lex<P>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P: AsRef<[usize]> + Eq> Ord for Lex<P> {
+    #[inline]
+    fn cmp(&self, other: &Lex<P>) -> Ordering {
+        self.0.as_ref().cmp(other.0.as_ref())
+    }
+}
+
+/// Iterates over the partitions of `n` as pairs `(part, multiplicity)`,
+/// in the order of `Partitions`.
+///
+/// The successor of a partition only ever changes its last two parts
+/// and then adds a run of equal parts and one more part, which in this
+/// form is a change to the last few pairs. So each partition takes
+/// constant time, not just constant amortized time, and the partitions
+/// of a large `n` take little memory.
+#[derive(Debug)]
+pub struct PartitionMults {
+    n: usize,
+    runs: Vec<(usize, usize)>,
+    state: State,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum State {
+    Fresh,
+    Going,
+    Done,
+}
+
+impl PartitionMults {
+    /// Makes a new iterator.
+    pub fn new(n: usize) -> PartitionMults {
+        PartitionMults {
+            n,
+            runs: Vec::new(),
+            state: State::Fresh,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[(usize, usize)]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Returns the current partition as a `Partition`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no current partition.
+    pub fn to_partition(&self) -> Partition {
+        Partition::from_mults(self.get().expect("no current partition"))
+    }
+
+    /// Takes one copy of the last part off, and returns it.
+    #[inline]
+    fn pop(&mut self) -> usize {
+        let last = self.runs.len() - 1;
+        let (x, c) = self.runs[last];
+        if c == 1 {
+            self.runs.pop();
+        } else {
+            self.runs[last].1 = c - 1;
+        }
+        x
+    }
+
+    /// Puts `c` copies of `x`, no smaller than the last part, on the end.
+    #[inline]
+    fn push(&mut self, x: usize, c: usize) {
+        match self.runs.last_mut() {
+            Some(r) if r.0 == x => r.1 += c,
+            _ => self.runs.push((x, c)),
+        }
+    }
+}
+
+impl StreamingIterator for PartitionMults {
+    type Item = [(usize, usize)];
+
+    #[inline]
+    fn get(&self) -> Option<&[(usize, usize)]> {
+        match self.state {
+            State::Going => Some(&self.runs),
+            _ => None,
+        }
+    }
+
+    fn advance(&mut self) {
+        match self.state {
+            State::Fresh => {
+                if self.n > 0 {
+                    self.runs.push((1, self.n));
+                }
+                self.state = State::Going;
+            }
+            State::Going => {
+                if self.runs.len() < 2 && self.runs.first().is_none_or(|r| r.1 < 2) {
+                    self.state = State::Done;
+                    return;
+                }
+                // Grow the second last part by one, and share what it
+                // and the last part leave out as copies of it and one
+                // larger part at the end.
+                let last = self.pop();
+                let prev = self.pop();
+                let s = prev + last;
+                let x = prev + 1;
+                let c = s / x - 1;
+                if c > 0 {
+                    self.push(x, c);
+                }
+                self.push(s - c * x, 1);
+            }
+            State::Done => {}
+        }
+    }
+}
+
+#[test]
+fn mults() {
+    //! Compares against `Partitions`, and the conversions both ways.
+
+    use Partitions;
+
+    for n in 0..30 {
+        let mut pp = Partitions::new(n);
+        let mut mm = PartitionMults::new(n);
+        while let Some(p) = pp.next() {
+            let m = mm.next().unwrap().to_vec();
+            assert!(m.iter().all(|r| r.1 > 0));
+            let q = Partition::new(p);
+            assert_eq!(q.to_mults(), m);
+            assert_eq!(Partition::from_mults(&m), q);
+            assert_eq!(mm.to_partition(), q);
+            assert_eq!(q.distinct_parts(), m.len());
+            for &(x, c) in &m {
+                assert_eq!(q.multiplicity(x), c);
+            }
+            assert_eq!(q.multiplicity(n + 1), 0);
+            assert_eq!(q.conjugate().conjugate(), q);
+            assert_eq!(q.is_self_conjugate(), q.conjugate() == q);
+            assert!(q.is_dominated_by(&Partition::new(&[n][..n.min(1)])));
+            assert!(q <= Partition::new(&[n][..n.min(1)]));
+            assert!(Partition::new(&vec![1; n]) <= q);
+            assert_eq!(Partition::new(&[n][..n.min(1)]).is_dominated_by(&q), p.len() <= 1);
+            let ones = Partition::new(&vec![1; n]);
+            assert_eq!((q.dominance_meet(&ones), q.dominance_join(&ones)), (ones.clone(), q.clone()));
+            assert_eq!((q.size(), q.len(), q.largest_part()), (n, p.len(), p.last().map_or(0, |&x| x)));
+        }
+        assert_eq!(mm.next(), None);
+        assert_eq!(mm.next(), None);
+    }
+
+    assert_eq!(Partition::from_mults(&[(1, 0), (2, 2), (5, 0)]).parts(), &[2, 2]);
+}
+
+#[test]
+fn order() {
+    //! Compares the order of `Partition` with `poset::dominates`, and
+    //! `Lex` with the order of `Partitions`.
+
+    use Partitions;
+
+    for n in 0..12 {
+        let mut all = Vec::new();
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            all.push(Partition::new(p));
+        }
+        for a in &all {
+            for b in &all {
+                assert_eq!(a <= b, poset::dominates(b.parts(), a.parts()));
+                assert_eq!(a >= b, poset::dominates(a.parts(), b.parts()));
+                let incomparable = !poset::dominates(a.parts(), b.parts()) && !poset::dominates(b.parts(), a.parts());
+                assert_eq!(a.partial_cmp(b).is_none(), incomparable);
+            }
+        }
+        assert!(all.windows(2).all(|w| Lex(&w[0]) < Lex(&w[1])));
+    }
+    assert_eq!(Partition::new(&[1]).partial_cmp(&Partition::new(&[2])), None);
+}
@@ -0,0 +1,211 @@
+//! The Plancherel measure on partitions.
+//!
+//! The Plancherel measure gives each partition `λ` of `n` the
+//! probability `(f^λ)² / n!`, where `f^λ` is the number of standard
+//! tableaux of shape `λ`. These add up to one because the squares of the
+//! dimensions of the irreducible representations of the symmetric group
+//! add up to its order. It is also the law of the shape that the
+//! Robinson–Schensted correspondence gives a uniformly random
+//! permutation.
+
+use core::convert::TryFrom;
+
+#[cfg(feature = "num-bigint")]
+use num_bigint::{BigInt, BigUint};
+
+use tableau::{dimension, hook_lengths, Dimensions};
+
+/// Returns the Plancherel probability of `p` as a fraction in lowest
+/// terms, or `None` if its numerator or denominator does not fit in a
+/// `u128`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::plancherel::probability;
+///
+/// // f^λ = 2 for λ = 2 + 1, out of 3! = 6.
+/// assert_eq!(probability(&[1, 2]), Some((2, 3)));
+/// ```
+pub fn probability(p: &[usize]) -> Option<(u128, u128)> {
+    // (n! / ∏ h)² / n! = n! / ∏ h²
+    let mut f = dimension(p);
+    for h in hook_lengths(p).iter().flat_map(|row| row.iter()) {
+        f.divide(*h);
+    }
+    f.to_fraction()
+}
+
+/// Returns the Plancherel probability of `p` as a fraction in lowest
+/// terms.
+///
+/// This is only available with the `num-bigint` feature.
+#[cfg(feature = "num-bigint")]
+pub fn probability_big(p: &[usize]) -> (BigUint, BigUint) {
+    // (n! / ∏ h)² / n! = n! / ∏ h²
+    let mut f = dimension(p);
+    for h in hook_lengths(p).iter().flat_map(|row| row.iter()) {
+        f.divide(*h);
+    }
+    f.to_big_fraction()
+}
+
+/// Returns the Plancherel probability of `p` as a float.
+///
+/// This adds up logarithms, `ln n! - 2 Σ ln h(c)`, so it stays accurate
+/// to about twelve digits for any size, and underflows to zero only for
+/// probabilities below `10⁻³⁰⁸`.
+#[cfg(feature = "std")]
+pub fn probability_f64(p: &[usize]) -> f64 {
+    let n: usize = p.iter().sum();
+    let mut log = (2..(n + 1)).map(|k| (k as f64).ln()).sum::<f64>();
+    for h in hook_lengths(p).iter().flat_map(|row| row.iter()) {
+        log -= 2.0 * (*h as f64).ln();
+    }
+    log.exp()
+}
+
+/// Returns the expectation of `stat` under the Plancherel measure on the
+/// partitions of `n`, as a float.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::plancherel::expectation;
+///
+/// // The expected first row grows like 2√n, from below.
+/// let e = expectation(20, |p| *p.last().unwrap() as f64);
+/// assert!((e - 6.6149).abs() < 0.0001);
+/// assert!(e < 2.0 * 20f64.sqrt());
+/// ```
+#[cfg(feature = "std")]
+pub fn expectation<F: FnMut(&[usize]) -> f64>(n: usize, mut stat: F) -> f64 {
+    let mut d = Dimensions::new(n);
+    let mut sum = 0.0;
+    while let Some(p) = d.next() {
+        sum += probability_f64(p) * stat(p);
+    }
+    sum
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// Returns the expectation of an integer `stat` under the Plancherel
+/// measure on the partitions of `n`, exactly, as a fraction in lowest
+/// terms.
+///
+/// This sums `(f^λ)² stat(λ)` over the partitions and divides by `n!`,
+/// and returns `None` if the sum does not fit, which can first happen
+/// from about `n = 30`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::plancherel::expectation_exact;
+///
+/// assert_eq!(expectation_exact(3, |p| p.len() as i128), Some((2, 1)));
+/// assert_eq!(expectation_exact(4, |p| p.len() as i128), Some((29, 12)));
+/// ```
+pub fn expectation_exact<F: FnMut(&[usize]) -> i128>(n: usize, mut stat: F) -> Option<(i128, u128)> {
+    let mut d = Dimensions::new(n);
+    let mut sum: i128 = 0;
+    while let Some(p) = d.next() {
+        let s = stat(p);
+        let f = i128::try_from(d.dimension()?).ok()?;
+        sum = sum.checked_add(f.checked_mul(f)?.checked_mul(s)?)?;
+    }
+
+    let factorial = (1..(n as u128 + 1)).try_fold(1u128, |f, k| f.checked_mul(k))?;
+    let g = gcd(sum.unsigned_abs(), factorial);
+    Some((sum / g as i128, factorial / g))
+}
+
+/// Returns the expectation of an integer `stat` under the Plancherel
+/// measure on the partitions of `n`, exactly, as a fraction in lowest
+/// terms.
+///
+/// This is only available with the `num-bigint` feature.
+#[cfg(feature = "num-bigint")]
+pub fn expectation_big<F: FnMut(&[usize]) -> i64>(n: usize, mut stat: F) -> (BigInt, BigUint) {
+    let mut d = Dimensions::new(n);
+    let mut sum = BigInt::from(0);
+    while let Some(p) = d.next().map(|p| p.to_vec()) {
+        let f = BigInt::from(d.dimension_big());
+        sum += &f * &f * stat(&p);
+    }
+
+    let mut factorial = BigUint::from(1u32);
+    for k in 2..(n as u64 + 1) {
+        factorial *= k;
+    }
+    let (mut a, mut b) = (sum.magnitude().clone(), factorial.clone());
+    while b != BigUint::from(0u32) {
+        let r = &a % &b;
+        a = b;
+        b = r;
+    }
+    (sum / BigInt::from(a.clone()), factorial / a)
+}
+
+#[test]
+fn plancherel_measure() {
+    //! Tests that the probabilities add up to one and agree with each
+    //! other, and that conjugation preserves the measure.
+
+    use tableau::count_standard;
+    use young::conjugate;
+    use Partitions;
+
+    let mut conj = Vec::new();
+    for n in 0..16 {
+        let factorial: u128 = (1..(n as u128 + 1)).product();
+        let mut sum = 0u128;
+        #[cfg(feature = "std")]
+        let mut total = 0.0;
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            let f = count_standard(p).unwrap();
+            let (a, b) = probability(p).unwrap();
+            assert_eq!(a * factorial, f * f * b);
+            assert_eq!(gcd(a, b), 1);
+            sum += f * f;
+
+            #[cfg(feature = "std")]
+            {
+                let x = probability_f64(p);
+                assert!((x - a as f64 / b as f64).abs() < 1e-12);
+                total += x;
+            }
+
+            conjugate(p, &mut conj);
+            assert_eq!(probability(&conj), Some((a, b)));
+        }
+        assert_eq!(sum, factorial);
+        #[cfg(feature = "std")]
+        assert!((total - 1.0).abs() < 1e-12);
+
+        assert_eq!(expectation_exact(n, |_| 1), Some((1, 1)));
+        let rows = expectation_exact(n, |p| p.len() as i128).unwrap();
+        assert_eq!(expectation_exact(n, |p| p.last().map_or(0, |&x| x as i128)), Some(rows));
+        #[cfg(feature = "std")]
+        {
+            let e = expectation(n, |p| p.len() as f64);
+            assert!((e - rows.0 as f64 / rows.1 as f64).abs() < 1e-9);
+        }
+
+        #[cfg(feature = "num-bigint")]
+        {
+            let (a, b) = expectation_big(n, |p| p.len() as i64);
+            assert_eq!((a, b), (BigInt::from(rows.0), BigUint::from(rows.1)));
+            let (a, b) = probability_big(&[n]);
+            assert_eq!((a, b), (BigUint::from(1u32), BigUint::from(factorial)));
+        }
+    }
+}
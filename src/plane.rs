@@ -0,0 +1,425 @@
+//! Plane partitions in a box.
+//!
+//! A plane partition in an `a × b × c` box is an `a × b` matrix of
+//! integers between `0` and `c`, weakly decreasing along each row and
+//! each column. They are stored as a list of rows.
+//!
+//! Besides sampling, this module counts the plane partitions in a box
+//! with each of the ten classical symmetries, by their product formulas.
+
+#[cfg(feature = "rand")]
+use alloc::vec::Vec;
+#[cfg(feature = "rand")]
+use rand::Rng;
+
+use factor::Product;
+
+// Counting functions return `None` when the count does not fit in a
+// `u128`. The formulas are those collected by Stanley, "Symmetries of
+// plane partitions", and by Krattenthaler, "Plane partitions in the
+// work of Richard Stanley and his school".
+
+/// Multiplies `f` by MacMahon's count of plane partitions in an
+/// `a × b × c` box, `∏ (i + j + c - 1) / (i + j - 1)` over `1 ≤ i ≤ a`
+/// and `1 ≤ j ≤ b`.
+fn macmahon(f: &mut Product, a: usize, b: usize, c: usize) {
+    for i in 1..(a + 1) {
+        for j in 1..(b + 1) {
+            f.times(i + j + c - 1);
+            f.divide(i + j - 1);
+        }
+    }
+}
+
+/// Counts the plane partitions in an `a × b × c` box.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::plane::count_in_box;
+///
+/// assert_eq!(count_in_box(2, 2, 2), Some(20));
+/// assert_eq!(count_in_box(1, 1, 9), Some(10));
+/// ```
+pub fn count_in_box(a: usize, b: usize, c: usize) -> Option<u128> {
+    let mut f = Product::new();
+    macmahon(&mut f, a, b, c);
+    f.to_u128()
+}
+
+/// Counts the symmetric plane partitions in an `n × n × c` box, those
+/// equal to their transpose.
+pub fn count_symmetric(n: usize, c: usize) -> Option<u128> {
+    let mut f = Product::new();
+    for i in 1..(n + 1) {
+        f.times(2 * i + c - 1);
+        f.divide(2 * i - 1);
+        for j in (i + 1)..(n + 1) {
+            f.times(i + j + c - 1);
+            f.divide(i + j - 1);
+        }
+    }
+    f.to_u128()
+}
+
+/// Counts the cyclically symmetric plane partitions in an `n × n × n`
+/// box, those whose diagram is invariant under rotating the three axes.
+pub fn count_cyclically_symmetric(n: usize) -> Option<u128> {
+    let mut f = Product::new();
+    for i in 1..(n + 1) {
+        f.times(3 * i - 1);
+        f.divide(3 * i - 2);
+        for j in i..(n + 1) {
+            f.times(n + i + j - 1);
+            f.divide(2 * i + j - 1);
+        }
+    }
+    f.to_u128()
+}
+
+/// Counts the totally symmetric plane partitions in an `n × n × n` box,
+/// those whose diagram is invariant under every permutation of the axes.
+pub fn count_totally_symmetric(n: usize) -> Option<u128> {
+    let mut f = Product::new();
+    for i in 1..(n + 1) {
+        for j in i..(n + 1) {
+            for k in j..(n + 1) {
+                f.times(i + j + k - 1);
+                f.divide(i + j + k - 2);
+            }
+        }
+    }
+    f.to_u128()
+}
+
+/// Counts the self-complementary plane partitions in an `a × b × c`
+/// box, those whose diagram is invariant under rotating the box by a
+/// half turn about its centre. There are none if `a`, `b` and `c` are
+/// all odd.
+pub fn count_self_complementary(a: usize, b: usize, c: usize) -> Option<u128> {
+    let mut dims = [a, b, c];
+    dims.sort_by_key(|&d| d % 2 == 0);
+    let [x, y, z] = dims;
+
+    let mut f = Product::new();
+    match (x % 2, y % 2, z % 2) {
+        (0, _, _) => {
+            macmahon(&mut f, x / 2, y / 2, z / 2);
+            macmahon(&mut f, x / 2, y / 2, z / 2);
+        }
+        (1, 0, _) => {
+            macmahon(&mut f, x / 2, y / 2, z / 2);
+            macmahon(&mut f, x / 2 + 1, y / 2, z / 2);
+        }
+        (1, 1, 0) => {
+            macmahon(&mut f, x / 2 + 1, y / 2, z / 2);
+            macmahon(&mut f, x / 2, y / 2 + 1, z / 2);
+        }
+        _ => return Some(0),
+    }
+    f.to_u128()
+}
+
+/// Counts the transpose-complementary plane partitions in an
+/// `n × n × c` box, those whose transpose is their complement in the
+/// box. There are none if `c` is odd, unless `n` is zero.
+pub fn count_transpose_complementary(n: usize, c: usize) -> Option<u128> {
+    if n == 0 {
+        return Some(1);
+    }
+    if c % 2 == 1 {
+        return Some(0);
+    }
+
+    // The binomial coefficient (m + n - 1 choose n - 1) times a product
+    // over 1 ≤ i ≤ j ≤ n - 2.
+    let m = c / 2;
+    let mut f = Product::new();
+    for i in 1..n {
+        f.times(m + i);
+        f.divide(i);
+    }
+    for i in 1..(n - 1) {
+        for j in i..(n - 1) {
+            f.times(2 * m + i + j + 1);
+            f.divide(i + j + 1);
+        }
+    }
+    f.to_u128()
+}
+
+/// Counts the symmetric self-complementary plane partitions in a
+/// `2n × 2n × 2m` box.
+///
+/// They are in bijection with the plane partitions in an
+/// `n × n × m` box.
+pub fn count_symmetric_self_complementary(n: usize, m: usize) -> Option<u128> {
+    count_in_box(n, n, m)
+}
+
+/// Multiplies `f` by `∏ (3i + 1)! / (n + i)!` over `0 ≤ i < n`, the
+/// number of `n × n` alternating sign matrices.
+fn asm(f: &mut Product, n: usize) {
+    for i in 0..n {
+        f.times_factorial(3 * i + 1);
+        f.divide_factorial(n + i);
+    }
+}
+
+/// Counts the cyclically symmetric transpose-complementary plane
+/// partitions in an `n × n × n` box. There are none if `n` is odd.
+pub fn count_cyclically_symmetric_transpose_complementary(n: usize) -> Option<u128> {
+    if n % 2 == 1 {
+        return Some(0);
+    }
+
+    let mut f = Product::new();
+    for i in 0..(n / 2) {
+        f.times(3 * i + 1);
+        f.times_factorial(6 * i);
+        f.times_factorial(2 * i);
+        f.divide_factorial(4 * i);
+        f.divide_factorial(4 * i + 1);
+    }
+    f.to_u128()
+}
+
+/// Counts the cyclically symmetric self-complementary plane partitions
+/// in an `n × n × n` box. There are none if `n` is odd.
+pub fn count_cyclically_symmetric_self_complementary(n: usize) -> Option<u128> {
+    if n % 2 == 1 {
+        return Some(0);
+    }
+
+    let mut f = Product::new();
+    asm(&mut f, n / 2);
+    asm(&mut f, n / 2);
+    f.to_u128()
+}
+
+/// Counts the totally symmetric self-complementary plane partitions in
+/// an `n × n × n` box. There are none if `n` is odd.
+///
+/// For `n = 2k` this is the number of `k × k` alternating sign
+/// matrices, as Andrews proved.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::plane::count_totally_symmetric_self_complementary;
+///
+/// let asm: Vec<_> = (0..6)
+///     .map(|k| count_totally_symmetric_self_complementary(2 * k).unwrap())
+///     .collect();
+/// assert_eq!(asm, vec![1, 1, 2, 7, 42, 429]);
+/// ```
+pub fn count_totally_symmetric_self_complementary(n: usize) -> Option<u128> {
+    if n % 2 == 1 {
+        return Some(0);
+    }
+
+    let mut f = Product::new();
+    asm(&mut f, n / 2);
+    f.to_u128()
+}
+
+/// Draws a uniformly random plane partition in an `a × b × c` box.
+///
+/// This uses coupling from the past (Propp and Wilson) on the Markov
+/// chain that repeatedly picks a random entry and tries to raise or
+/// lower it by one. The chain is monotone, so it is enough to run it
+/// from the empty and the full box until the two coalesce, and the
+/// result is exactly uniform. The running time grows roughly like the
+/// fourth power of the side of the box, which makes boxes with sides in
+/// the dozens practical, far beyond the reach of enumeration.
+///
+/// Only available with the `rand` feature.
+///
+/// # Examples
+///
+/// ```
+/// extern crate rand;
+/// # extern crate integer_partitions;
+/// use integer_partitions::plane::sample_in_box;
+///
+/// # fn main() {
+/// # #[cfg(feature = "std")] {
+/// let pp = sample_in_box(10, 10, 10, &mut rand::thread_rng());
+/// assert_eq!(pp.len(), 10);
+/// assert!(pp.iter().all(|row| row.windows(2).all(|w| w[0] >= w[1])));
+/// # }
+/// # }
+/// ```
+#[cfg(feature = "rand")]
+pub fn sample_in_box<R: Rng + ?Sized>(a: usize, b: usize, c: usize, rng: &mut R) -> Vec<Vec<usize>> {
+    let cells = a * b;
+    if cells == 0 {
+        return vec![Vec::new(); a];
+    }
+
+    // moves[t] is the move made t steps before the end, so that moves
+    // for earlier starting times can be appended without disturbing the
+    // later ones.
+    let mut moves: Vec<usize> = Vec::new();
+    let mut steps = cells;
+
+    loop {
+        while moves.len() < steps {
+            moves.push(rng.gen_range(0..2 * cells));
+        }
+
+        let mut lo = vec![0; cells];
+        let mut hi = vec![c; cells];
+        for &m in moves.iter().rev() {
+            step(&mut lo, a, b, c, m);
+            step(&mut hi, a, b, c, m);
+        }
+
+        if lo == hi {
+            return lo.chunks(b).map(|row| row.to_vec()).collect();
+        }
+        steps *= 2;
+    }
+}
+
+/// Tries to raise (for even `m`) or lower (for odd `m`) entry `m / 2`.
+#[cfg(feature = "rand")]
+#[inline]
+fn step(h: &mut [usize], a: usize, b: usize, c: usize, m: usize) {
+    let cell = m / 2;
+    let (i, j) = (cell / b, cell % b);
+    let x = h[cell];
+
+    if m & 1 == 0 {
+        let ok = x < c
+            && (i == 0 || h[cell - b] > x)
+            && (j == 0 || h[cell - 1] > x);
+        if ok {
+            h[cell] = x + 1;
+        }
+    } else {
+        let ok = x > 0
+            && (i + 1 == a || h[cell + b] < x)
+            && (j + 1 == b || h[cell + 1] < x);
+        if ok {
+            h[cell] = x - 1;
+        }
+    }
+}
+
+#[cfg(all(feature = "rand", feature = "std"))]
+#[test]
+fn uniform() {
+    //! Tests that all 20 plane partitions in a 2 × 2 × 2 box are drawn
+    //! about equally often.
+
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use std::collections::HashMap;
+
+    let mut rng = StdRng::seed_from_u64(4);
+    let mut hits = HashMap::new();
+    for _ in 0..20000 {
+        let pp = sample_in_box(2, 2, 2, &mut rng);
+        assert!(pp[0][0] >= pp[0][1] && pp[0][0] >= pp[1][0]);
+        assert!(pp[0][1] >= pp[1][1] && pp[1][0] >= pp[1][1]);
+        *hits.entry(pp).or_insert(0) += 1;
+    }
+
+    assert_eq!(hits.len(), 20);
+    assert!(hits.values().all(|&h| h > 850 && h < 1150), "{:?}", hits);
+}
+
+#[test]
+fn symmetry_classes() {
+    //! Tests every count against brute force in small boxes.
+
+    fn all(a: usize, b: usize, c: usize) -> Vec<Vec<usize>> {
+        fn fill(pp: &mut Vec<usize>, a: usize, b: usize, c: usize, out: &mut Vec<Vec<usize>>) {
+            let cell = pp.len();
+            if cell == a * b {
+                out.push(pp.clone());
+                return;
+            }
+            let (i, j) = (cell / b, cell % b);
+            let mut hi = c;
+            if i > 0 {
+                hi = hi.min(pp[cell - b]);
+            }
+            if j > 0 {
+                hi = hi.min(pp[cell - 1]);
+            }
+            for x in 0..(hi + 1) {
+                pp.push(x);
+                fill(pp, a, b, c, out);
+                pp.pop();
+            }
+        }
+
+        let mut out = Vec::new();
+        fill(&mut Vec::new(), a, b, c, &mut out);
+        out
+    }
+
+    let count = |v: &[Vec<usize>], f: &dyn Fn(&[usize]) -> bool| {
+        Some(v.iter().filter(|pp| f(pp)).count() as u128)
+    };
+
+    for a in 0..4 {
+        for b in 0..4 {
+            for c in 0..4 {
+                let v = all(a, b, c);
+                let comp = |pp: &[usize]| (0..a * b).all(|k| pp[k] + pp[a * b - 1 - k] == c);
+                assert_eq!(count_in_box(a, b, c), Some(v.len() as u128));
+                assert_eq!(count_self_complementary(a, b, c), count(&v, &comp));
+            }
+        }
+    }
+
+    for n in 0..5 {
+        for c in 0..5 {
+            if n * n * c > 40 {
+                continue;
+            }
+            let v = all(n, n, c);
+            let at = |pp: &[usize], i: usize, j: usize| pp[i * n + j];
+            let sym = |pp: &[usize]| (0..n).all(|i| (0..n).all(|j| at(pp, i, j) == at(pp, j, i)));
+            let tc = |pp: &[usize]| {
+                (0..n).all(|i| (0..n).all(|j| at(pp, i, j) + at(pp, n - 1 - j, n - 1 - i) == c))
+            };
+            let comp = |pp: &[usize]| (0..n * n).all(|k| pp[k] + pp[n * n - 1 - k] == c);
+
+            assert_eq!(count_symmetric(n, c), count(&v, &sym));
+            assert_eq!(count_transpose_complementary(n, c), count(&v, &tc));
+            if n % 2 == 0 && c % 2 == 0 {
+                let ssc = |pp: &[usize]| sym(pp) && comp(pp);
+                assert_eq!(count_symmetric_self_complementary(n / 2, c / 2), count(&v, &ssc));
+            }
+        }
+    }
+
+    for n in 0..5 {
+        let v = all(n, n, n);
+        let at = |pp: &[usize], i: usize, j: usize| pp[i * n + j];
+        let sym = |pp: &[usize]| (0..n).all(|i| (0..n).all(|j| at(pp, i, j) == at(pp, j, i)));
+        let comp = |pp: &[usize]| (0..n * n).all(|k| pp[k] + pp[n * n - 1 - k] == n);
+        let tc = |pp: &[usize]| {
+            (0..n).all(|i| (0..n).all(|j| at(pp, i, j) + at(pp, n - 1 - j, n - 1 - i) == n))
+        };
+        let cyc = |pp: &[usize]| {
+            (0..n).all(|i| (0..n).all(|j| (0..n).all(|k| (k < at(pp, i, j)) == (i < at(pp, j, k)))))
+        };
+
+        assert_eq!(count_cyclically_symmetric(n), count(&v, &cyc));
+        assert_eq!(count_totally_symmetric(n), count(&v, &|pp| cyc(pp) && sym(pp)));
+        assert_eq!(count_cyclically_symmetric_transpose_complementary(n), count(&v, &|pp| cyc(pp) && tc(pp)));
+        assert_eq!(count_cyclically_symmetric_self_complementary(n), count(&v, &|pp| cyc(pp) && comp(pp)));
+        assert_eq!(
+            count_totally_symmetric_self_complementary(n),
+            count(&v, &|pp| cyc(pp) && sym(pp) && comp(pp))
+        );
+    }
+
+    // A prime factor near 10⁹ costs no more than a small one.
+    assert_eq!(count_in_box(1, 1, 1_000_000_006), Some(1_000_000_007));
+}
@@ -0,0 +1,616 @@
+//! Intervals in Young's lattice and in the dominance order.
+//!
+//! Young's lattice orders all partitions by containment of their
+//! diagrams. The dominance order compares partitions of the same number
+//! by their partial sums: `μ ⊴ λ` when the `k` largest parts of `μ` add
+//! up to no more than those of `λ`, for every `k`.
+//!
+//! Partitions of the same number form a lattice under dominance, with
+//! `dominance_meet` and `dominance_join`, and `Dominance` compares them
+//! with the usual operators. `Dominated` lists every partition below a
+//! given one.
+//!
+//! An `Interval` holds every partition between two others in one of
+//! these orders, and from it gives the Möbius function, the number of
+//! chains of each length, and the zeta polynomial, by recursion over
+//! the interval. The interval is found by listing candidates, so this
+//! is only for intervals of modest size.
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+
+use streaming_iterator::StreamingIterator;
+use successor::{Constrained, Constraint};
+use young::conjugate;
+
+/// Returns whether `a ⊆ b`, that is whether each part of `a`, from the
+/// largest, is at most the corresponding part of `b`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::poset::contains;
+///
+/// assert!(contains(&[1, 2], &[2, 3]));
+/// assert!(!contains(&[1, 1, 1], &[2, 3]));
+/// ```
+pub fn contains(a: &[usize], b: &[usize]) -> bool {
+    a.len() <= b.len() && a.iter().rev().zip(b.iter().rev()).all(|(x, y)| x <= y)
+}
+
+/// Returns whether `a` dominates `b`, that is whether `a ⊵ b` in the
+/// dominance order, for partitions of the same number.
+///
+/// Partitions of different numbers are not comparable, and give
+/// `false`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::poset::dominates;
+///
+/// assert!(dominates(&[1, 1, 1, 3], &[1, 1, 2, 2]));
+/// assert!(!dominates(&[1, 1, 2, 2], &[1, 1, 1, 3]));
+/// assert!(!dominates(&[1, 1, 1, 3], &[2, 2, 2]));
+/// ```
+pub fn dominates(a: &[usize], b: &[usize]) -> bool {
+    if a.iter().sum::<usize>() != b.iter().sum::<usize>() {
+        return false;
+    }
+    let (mut sa, mut sb) = (0, 0);
+    for i in 0..a.len().max(b.len()) {
+        sa += a.len().checked_sub(i + 1).map_or(0, |j| a[j]);
+        sb += b.len().checked_sub(i + 1).map_or(0, |j| b[j]);
+        if sa < sb {
+            return false;
+        }
+    }
+    true
+}
+
+/// Returns the Möbius function of Young's lattice between `lower` and
+/// `upper`, which is `(-1)^k` if `upper / lower` is `k` cells in
+/// distinct rows and columns, and zero otherwise.
+///
+/// # Panics
+///
+/// Panics unless `lower ⊆ upper`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::poset::young_mobius;
+///
+/// assert_eq!(young_mobius(&[1], &[1, 2]), 1);
+/// assert_eq!(young_mobius(&[1], &[3]), 0);
+/// ```
+pub fn young_mobius(lower: &[usize], upper: &[usize]) -> i32 {
+    assert!(contains(lower, upper), "lower must be contained in upper");
+    // Rows from the longest, with lower padded with zeros.
+    let row = |p: &[usize], i: usize| p.len().checked_sub(i + 1).map_or(0, |j| p[j]);
+    let mut cells = 0;
+    for i in 0..upper.len() {
+        let (lo, hi) = (row(lower, i), row(upper, i));
+        match hi - lo {
+            0 => {}
+            // A cell nothing is stacked on: the next row stops short.
+            1 if row(upper, i + 1) <= lo => cells += 1,
+            _ => return 0,
+        }
+    }
+    if cells % 2 == 0 { 1 } else { -1 }
+}
+
+/// Returns the sums of the `k` largest parts of `p`, for `k` from one
+/// to `len`, padded with zeros.
+fn top_sums(p: &[usize], len: usize) -> Vec<usize> {
+    let mut s = 0;
+    (0..len).map(|i| {
+        s += p.len().checked_sub(i + 1).map_or(0, |j| p[j]);
+        s
+    }).collect()
+}
+
+/// Returns the meet of `a` and `b` in the dominance order: the largest
+/// partition that both dominate.
+///
+/// Its `k` largest parts add up to the smaller of those of `a` and `b`,
+/// for each `k`.
+///
+/// # Panics
+///
+/// Panics if `a` and `b` are partitions of different numbers.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::poset::{dominance_join, dominance_meet};
+///
+/// // 1+1+4 and 3+3 are not comparable.
+/// assert_eq!(dominance_meet(&[1, 1, 4], &[3, 3]), vec![1, 2, 3]);
+/// assert_eq!(dominance_join(&[1, 1, 4], &[3, 3]), vec![2, 4]);
+/// ```
+pub fn dominance_meet(a: &[usize], b: &[usize]) -> Vec<usize> {
+    assert_eq!(a.iter().sum::<usize>(), b.iter().sum::<usize>(), "partitions must be of the same number");
+    let len = a.len().max(b.len());
+    let (sa, sb) = (top_sums(a, len), top_sums(b, len));
+    // The least of two concave sequences is concave, so the differences
+    // decrease and are the parts, from the largest.
+    let mut out = Vec::with_capacity(len);
+    let mut last = 0;
+    for (x, y) in sa.into_iter().zip(sb) {
+        let s = x.min(y);
+        if s > last {
+            out.push(s - last);
+        }
+        last = s;
+    }
+    out.reverse();
+    out
+}
+
+/// Returns the join of `a` and `b` in the dominance order: the smallest
+/// partition that dominates both.
+///
+/// Conjugation reverses the order, so this is the conjugate of the meet
+/// of the conjugates.
+///
+/// # Panics
+///
+/// Panics if `a` and `b` are partitions of different numbers.
+pub fn dominance_join(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let (mut ca, mut cb, mut out) = (Vec::new(), Vec::new(), Vec::new());
+    conjugate(a, &mut ca);
+    conjugate(b, &mut cb);
+    conjugate(&dominance_meet(&ca, &cb), &mut out);
+    out
+}
+
+/// Compares partitions by dominance with `<`, `<=` and the rest.
+///
+/// Partitions of different numbers, and partitions of the same number
+/// that neither dominates, are unordered.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::poset::Dominance;
+///
+/// assert!(Dominance(&[1, 1, 2][..]) < Dominance(&[2, 2][..]));
+/// assert!(Dominance(&[3, 3][..]).partial_cmp(&Dominance(&[1, 1, 4][..])).is_none());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Dominance<P>(pub P);
+
+impl<P: AsRef<[usize]>> PartialEq for Dominance<P> {
+    #[inline]
+    fn eq(&self, other: &Dominance<P>) -> bool {
+        self.0.as_ref() == other.0.as_ref()
+    }
+}
+
+impl<P: AsRef<[usize]>> Eq for Dominance<P> {}
+
+impl<P: AsRef<[usize]>> PartialOrd for Dominance<P> {
+    fn partial_cmp(&self, other: &Dominance<P>) -> Option<Ordering> {
+        let (a, b) = (self.0.as_ref(), other.0.as_ref());
+        if a == b {
+            Some(Ordering::Equal)
+        } else if dominates(b, a) {
+            Some(Ordering::Less)
+        } else if dominates(a, b) {
+            Some(Ordering::Greater)
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether a prefix of smallest parts can still be completed below
+/// `upper`, whose `k` largest parts add up to `sums[k - 1]`.
+#[derive(Debug)]
+struct Below {
+    sums: Vec<usize>,
+}
+
+impl Constraint for Below {
+    fn allows(&self, prefix: &[usize], x: usize, rest: usize) -> bool {
+        if rest > 0 && rest < x {
+            return false;
+        }
+        // The rest is lowest in dominance split as evenly as it can be
+        // into as many parts of at least x as it has room for, and those
+        // are all at least as large as the parts so far.
+        let q = rest.checked_div(x).unwrap_or(0);
+        let (b, r) = rest.checked_div(q).map_or((0, 0), |b| (b, rest % q));
+        let upper = |k: usize| self.sums.get(k).map_or(*self.sums.last().unwrap_or(&0), |&s| s);
+        if (0..q).any(|k| (k + 1) * b + (k + 1).min(r) > upper(k)) {
+            return false;
+        }
+        let mut s = rest;
+        let small = Some(x).into_iter().chain(prefix.iter().rev().cloned());
+        for (k, y) in (q..).zip(small) {
+            s += y;
+            if s > upper(k) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn first(&self, prefix: &[usize], lo: usize, s: usize) -> Option<usize> {
+        (lo..(s + 1)).find(|&x| self.allows(prefix, x, s - x))
+    }
+}
+
+/// Iterates over the partitions dominated by a given one, itself
+/// included, in the order of `Partitions`.
+///
+/// Parts are only placed while the partition can still be finished
+/// below the bound, so only the partitions below it are visited. Each
+/// candidate for a part is checked against the bound in time linear in
+/// `n`, and up to `n` candidates may be tried for every part placed, so
+/// a step can take time quadratic in `n` for each part it changes.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::poset::Dominated;
+///
+/// let mut pp = Dominated::new(&[1, 2, 2]);
+/// assert_eq!(pp.next(), Some(&[1, 1, 1, 1, 1][..]));
+/// assert_eq!(pp.next(), Some(&[1, 1, 1, 2][..]));
+/// assert_eq!(pp.next(), Some(&[1, 2, 2][..]));
+/// assert_eq!(pp.next(), None);
+/// ```
+#[derive(Debug)]
+pub struct Dominated {
+    inner: Constrained<Below>,
+}
+
+impl Dominated {
+    /// Makes a new iterator over the partitions dominated by `upper`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `upper` is not in non-decreasing order, or has a part
+    /// of zero.
+    pub fn new(upper: &[usize]) -> Dominated {
+        assert!(upper.first() != Some(&0), "parts must be positive");
+        assert!(upper.windows(2).all(|w| w[0] <= w[1]), "parts must be in non-decreasing order");
+        let n = upper.iter().sum();
+        Dominated {
+            inner: Constrained::new(n, Below { sums: top_sums(upper, upper.len()) }),
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+}
+
+impl StreamingIterator for Dominated {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        self.inner.get()
+    }
+
+    #[inline]
+    fn advance(&mut self) {
+        self.inner.advance()
+    }
+}
+
+/// The partitions between two others in Young's lattice or the
+/// dominance order.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::poset::Interval;
+///
+/// // All eleven partitions of 6 lie between 1+1+1+1+1+1 and 6.
+/// let all = Interval::dominance(&[1; 6], &[6]).unwrap();
+/// assert_eq!(all.len(), 11);
+/// assert_eq!(all.mobius(), 0);
+///
+/// let box2 = Interval::young(&[], &[2, 2]).unwrap();
+/// assert_eq!(box2.len(), 6);
+/// assert_eq!(box2.chains(), vec![0, 1, 4, 5, 2]);
+/// assert_eq!(box2.zeta(2), Some(6));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Interval {
+    /// The partitions, in an order that extends the partial order.
+    elements: Vec<Vec<usize>>,
+    /// below[j] lists the indices of the elements strictly below j.
+    below: Vec<Vec<usize>>,
+}
+
+impl Interval {
+    fn build<F: Fn(&[usize], &[usize]) -> bool>(elements: Vec<Vec<usize>>, leq: F) -> Interval {
+        let below = (0..elements.len())
+            .map(|j| (0..j).filter(|&i| leq(&elements[i], &elements[j])).collect())
+            .collect();
+        Interval { elements, below }
+    }
+
+    /// Makes the interval `[lower, upper]` of Young's lattice, or returns
+    /// `None` unless `lower ⊆ upper`.
+    pub fn young(lower: &[usize], upper: &[usize]) -> Option<Interval> {
+        if !contains(lower, upper) {
+            return None;
+        }
+        // Rows from the longest, each between lower and upper and no
+        // longer than the one before.
+        let row = |p: &[usize], i: usize| p.len().checked_sub(i + 1).map_or(0, |j| p[j]);
+        let l = upper.len();
+        let mut elements = Vec::new();
+        let mut rows = Vec::with_capacity(l);
+        fn go<R>(row: &R, lower: &[usize], upper: &[usize], rows: &mut Vec<usize>, out: &mut Vec<Vec<usize>>)
+        where
+            R: Fn(&[usize], usize) -> usize,
+        {
+            let i = rows.len();
+            if i == upper.len() {
+                out.push(rows.iter().rev().cloned().filter(|&x| x > 0).collect());
+                return;
+            }
+            let hi = rows.last().map_or(row(upper, i), |&r| r.min(row(upper, i)));
+            for x in row(lower, i)..(hi + 1) {
+                rows.push(x);
+                go(row, lower, upper, rows, out);
+                rows.pop();
+            }
+        }
+        go(&row, lower, upper, &mut rows, &mut elements);
+        elements.sort_by_key(|p| p.iter().sum::<usize>());
+        Some(Interval::build(elements, contains))
+    }
+
+    /// Makes the interval `[lower, upper]` of the dominance order, or
+    /// returns `None` unless `lower ⊴ upper`.
+    ///
+    /// This looks at every partition below `upper`.
+    pub fn dominance(lower: &[usize], upper: &[usize]) -> Option<Interval> {
+        if !dominates(upper, lower) {
+            return None;
+        }
+        let mut elements = Vec::new();
+        let mut pp = Dominated::new(upper);
+        while let Some(p) = pp.next() {
+            if dominates(p, lower) {
+                elements.push(p.to_vec());
+            }
+        }
+        // Dominance implies the same order lexicographically, from the
+        // largest part.
+        elements.sort_by(|a, b| a.iter().rev().cmp(b.iter().rev()));
+        Some(Interval::build(elements, |a, b| dominates(b, a)))
+    }
+
+    /// Returns the number of partitions in the interval.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Returns whether the interval is empty, which it never is.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Returns the partitions in the interval, in an order in which each
+    /// comes after all those below it, so the first is the lower end and
+    /// the last the upper end.
+    #[inline]
+    pub fn elements(&self) -> &[Vec<usize>] {
+        &self.elements
+    }
+
+    /// Returns the covering pairs `(i, j)`, with the element at `j`
+    /// directly above the one at `i`, as indices into `elements`.
+    pub fn covers(&self) -> Vec<(usize, usize)> {
+        let mut out = Vec::new();
+        for (j, below) in self.below.iter().enumerate() {
+            for &i in below {
+                // i is covered by j unless something between them is
+                // above i.
+                if !below.iter().any(|&k| self.below[k].contains(&i)) {
+                    out.push((i, j));
+                }
+            }
+        }
+        out
+    }
+
+    /// Returns the Möbius function from the lower end to the upper end.
+    pub fn mobius(&self) -> i128 {
+        let mut m: Vec<i128> = Vec::with_capacity(self.len());
+        for (j, below) in self.below.iter().enumerate() {
+            m.push(if j == 0 { 1 } else { -below.iter().map(|&i| m[i]).sum::<i128>() });
+        }
+        m[self.len() - 1]
+    }
+
+    /// Returns the numbers of chains `lower = x_0 < x_1 < ⋯ < x_k = upper`
+    /// for each length `k` from zero to the longest.
+    pub fn chains(&self) -> Vec<u128> {
+        // chains[j][k] counts the chains of length k from the lower end
+        // to element j.
+        let mut chains: Vec<Vec<u128>> = Vec::with_capacity(self.len());
+        for (j, below) in self.below.iter().enumerate() {
+            let mut c = if j == 0 { vec![1] } else { Vec::new() };
+            for &i in below {
+                if c.len() < chains[i].len() + 1 {
+                    c.resize(chains[i].len() + 1, 0);
+                }
+                for (k, &x) in chains[i].iter().enumerate() {
+                    c[k + 1] += x;
+                }
+            }
+            chains.push(c);
+        }
+        chains.pop().unwrap()
+    }
+
+    /// Evaluates the zeta polynomial at `k`: the number of multichains
+    /// `lower = x_0 ≤ x_1 ≤ ⋯ ≤ x_k = upper`.
+    ///
+    /// A multichain takes `j` strict steps out of its `k`, so this is
+    /// `Σ_j b_j C(k, j)` for the counts `b_j` of `chains`, a polynomial
+    /// in `k` whose value at `-1` is the Möbius function. Returns `None`
+    /// if the number does not fit in a `u128`.
+    pub fn zeta(&self, k: usize) -> Option<u128> {
+        let mut total: u128 = 0;
+        let mut binom: u128 = 1;
+        for (j, &b) in self.chains().iter().enumerate() {
+            if j > k {
+                break;
+            }
+            if j > 0 {
+                binom = binom.checked_mul(u128::try_from(k + 1 - j).ok()?)? / j as u128;
+            }
+            total = total.checked_add(b.checked_mul(binom)?)?;
+        }
+        Some(total)
+    }
+}
+
+#[test]
+fn intervals() {
+    //! Compares the Möbius function with the chains, and with the
+    //! closed form for Young's lattice, and the multichains directly.
+
+    use Partitions;
+
+    fn check(iv: &Interval) {
+        let b = iv.chains();
+        let alternating: i128 = b.iter().enumerate()
+            .map(|(k, &x)| if k % 2 == 0 { x as i128 } else { -(x as i128) })
+            .sum();
+        assert_eq!(iv.mobius(), alternating);
+
+        // Multichains with k steps, by powers of the zeta matrix.
+        let n = iv.len();
+        let mut reach = vec![0u128; n];
+        reach[0] = 1;
+        for k in 0..5 {
+            assert_eq!(iv.zeta(k), Some(reach[n - 1]));
+            reach = (0..n).map(|j| reach[j] + iv.below[j].iter().map(|&i| reach[i]).sum::<u128>()).collect();
+        }
+
+        // The covers generate the order.
+        let covers = iv.covers();
+        for (j, below) in iv.below.iter().enumerate() {
+            let mut up = vec![false; n];
+            up[j] = true;
+            for &(a, c) in covers.iter().rev() {
+                if up[c] {
+                    up[a] = true;
+                }
+            }
+            let mut expect = vec![false; n];
+            expect[j] = true;
+            for &i in below {
+                expect[i] = true;
+            }
+            assert_eq!(up, expect);
+        }
+    }
+
+    for n in 0..9 {
+        let mut all = Vec::new();
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            all.push(p.to_vec());
+        }
+        for a in &all {
+            for b in &all {
+                match Interval::dominance(a, b) {
+                    Some(iv) => {
+                        assert!(dominates(b, a));
+                        assert_eq!((&iv.elements()[0], iv.elements().last().unwrap()), (a, b));
+                        check(&iv);
+                    }
+                    None => assert!(!dominates(b, a)),
+                }
+            }
+        }
+    }
+
+    for m in 0..5 {
+        let mut pp = Partitions::new(m);
+        while let Some(lower) = pp.next().map(|p| p.to_vec()) {
+            for n in m..(m + 5) {
+                let mut qq = Partitions::new(n);
+                while let Some(upper) = qq.next() {
+                    match Interval::young(&lower, upper) {
+                        Some(iv) => {
+                            assert_eq!(iv.mobius(), young_mobius(&lower, upper) as i128);
+                            check(&iv);
+                        }
+                        None => assert!(!contains(&lower, upper)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn dominance_lattice() {
+    //! Compares the meets, joins and comparisons against the definitions,
+    //! and `Dominated` against filtering `Partitions`.
+
+    use Partitions;
+
+    for n in 0..13 {
+        let mut all = Vec::new();
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            all.push(p.to_vec());
+        }
+
+        for b in &all {
+            let mut below = Vec::new();
+            let mut dd = Dominated::new(b);
+            while let Some(p) = dd.next() {
+                below.push(p.to_vec());
+            }
+            assert_eq!(dd.next(), None);
+            let expected: Vec<_> = all.iter().filter(|a| dominates(b, a)).cloned().collect();
+            assert_eq!(below, expected);
+        }
+
+        if n > 9 {
+            continue;
+        }
+        for a in &all {
+            for b in &all {
+                let expected = match (dominates(b, a), dominates(a, b)) {
+                    (true, true) => Some(Ordering::Equal),
+                    (true, false) => Some(Ordering::Less),
+                    (false, true) => Some(Ordering::Greater),
+                    (false, false) => None,
+                };
+                assert_eq!(Dominance(a).partial_cmp(&Dominance(b)), expected);
+
+                let meet = dominance_meet(a, b);
+                let join = dominance_join(a, b);
+                assert!(all.contains(&meet) && all.contains(&join));
+                for c in &all {
+                    assert_eq!(dominates(a, c) && dominates(b, c), dominates(&meet, c));
+                    assert_eq!(dominates(c, a) && dominates(c, b), dominates(c, &join));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Boundary profiles of Young diagrams and their limit shape.
+//!
+//! In Russian coordinates the Young diagram of `λ` is drawn in English
+//! notation and turned by 45°, with the cell in row `i` and column `j`
+//! centred at `u = j - i` and `v = i + j + 1`. Its upper boundary is
+//! then the graph of a function `ψ(u)` with slopes `±1`, equal to `|u|`
+//! away from the diagram, and the area between `ψ` and `|u|` is twice
+//! the size of `λ`.
+//!
+//! Shrinking both axes by `√n` gives an area of 2 for every partition of
+//! `n`. For partitions drawn from the Plancherel measure the scaled
+//! profile converges to the curve of Logan and Shepp and of Vershik and
+//! Kerov,
+//!
+//! ```text
+//! Ω(u) = (2/π) (u arcsin(u/2) + √(4 - u²))  for |u| ≤ 2,
+//! Ω(u) = |u|                                 otherwise.
+//! ```
+
+use std::f64::consts::PI;
+
+/// Returns the corners of the boundary profile of `p`, from left to
+/// right, in unscaled Russian coordinates.
+///
+/// The profile is linear between consecutive corners, and equal to `|u|`
+/// to the left of the first and the right of the last.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::profile::breakpoints;
+///
+/// assert_eq!(breakpoints(&[]), vec![(0, 0)]);
+/// assert_eq!(breakpoints(&[1, 2]), vec![(-2, 2), (-1, 3), (0, 2), (1, 3), (2, 2)]);
+/// ```
+pub fn breakpoints(p: &[usize]) -> Vec<(isize, usize)> {
+    let mut u = -(p.len() as isize);
+    let mut v = p.len();
+    let mut out = vec![(u, v)];
+
+    // Walk the boundary from the bottom left, along each row from the
+    // shortest and up past its end.
+    let mut prev = 0;
+    for &x in p {
+        if x > prev {
+            u += (x - prev) as isize;
+            v += x - prev;
+            out.push((u, v));
+        }
+        u += 1;
+        v -= 1;
+        if x == prev && out.len() > 1 {
+            // Another step up, in line with the last.
+            *out.last_mut().unwrap() = (u, v);
+        } else {
+            out.push((u, v));
+        }
+        prev = x;
+    }
+    out
+}
+
+/// Returns the corners of the boundary profile of `p`, with both axes
+/// scaled down by the square root of its size.
+pub fn scaled_breakpoints(p: &[usize]) -> Vec<(f64, f64)> {
+    let n: usize = p.iter().sum();
+    let s = if n == 0 { 1.0 } else { (n as f64).sqrt() };
+    breakpoints(p).into_iter().map(|(u, v)| (u as f64 / s, v as f64 / s)).collect()
+}
+
+/// Evaluates a piecewise linear profile given by its corners.
+fn eval(corners: &[(f64, f64)], u: f64) -> f64 {
+    let i = corners.partition_point(|&(x, _)| x <= u);
+    if i == 0 || i == corners.len() {
+        return u.abs();
+    }
+    let (x0, y0) = corners[i - 1];
+    let (x1, y1) = corners[i];
+    y0 + (y1 - y0) * (u - x0) / (x1 - x0)
+}
+
+/// Evaluates the scaled boundary profile of `p` at each of the points
+/// `us`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::profile::sampled;
+///
+/// // The single cell, scaled by 1.
+/// assert_eq!(sampled(&[1], &[-2.0, -0.5, 0.0, 0.25, 3.0]), vec![2.0, 1.5, 2.0, 1.75, 3.0]);
+/// ```
+pub fn sampled(p: &[usize], us: &[f64]) -> Vec<f64> {
+    let corners = scaled_breakpoints(p);
+    us.iter().map(|&u| eval(&corners, u)).collect()
+}
+
+/// The limit shape `Ω` of Logan, Shepp, Vershik and Kerov.
+pub fn omega(u: f64) -> f64 {
+    if u.abs() >= 2.0 {
+        u.abs()
+    } else {
+        2.0 / PI * (u * (u / 2.0).asin() + (4.0 - u * u).sqrt())
+    }
+}
+
+/// Returns the greatest distance between the scaled boundary profile of
+/// `p` and the limit shape `Ω`, over the whole real line.
+///
+/// Between corners the profile has slope `±1` while `Ω` has a smaller
+/// slope inside `[-2, 2]` and agrees with `|u|` outside, so the distance
+/// is greatest at a corner or at `±2`, and this is exact up to rounding.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::profile::lsvk_distance;
+///
+/// // A staircase is not far from the limit shape, unlike a single row.
+/// let staircase: Vec<usize> = (1..40).collect();
+/// assert!(lsvk_distance(&staircase) < 0.25);
+/// assert!(lsvk_distance(&[780]) > 1.0);
+/// ```
+pub fn lsvk_distance(p: &[usize]) -> f64 {
+    let corners = scaled_breakpoints(p);
+    let at = |u: f64| (eval(&corners, u) - omega(u)).abs();
+
+    let mut d = at(-2.0).max(at(2.0));
+    for &(u, v) in &corners {
+        d = d.max((v - omega(u)).abs());
+    }
+    d
+}
+
+#[test]
+fn profiles() {
+    //! Tests the area under each profile, and the distance against a
+    //! fine sampling.
+
+    use Partitions;
+
+    for n in 1..16 {
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            let c = breakpoints(p);
+            for w in c.windows(2) {
+                let (du, dv) = (w[1].0 - w[0].0, w[1].1 as isize - w[0].1 as isize);
+                assert_eq!(du.abs(), dv.abs());
+            }
+            assert!(c.windows(3).all(|w| (w[1].1 > w[0].1) != (w[2].1 > w[1].1)));
+
+            // The profile is linear between integers, so the area is the
+            // sum of the excess over |u| at them.
+            let f: Vec<(f64, f64)> = c.iter().map(|&(u, v)| (u as f64, v as f64)).collect();
+            let area: f64 = (-(n as isize)..(n as isize + 1))
+                .map(|u| eval(&f, u as f64) - (u as f64).abs())
+                .sum();
+            assert_eq!(area, 2.0 * n as f64);
+
+            let d = lsvk_distance(p);
+            let us: Vec<f64> = (0..2001).map(|i| -5.0 + i as f64 / 200.0).collect();
+            let s = sampled(p, &us);
+            let approx = us.iter().zip(&s).map(|(&u, &v)| (v - omega(u)).abs()).fold(0.0, f64::max);
+            assert!(approx <= d + 1e-12 && d - approx < 0.01);
+        }
+    }
+}
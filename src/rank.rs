@@ -0,0 +1,177 @@
+//! Counting tables for ranking partitions in enumeration order.
+
+use alloc::vec::Vec;
+
+use count::P_CONST_MAX;
+
+/// Counts partitions by their smallest part, to rank and unrank the
+/// partitions of `n` in the lexicographic order used by `Partitions`.
+///
+/// Ranks are `usize`, so tables only go up to `P_CONST_MAX`, past which
+/// `p(n)` does not fit in a `u64`, and on narrower targets only as far
+/// as the counts fit.
+#[derive(Debug, Clone)]
+pub(crate) struct RankTable {
+    n: usize,
+    // q[r * (n + 2) + m] is the number of partitions of r whose parts
+    // are all at least m, for 0 <= r <= n and 0 <= m <= n + 1.
+    q: Vec<usize>,
+}
+
+impl RankTable {
+    /// Builds the table for `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than `P_CONST_MAX`, or if `p(n)` does
+    /// not fit in a `usize`.
+    pub(crate) fn new(n: usize) -> RankTable {
+        assert!(n <= P_CONST_MAX, "partitions can only be ranked up to n = P_CONST_MAX = 416, past which p(n) does not fit in a u64");
+        let w = n + 2;
+        let mut q = vec![0usize; (n + 1) * w];
+
+        for x in &mut q[..w] {
+            *x = 1;
+        }
+        for r in 1..(n + 1) {
+            for m in (1..(r + 1)).rev() {
+                q[r * w + m] = q[r * w + m + 1]
+                    .checked_add(q[(r - m) * w + m])
+                    .expect("p(n) does not fit in a usize");
+            }
+            q[r * w] = q[r * w + 1];
+        }
+
+        RankTable { n, q }
+    }
+
+    #[inline]
+    pub(crate) fn n(&self) -> usize {
+        self.n
+    }
+
+    /// The number of partitions of `r` with every part at least `m`.
+    #[inline]
+    pub(crate) fn at_least(&self, r: usize, m: usize) -> usize {
+        if m > r {
+            (r == 0) as usize
+        } else {
+            self.q[r * (self.n + 2) + m]
+        }
+    }
+
+    /// The number of partitions of `n`.
+    #[inline]
+    pub(crate) fn count(&self) -> usize {
+        self.at_least(self.n, 1)
+    }
+
+    /// Returns the position of `p` in enumeration order, or `None` if it
+    /// isn't a partition of `n` in non-decreasing order.
+    #[inline]
+    pub(crate) fn rank(&self, p: &[usize]) -> Option<usize> {
+        self.rank_in(self.n, p)
+    }
+
+    /// Ranks `p` among the partitions of `n`, which may be any number
+    /// up to the table's own.
+    pub(crate) fn rank_in(&self, n: usize, p: &[usize]) -> Option<usize> {
+        debug_assert!(n <= self.n);
+        let mut r = n;
+        let mut m = 1;
+        let mut rank = 0;
+
+        for &x in p {
+            if x < m || x > r {
+                return None;
+            }
+            rank += self.at_least(r, m) - self.at_least(r, x);
+            r -= x;
+            m = x;
+        }
+
+        if r == 0 {
+            Some(rank)
+        } else {
+            None
+        }
+    }
+
+    /// Writes the partition at position `i` in enumeration order into
+    /// `out`, which is cleared first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is not less than the number of partitions.
+    #[inline]
+    pub(crate) fn unrank(&self, i: usize, out: &mut Vec<usize>) {
+        self.unrank_in(self.n, i, out)
+    }
+
+    /// Unranks `i` among the partitions of `n`, which may be any number
+    /// up to the table's own.
+    pub(crate) fn unrank_in(&self, n: usize, mut i: usize, out: &mut Vec<usize>) {
+        debug_assert!(n <= self.n);
+        assert!(i < self.at_least(n, 1), "rank out of range");
+        out.clear();
+
+        let mut r = n;
+        let mut m = 1;
+        while r > 0 {
+            let mut x = m;
+            loop {
+                let c = self.at_least(r - x, x);
+                if i < c {
+                    break;
+                }
+                i -= c;
+                x += 1;
+            }
+            out.push(x);
+            r -= x;
+            m = x;
+        }
+    }
+}
+
+#[test]
+fn round_trip() {
+    //! Tests that ranks follow enumeration order and unranking inverts them.
+
+    use Partitions;
+
+    let mut buf = Vec::new();
+    for n in 0..25 {
+        let t = RankTable::new(n);
+        let mut pp = Partitions::new(n);
+        let mut i = 0;
+
+        while let Some(p) = pp.next() {
+            assert_eq!(t.rank(p), Some(i));
+            t.unrank(i, &mut buf);
+            assert_eq!(&buf[..], p);
+            i += 1;
+        }
+
+        assert_eq!(t.count(), i);
+        assert_eq!(t.rank(&[0, n]), None);
+        assert_eq!(t.rank(&[n + 1]), None);
+    }
+
+    // The largest table counts p(416) without overflowing.
+    #[cfg(target_pointer_width = "64")]
+    assert_eq!(RankTable::new(P_CONST_MAX).count(), 17_873_792_969_689_876_004);
+
+    // A larger table serves the smaller numbers too.
+    let big = RankTable::new(30);
+    for n in 0..25 {
+        let t = RankTable::new(n);
+        for i in 0..t.count() {
+            t.unrank(i, &mut buf);
+            assert_eq!(big.rank_in(n, &buf), Some(i));
+            let p = buf.clone();
+            big.unrank_in(n, i, &mut buf);
+            assert_eq!(buf, p);
+        }
+    }
+}
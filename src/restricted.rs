@@ -0,0 +1,259 @@
+//! Partitions with bounds on their parts and on how many there are.
+//!
+//! `Partitions::builder` sets limits on the smallest part, the largest
+//! part and the number of parts, and builds an iterator over just the
+//! partitions within them, in the order of `Partitions`. The limits
+//! prune the search directly instead of filtering, so each partition
+//! takes constant amortized time however few of them there are. Bounding
+//! both the largest part and the number of parts gives the partitions
+//! that fit in a box.
+//!
+//! # Examples
+//!
+//! ```
+//! use integer_partitions::Partitions;
+//!
+//! // The partitions of 6 that fit in a 3×3 box.
+//! let mut pp = Partitions::builder(6).max_parts(3).max_part(3).build();
+//! assert_eq!(pp.next(), Some(&[1, 2, 3][..]));
+//! assert_eq!(pp.next(), Some(&[2, 2, 2][..]));
+//! assert_eq!(pp.next(), Some(&[3, 3][..]));
+//! assert_eq!(pp.next(), None);
+//! ```
+
+use alloc::vec::Vec;
+
+use streaming_iterator::StreamingIterator;
+use successor::{start, step, Constraint, Family};
+#[cfg(feature = "validate")]
+use validate::{Order, Validator};
+use PartitionIterator;
+
+/// Limits for the partitions of a number, made by `Partitions::builder`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartitionsBuilder {
+    n: usize,
+    limits: Limits,
+}
+
+impl PartitionsBuilder {
+    pub(crate) fn new(n: usize) -> PartitionsBuilder {
+        PartitionsBuilder {
+            n,
+            limits: Limits {
+                min_part: 1,
+                max_part: usize::MAX,
+                max_parts: usize::MAX,
+            },
+        }
+    }
+
+    /// Allows only parts of at least `m`. A bound of zero is treated
+    /// like one.
+    pub fn min_part(mut self, m: usize) -> PartitionsBuilder {
+        self.limits.min_part = m.max(1);
+        self
+    }
+
+    /// Allows only parts of at most `m`.
+    pub fn max_part(mut self, m: usize) -> PartitionsBuilder {
+        self.limits.max_part = m;
+        self
+    }
+
+    /// Allows at most `k` parts.
+    pub fn max_parts(mut self, k: usize) -> PartitionsBuilder {
+        self.limits.max_parts = k;
+        self
+    }
+
+    /// Makes the iterator.
+    ///
+    /// The empty partition of zero has no parts to break the limits,
+    /// and is always included.
+    pub fn build(self) -> Restricted {
+        Restricted {
+            n: self.n,
+            limits: self.limits,
+            p: Vec::new(),
+            state: State::Fresh,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Limits {
+    min_part: usize,
+    max_part: usize,
+    max_parts: usize,
+}
+
+impl Limits {
+    /// Returns whether `t` can be made up of at most `slots` parts
+    /// between `x` and the largest part.
+    #[inline]
+    fn fits(&self, x: usize, t: usize, slots: usize) -> bool {
+        t == 0 || t.div_ceil(self.max_part) <= slots.min(t / x)
+    }
+}
+
+impl Constraint for Limits {
+    #[inline]
+    fn allows(&self, prefix: &[usize], x: usize, rest: usize) -> bool {
+        x >= self.min_part
+            && x <= self.max_part
+            && prefix.len() < self.max_parts
+            && self.fits(x, rest, self.max_parts - prefix.len() - 1)
+    }
+
+    fn first(&self, prefix: &[usize], lo: usize, s: usize) -> Option<usize> {
+        let m = self.max_part;
+        let slots = self.max_parts.checked_sub(prefix.len() + 1)?;
+        // The rest must fit in the slots left, so x is at least
+        // s - slots·m. Past that, the least number c of parts for the
+        // rest only goes down as x goes up, so either the least x works,
+        // or the next chance is where c drops by one, or only a single
+        // last part of s is left.
+        let x0 = lo.max(self.min_part).max(s.saturating_sub(slots.saturating_mul(m)));
+        if x0 > m.min(s) {
+            return None;
+        }
+        if self.fits(x0, s - x0, slots) {
+            return Some(x0);
+        }
+        let c = (s - x0).div_ceil(m) - 1;
+        if c > 0 && self.fits(s - c * m, c * m, slots) {
+            return Some(s - c * m);
+        }
+        if s <= m {
+            Some(s)
+        } else {
+            None
+        }
+    }
+}
+
+impl Family for Limits {
+    #[cfg(feature = "validate")]
+    fn validator(&self, n: usize) -> Validator<usize> {
+        Validator::new("restricted::Restricted", n, Order::Lex)
+            .min_part(self.min_part)
+            .max_part(self.max_part)
+            .parts(0, self.max_parts)
+    }
+}
+
+/// Iterates over the partitions of a number within the limits of a
+/// `PartitionsBuilder`, in the order of `Partitions`.
+#[derive(Debug)]
+pub struct Restricted {
+    n: usize,
+    limits: Limits,
+    p: Vec<usize>,
+    state: State,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum State {
+    Fresh,
+    Going,
+    Done,
+}
+
+impl Restricted {
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Restarts the iterator at the first partition of `n` within the
+    /// same limits.
+    #[inline]
+    pub fn reinit(&mut self, n: usize) {
+        self.n = n;
+        self.p.clear();
+        self.state = State::Fresh;
+    }
+
+    /// Destroys the iterator and returns a vector for further use.
+    ///
+    /// You only need to call this function if you want to reuse the
+    /// vector for something else. Its contents will be in an undefined
+    /// state, and so cannot be relied upon.
+    #[inline]
+    pub fn end(self) -> Vec<usize> {
+        self.p
+    }
+}
+
+impl StreamingIterator for Restricted {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        match self.state {
+            State::Going => Some(&self.p),
+            _ => None,
+        }
+    }
+
+    fn advance(&mut self) {
+        let found = match self.state {
+            State::Fresh => start(&self.limits, self.n, &mut self.p),
+            State::Going => step(&self.limits, &mut self.p),
+            State::Done => false,
+        };
+        self.state = if found { State::Going } else { State::Done };
+    }
+}
+
+impl PartitionIterator for Restricted {
+    #[inline]
+    fn n(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn reinit(&mut self, n: usize) {
+        Restricted::reinit(self, n)
+    }
+
+    #[inline]
+    fn end(self) -> Vec<usize> {
+        Restricted::end(self)
+    }
+}
+
+#[test]
+fn limits() {
+    //! Compares every combination of limits against filtering
+    //! `Partitions`.
+
+    use Partitions;
+
+    for n in 0..20 {
+        for a in 0..4 {
+            for m in 0..(n + 2) {
+                for k in 0..(n + 2) {
+                    let mut expected = Vec::new();
+                    let mut pp = Partitions::new(n);
+                    while let Some(p) = pp.next() {
+                        if p.len() <= k && p.iter().all(|&x| a <= x && x <= m) || n == 0 {
+                            expected.push(p.to_vec());
+                        }
+                    }
+
+                    let mut got = Vec::new();
+                    let mut rr = Partitions::builder(n).min_part(a).max_part(m).max_parts(k).build();
+                    while let Some(p) = rr.next() {
+                        got.push(p.to_vec());
+                    }
+                    assert_eq!(got, expected, "n = {}, min {}, max {}, at most {} parts", n, a, m, k);
+                    assert_eq!(rr.next(), None);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,970 @@
+//! Random partitions.
+//!
+//! This module is only available with the `rand` feature.
+
+use std::collections::HashMap;
+
+use rand::Rng;
+
+/// A Boltzmann sampler, which draws partitions of random size.
+///
+/// A partition `λ` is drawn with probability proportional to `x^|λ|`,
+/// so all partitions of the same size are equally likely, and the size
+/// itself is random. This works by drawing the multiplicity of each part
+/// `k` independently from a geometric distribution with ratio `x^k`,
+/// which is far cheaper than drawing a partition of an exact size.
+///
+/// Parts larger than a cutoff, chosen so that the probability of any of
+/// them appearing is below 2⁻⁶⁰, are never drawn.
+///
+/// # Examples
+///
+/// ```
+/// extern crate rand;
+/// # extern crate integer_partitions;
+/// use integer_partitions::sample::Boltzmann;
+///
+/// # fn main() {
+/// let b = Boltzmann::with_expected_size(1000.0);
+/// let p = b.sample(&mut rand::thread_rng());
+/// assert!(p.windows(2).all(|w| w[0] <= w[1]));
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Boltzmann {
+    x: f64,
+    ln_x: f64,
+    max_part: usize,
+}
+
+impl Boltzmann {
+    /// Makes a sampler with parameter `x`.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `0 < x < 1`.
+    pub fn new(x: f64) -> Boltzmann {
+        assert!(x > 0.0 && x < 1.0, "x must be between 0 and 1");
+
+        let ln_x = x.ln();
+        let cutoff = (1.0 - x).ln() - 60.0 * 2f64.ln();
+        let max_part = (cutoff / ln_x).ceil().max(1.0) as usize;
+
+        Boltzmann { x, ln_x, max_part }
+    }
+
+    /// Makes a sampler whose partitions have expected size `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `n` is positive and finite.
+    pub fn with_expected_size(n: f64) -> Boltzmann {
+        assert!(n > 0.0 && n.is_finite(), "size must be positive");
+
+        // Bisect on t = -ln x, starting from the asymptotic estimate.
+        let guess = ::std::f64::consts::PI / (6.0 * n).sqrt();
+        let (mut lo, mut hi) = (guess / 4.0, guess * 4.0);
+        while expected_size(-lo) < n {
+            lo /= 2.0;
+        }
+        while expected_size(-hi) > n {
+            hi *= 2.0;
+        }
+        for _ in 0..100 {
+            let mid = (lo + hi) / 2.0;
+            if expected_size(-mid) > n {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+
+        Boltzmann::new((-(lo + hi) / 2.0).exp())
+    }
+
+    /// Returns the parameter `x`.
+    #[inline]
+    pub fn x(&self) -> f64 {
+        self.x
+    }
+
+    /// Returns the expected size of the partitions drawn.
+    #[inline]
+    pub fn expected_size(&self) -> f64 {
+        expected_size(self.ln_x)
+    }
+
+    /// Draws a partition, with parts in non-decreasing order.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<usize> {
+        let mut out = Vec::new();
+        self.sample_into(rng, &mut out);
+        out
+    }
+
+    /// Draws a partition into `out`, which is cleared first.
+    pub fn sample_into<R: Rng + ?Sized>(&self, rng: &mut R, out: &mut Vec<usize>) {
+        out.clear();
+        for k in 1..(self.max_part + 1) {
+            for _ in 0..geometric(rng, k as f64 * self.ln_x) {
+                out.push(k);
+            }
+        }
+    }
+}
+
+/// Draws `j` with probability `(1 - r) r^j`, where `ln_r = ln r`.
+#[inline]
+fn geometric<R: Rng + ?Sized>(rng: &mut R, ln_r: f64) -> usize {
+    let u: f64 = 1.0 - rng.gen::<f64>();
+    (u.ln() / ln_r).floor() as usize
+}
+
+/// The expected size `Σ k x^k / (1 - x^k)` of a Boltzmann partition.
+fn expected_size(ln_x: f64) -> f64 {
+    let mut sum = 0.0;
+    let mut k = 1.0;
+    loop {
+        let xk = (k * ln_x).exp();
+        let term = k * xk / (1.0 - xk);
+        sum += term;
+        if term < sum * 1e-17 {
+            return sum;
+        }
+        k += 1.0;
+    }
+}
+
+/// Draws a partition of `n` uniformly at random, with parts in
+/// non-decreasing order.
+///
+/// This is the probabilistic divide-and-conquer of Arratia and DeSalvo:
+/// the multiplicities of the parts from 2 on are drawn as by a
+/// `Boltzmann` sampler with `x = e^(-π/√(6n))`, about right for size
+/// `n`, and the rest is made up with ones, which is kept with
+/// probability `x^r` for `r` ones. Conditioned on being kept, every
+/// partition of `n` is equally likely whatever `x` is, and a draw is
+/// kept about once in every `O(n^(1/4))` tries.
+///
+/// # Examples
+///
+/// ```
+/// extern crate rand;
+/// # extern crate integer_partitions;
+/// use integer_partitions::sample::random_partition;
+///
+/// # fn main() {
+/// let p = random_partition(1000, &mut rand::thread_rng());
+/// assert_eq!(p.iter().sum::<usize>(), 1000);
+/// # }
+/// ```
+pub fn random_partition<R: Rng + ?Sized>(n: usize, rng: &mut R) -> Vec<usize> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let b = Boltzmann::new((-::std::f64::consts::PI / (6.0 * n as f64).sqrt()).exp());
+    let mut rest = Vec::new();
+    'draw: loop {
+        rest.clear();
+        let mut s = 0;
+        for k in 2..(b.max_part.min(n) + 1) {
+            let z = geometric(rng, k as f64 * b.ln_x);
+            s += k * z;
+            if s > n {
+                continue 'draw;
+            }
+            for _ in 0..z {
+                rest.push(k);
+            }
+        }
+        let ones = n - s;
+        if rng.gen::<f64>() < (ones as f64 * b.ln_x).exp() {
+            let mut out = vec![1; ones];
+            out.extend_from_slice(&rest);
+            return out;
+        }
+    }
+}
+
+/// Draws a partition of `n` into at most `k` parts uniformly at random,
+/// like `AtMostParts::sample`.
+///
+/// This sets up a table of `O(nk)` counts for the one draw; make an
+/// `AtMostParts` to draw many.
+pub fn random_partition_at_most<R: Rng + ?Sized>(n: usize, k: usize, rng: &mut R) -> Option<Vec<usize>> {
+    AtMostParts::new(n, k).sample(rng)
+}
+
+/// A uniform sampler for the partitions of `n` into at most `k` parts.
+///
+/// The sampler draws the conjugate, a partition with parts at most `k`,
+/// from the largest part down, taking another copy of the current part
+/// with probability proportional to the number of ways to complete it.
+/// The counts are kept as floating point numbers, so setting up takes
+/// `O(nk)` time and space and the distribution is uniform up to
+/// rounding.
+///
+/// # Examples
+///
+/// ```
+/// extern crate rand;
+/// # extern crate integer_partitions;
+/// use integer_partitions::sample::AtMostParts;
+///
+/// # fn main() {
+/// let s = AtMostParts::new(100, 5);
+/// let p = s.sample(&mut rand::thread_rng()).unwrap();
+/// assert!(p.len() <= 5 && p.iter().sum::<usize>() == 100);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AtMostParts {
+    n: usize,
+    k: usize,
+    // counts[m * (k + 1) + j] is the number of partitions of m into
+    // parts no larger than j.
+    counts: Vec<f64>,
+}
+
+impl AtMostParts {
+    /// Makes a new sampler.
+    pub fn new(n: usize, k: usize) -> AtMostParts {
+        let k = k.min(n);
+        let w = k + 1;
+        let mut counts = vec![0.0; (n + 1) * w];
+        for c in &mut counts[..w] {
+            *c = 1.0;
+        }
+        for m in 1..(n + 1) {
+            for j in 1..w {
+                let mut c = counts[m * w + j - 1];
+                if m >= j {
+                    c += counts[(m - j) * w + j];
+                }
+                counts[m * w + j] = c;
+            }
+        }
+        AtMostParts { n, k, counts }
+    }
+
+    /// Returns the (approximate) number of partitions of `n` into at
+    /// most `k` parts.
+    pub fn count(&self) -> f64 {
+        self.counts[self.n * (self.k + 1) + self.k]
+    }
+
+    /// Draws a partition, with parts in non-decreasing order, or returns
+    /// `None` if there are none.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Vec<usize>> {
+        if self.count() == 0.0 {
+            return None;
+        }
+
+        // Draw the conjugate, largest part first.
+        let w = self.k + 1;
+        let mut m = self.n;
+        let mut j = self.k;
+        let mut columns = Vec::new();
+        while m > 0 {
+            let all = self.counts[m * w + j];
+            let smaller = self.counts[m * w + j - 1];
+            if m >= j && rng.gen::<f64>() * all >= smaller {
+                columns.push(j);
+                m -= j;
+            } else {
+                j -= 1;
+            }
+        }
+
+        // Row i, from the shortest, has a cell in every column at least
+        // k - i high.
+        let rows = (1..(self.k + 1)).rev()
+            .map(|i| columns.iter().take_while(|&&c| c >= i).count())
+            .filter(|&r| r > 0)
+            .collect();
+        Some(rows)
+    }
+}
+
+/// A uniform sampler for self-conjugate partitions of `n`.
+///
+/// A self-conjugate partition with a Durfee square of side `d` is the
+/// square with a partition into at most `d` parts to its right and the
+/// conjugate of that partition below it. The sampler picks `d` and then
+/// the partition to the right, each with probability proportional to
+/// the number of ways to complete it. The counts are kept as floating
+/// point numbers, so setting up takes `O(n^1.5)` time and space and the
+/// distribution is uniform up to rounding.
+#[derive(Debug, Clone)]
+pub struct SelfConjugate {
+    n: usize,
+    d: usize,
+    // counts[m * (d + 1) + j] is the number of partitions of m into
+    // parts no larger than j.
+    counts: Vec<f64>,
+}
+
+impl SelfConjugate {
+    /// Makes a new sampler.
+    pub fn new(n: usize) -> SelfConjugate {
+        let mut d = 0;
+        while (d + 1) * (d + 1) <= n {
+            d += 1;
+        }
+
+        let w = d + 1;
+        let rows = n / 2 + 1;
+        let mut counts = vec![0.0; rows * w];
+        for c in &mut counts[..w] {
+            *c = 1.0;
+        }
+        for m in 1..rows {
+            for j in 1..w {
+                let mut c = counts[m * w + j - 1];
+                if m >= j {
+                    c += counts[(m - j) * w + j];
+                }
+                counts[m * w + j] = c;
+            }
+        }
+
+        SelfConjugate { n, d, counts }
+    }
+
+    /// Returns the (approximate) number of self-conjugate partitions of `n`.
+    pub fn count(&self) -> f64 {
+        (0..(self.d + 1)).map(|d| self.weight(d)).sum()
+    }
+
+    fn weight(&self, d: usize) -> f64 {
+        if d * d > self.n || (self.n - d * d) % 2 == 1 {
+            0.0
+        } else {
+            self.counts[(self.n - d * d) / 2 * (self.d + 1) + d]
+        }
+    }
+
+    /// Draws a partition, with parts in non-decreasing order, or returns
+    /// `None` if `n` has no self-conjugate partitions.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Vec<usize>> {
+        let total = self.count();
+        if total == 0.0 {
+            return None;
+        }
+
+        let mut u = rng.gen::<f64>() * total;
+        let mut d = 0;
+        while d < self.d && u >= self.weight(d) {
+            u -= self.weight(d);
+            d += 1;
+        }
+        while self.weight(d) == 0.0 {
+            d -= 1;
+        }
+
+        // Draw the part below the square, with parts at most d, largest first.
+        let w = self.d + 1;
+        let mut m = (self.n - d * d) / 2;
+        let mut j = d;
+        let mut below = Vec::new();
+        while m > 0 {
+            let all = self.counts[m * w + j];
+            let smaller = self.counts[m * w + j - 1];
+            if m >= j && rng.gen::<f64>() * all >= smaller {
+                below.push(j);
+                m -= j;
+            } else {
+                j -= 1;
+            }
+        }
+
+        // The rows through the square are d plus the conjugate of that.
+        let mut out: Vec<usize> = below.iter().rev().cloned().collect();
+        for i in (1..(d + 1)).rev() {
+            out.push(d + below.iter().take_while(|&&b| b >= i).count());
+        }
+        Some(out)
+    }
+}
+
+/// A uniform sampler for the `t`-cores of size `n`.
+///
+/// The `t`-cores are in bijection with the integer vectors
+/// `(v_0, …, v_{t-1})` summing to zero, with the size of the core being
+/// `t/2 Σ v_i² + Σ i v_i` (Garvan, Kim and Stanton). The sampler counts
+/// the vectors with each possible prefix, memoising as it goes, and
+/// chooses each coordinate with probability proportional to the number
+/// of completions. Counts are exact, so the distribution is uniform.
+#[derive(Debug, Clone)]
+pub struct TCores {
+    t: usize,
+    n: usize,
+    bound: i64,
+    memo: HashMap<(usize, i64, i64), u128>,
+}
+
+impl TCores {
+    /// Makes a new sampler.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t` is zero.
+    pub fn new(t: usize, n: usize) -> TCores {
+        assert!(t > 0, "t must be positive");
+
+        // As the coordinates sum to zero, twice the size can be written
+        // as a sum of t v_i² + (2i - t + 1) v_i, with every term at least
+        // -t/4, which bounds each coordinate.
+        let mut bound = 0;
+        while 4 * t * bound * bound - 4 * (t - 1) * bound <= 8 * n + t * t {
+            bound += 1;
+        }
+
+        TCores {
+            t,
+            n,
+            bound: bound as i64,
+            memo: HashMap::new(),
+        }
+    }
+
+    /// Returns the number of `t`-cores of size `n`.
+    pub fn count(&mut self) -> u128 {
+        self.completions(0, 2 * self.n as i64, 0)
+    }
+
+    /// Twice the contribution of coordinate `i` having value `v`.
+    #[inline]
+    fn term(&self, i: usize, v: i64) -> i64 {
+        self.t as i64 * v * v + 2 * i as i64 * v
+    }
+
+    /// Counts the ways to choose coordinates `i..` given that the earlier
+    /// ones sum to `sum` and leave `rest` of twice the size to make up.
+    fn completions(&mut self, i: usize, rest: i64, sum: i64) -> u128 {
+        if i == self.t - 1 {
+            return (self.term(i, -sum) == rest && sum.abs() <= self.bound) as u128;
+        }
+        if let Some(&c) = self.memo.get(&(i, rest, sum)) {
+            return c;
+        }
+
+        let mut c = 0;
+        for v in -self.bound..(self.bound + 1) {
+            let r = rest - self.term(i, v);
+            if r >= -((self.t * self.t) as i64) {
+                c += self.completions(i + 1, r, sum + v);
+            }
+        }
+
+        self.memo.insert((i, rest, sum), c);
+        c
+    }
+
+    /// Draws a `t`-core, with parts in non-decreasing order, or returns
+    /// `None` if there are no `t`-cores of size `n`.
+    pub fn sample<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<Vec<usize>> {
+        let total = self.count();
+        if total == 0 {
+            return None;
+        }
+
+        let mut u = rng.gen_range(0..total);
+        let (mut rest, mut sum) = (2 * self.n as i64, 0);
+        let mut v = Vec::with_capacity(self.t);
+        for i in 0..(self.t - 1) {
+            for x in -self.bound..(self.bound + 1) {
+                let r = rest - self.term(i, x);
+                let c = if r >= -((self.t * self.t) as i64) {
+                    self.completions(i + 1, r, sum + x)
+                } else {
+                    0
+                };
+                if u < c {
+                    v.push(x);
+                    rest = r;
+                    sum += x;
+                    break;
+                }
+                u -= c;
+            }
+        }
+        v.push(-sum);
+
+        // Put v_i + s beads on runner i of the abacus, and read off the
+        // partition from the bead positions.
+        let s = 1 - v.iter().cloned().min().unwrap_or(0).min(0);
+        let mut beads = Vec::new();
+        for (i, &x) in v.iter().enumerate() {
+            for k in 0..(x + s) {
+                beads.push(i as i64 + self.t as i64 * k);
+            }
+        }
+        beads.sort();
+
+        Some(beads.iter().enumerate()
+            .map(|(j, &b)| (b - j as i64) as usize)
+            .filter(|&x| x > 0)
+            .collect())
+    }
+}
+
+/// A sampler for partitions in a box, weighted by size.
+///
+/// A partition with at most `rows` parts, each at most `cols`, is drawn
+/// with probability proportional to `q^|λ|`. The weights add up to the
+/// Gaussian binomial coefficient `[rows + cols, rows]_q`, and the
+/// sampler walks the boundary of the box from its corner, either taking
+/// the top row at full width or dropping the last column, with the
+/// probabilities that ratios of Gaussian coefficients give. So each
+/// sample takes `O(rows + cols)` time, and there is nothing to set up.
+///
+/// For `q > 1` it draws the complement of a partition from the sampler
+/// with `1 / q`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate rand;
+/// # extern crate integer_partitions;
+/// use integer_partitions::sample::InBox;
+///
+/// # fn main() {
+/// let b = InBox::new(10, 20, 0.9);
+/// let p = b.sample(&mut rand::thread_rng());
+/// assert!(p.len() <= 10 && p.iter().all(|&x| x <= 20));
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct InBox {
+    rows: usize,
+    cols: usize,
+    q: f64,
+}
+
+impl InBox {
+    /// Makes a sampler for the box of `rows` by `cols` with weight `q`.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `q` is positive and finite.
+    pub fn new(rows: usize, cols: usize, q: f64) -> InBox {
+        assert!(q > 0.0 && q.is_finite(), "q must be positive");
+        InBox { rows, cols, q }
+    }
+
+    /// Returns the parameter `q`.
+    #[inline]
+    pub fn q(&self) -> f64 {
+        self.q
+    }
+
+    /// Draws a partition, with parts in non-decreasing order.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<usize> {
+        let mut out = Vec::new();
+        self.sample_into(rng, &mut out);
+        out
+    }
+
+    /// Draws a partition into `out`, which is cleared first.
+    pub fn sample_into<R: Rng + ?Sized>(&self, rng: &mut R, out: &mut Vec<usize>) {
+        out.clear();
+        let flip = self.q > 1.0;
+        let ln_q = if flip { -self.q.ln() } else { self.q.ln() };
+
+        // Of the partitions in an a by b box, those with a part of b
+        // have weight q^b [a + b - 1, a - 1] and the rest [a + b - 1, a],
+        // a share of (1 - q^b) / (1 - q^(a + b)).
+        let (mut a, mut b) = (self.rows, self.cols);
+        while a > 0 && b > 0 {
+            let narrower = if ln_q == 0.0 {
+                b as f64 / (a + b) as f64
+            } else {
+                (b as f64 * ln_q).exp_m1() / ((a + b) as f64 * ln_q).exp_m1()
+            };
+            if rng.gen::<f64>() < narrower {
+                b -= 1;
+            } else {
+                out.push(b);
+                a -= 1;
+            }
+        }
+
+        if flip {
+            // The rows of the complement, from the shortest, fill in
+            // what is missing from the longest.
+            let mut comp: Vec<usize> = out.iter().map(|&x| self.cols - x).filter(|&x| x > 0).collect();
+            if self.cols > 0 {
+                comp.extend((out.len()..self.rows).map(|_| self.cols));
+            }
+            *out = comp;
+        } else {
+            out.reverse();
+        }
+    }
+}
+
+/// A sampler for multiplicative measures on the partitions of `n`.
+///
+/// A partition with `m_k` parts of size `k` for each `k` is drawn with
+/// probability proportional to `∏ w(k, m_k)` over the sizes that occur,
+/// for a weight function `w` given when the sampler is made. Weights
+/// `w(k, m) = x_k^m` give each part size a weight of its own, and
+/// `ewens` gives the Ewens measure. The sampler tabulates the total
+/// weight of the partitions of each `s ≤ n` into parts at most `k`, in
+/// floating point, so setting up takes `O(n²)` space and
+/// `O(n² log n)` time, and then chooses the multiplicities from the
+/// largest part down.
+///
+/// # Examples
+///
+/// ```
+/// extern crate rand;
+/// # extern crate integer_partitions;
+/// use integer_partitions::sample::Multiplicative;
+///
+/// # fn main() {
+/// // Only odd parts, with the larger ones more likely.
+/// let m = Multiplicative::new(30, |k, c| if k % 2 == 1 { (k as f64).powi(c as i32) } else { 0.0 });
+/// let p = m.sample(&mut rand::thread_rng()).unwrap();
+/// assert_eq!(p.iter().sum::<usize>(), 30);
+/// assert!(p.iter().all(|&x| x % 2 == 1));
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Multiplicative {
+    n: usize,
+    // weights[k][m] is w(k, m), for m ≤ n / k.
+    weights: Vec<Vec<f64>>,
+    // totals[k * (n + 1) + s] is the total weight of the partitions of s
+    // into parts no larger than k.
+    totals: Vec<f64>,
+}
+
+impl Multiplicative {
+    /// Makes a sampler for the weights `w(k, m)` of `m` parts of size
+    /// `k`, which is called for every `k ≥ 1` and `1 ≤ m ≤ n / k`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any weight is negative or not finite.
+    pub fn new<W: Fn(usize, usize) -> f64>(n: usize, w: W) -> Multiplicative {
+        let mut weights = vec![vec![1.0]];
+        for k in 1..(n + 1) {
+            let mut row = vec![1.0];
+            for m in 1..(n / k + 1) {
+                let x = w(k, m);
+                assert!(x >= 0.0 && x.is_finite(), "weights must be non-negative and finite");
+                row.push(x);
+            }
+            weights.push(row);
+        }
+
+        let len = n + 1;
+        let mut totals = vec![0.0; len * len];
+        totals[0] = 1.0;
+        for k in 1..len {
+            for s in 0..len {
+                totals[k * len + s] = weights[k].iter().enumerate()
+                    .filter(|&(m, _)| m * k <= s)
+                    .map(|(m, &x)| x * totals[(k - 1) * len + s - m * k])
+                    .sum();
+            }
+        }
+
+        Multiplicative { n, weights, totals }
+    }
+
+    /// Makes a sampler for the Ewens measure with parameter `theta`,
+    /// with weights `w(k, m) = (θ / k)^m / m!`.
+    ///
+    /// This is the distribution of the cycle type of a random
+    /// permutation of `n` whose probability is proportional to
+    /// `θ^(cycles)`, so that `theta = 1` gives the cycle type of a
+    /// uniformly random permutation.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `theta` is positive and finite.
+    pub fn ewens(n: usize, theta: f64) -> Multiplicative {
+        assert!(theta > 0.0 && theta.is_finite(), "theta must be positive");
+        Multiplicative::new(n, |k, m| {
+            let r = theta / k as f64;
+            (1..(m + 1)).fold(1.0, |x, i| x * r / i as f64)
+        })
+    }
+
+    /// Returns the (approximate) total weight of the partitions of `n`.
+    pub fn total_weight(&self) -> f64 {
+        self.totals[self.n * (self.n + 1) + self.n]
+    }
+
+    /// Draws a partition, with parts in non-decreasing order, or returns
+    /// `None` if every partition of `n` has weight zero.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Vec<usize>> {
+        if self.total_weight() == 0.0 {
+            return None;
+        }
+
+        let len = self.n + 1;
+        let mut s = self.n;
+        let mut out = Vec::new();
+        for k in (1..len).rev() {
+            if s == 0 {
+                break;
+            }
+            let mut u = rng.gen::<f64>() * self.totals[k * len + s];
+            // Fall back on the last multiplicity with any weight, in case
+            // rounding leaves u past them all.
+            let mut pick = None;
+            for (m, &x) in self.weights[k].iter().enumerate().take_while(|&(m, _)| m * k <= s) {
+                let c = x * self.totals[(k - 1) * len + s - m * k];
+                if c > 0.0 {
+                    pick = Some(m);
+                    if u < c {
+                        break;
+                    }
+                }
+                u -= c;
+            }
+            let m = pick.expect("no multiplicity has any weight");
+            out.extend((0..m).map(|_| k));
+            s -= m * k;
+        }
+        out.reverse();
+        Some(out)
+    }
+}
+
+#[test]
+fn boltzmann_size() {
+    //! Tests that the average size is close to the target.
+
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    let mut rng = StdRng::seed_from_u64(1);
+    let b = Boltzmann::with_expected_size(100.0);
+    assert!((b.expected_size() - 100.0).abs() < 1e-6);
+
+    let trials = 4000;
+    let mut total = 0;
+    let mut buf = Vec::new();
+    for _ in 0..trials {
+        b.sample_into(&mut rng, &mut buf);
+        assert!(buf.windows(2).all(|w| w[0] <= w[1]));
+        total += buf.iter().sum::<usize>();
+    }
+
+    let mean = total as f64 / trials as f64;
+    assert!((mean - 100.0).abs() < 4.0, "mean size {}", mean);
+}
+
+#[test]
+fn uniform() {
+    //! Tests that every partition of 8, and of 12 into at most 4 parts,
+    //! is drawn about equally often.
+
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use Partitions;
+
+    let mut rng = StdRng::seed_from_u64(5);
+    for &(n, k) in [(8, 8), (12, 4)].iter() {
+        let mut all = Vec::new();
+        let mut pp = Partitions::builder(n).max_parts(k).build();
+        while let Some(p) = pp.next() {
+            all.push(p.to_vec());
+        }
+        let s = AtMostParts::new(n, k);
+        assert_eq!(s.count(), all.len() as f64);
+
+        let trials = 2000 * all.len();
+        let mut hits = vec![0; all.len()];
+        let mut hits_at_most = vec![0; all.len()];
+        for _ in 0..trials {
+            if k == n {
+                let p = random_partition(n, &mut rng);
+                hits[all.iter().position(|q| *q == p).unwrap()] += 1;
+            }
+            let p = s.sample(&mut rng).unwrap();
+            hits_at_most[all.iter().position(|q| *q == p).unwrap()] += 1;
+        }
+        if k == n {
+            assert!(hits.iter().all(|&h| h > 1800 && h < 2200), "{:?}", hits);
+        }
+        assert!(hits_at_most.iter().all(|&h| h > 1800 && h < 2200), "{:?}", hits_at_most);
+    }
+
+    assert_eq!(random_partition(0, &mut rng), Vec::<usize>::new());
+    assert_eq!(random_partition_at_most(0, 0, &mut rng), Some(Vec::new()));
+    assert_eq!(random_partition_at_most(3, 0, &mut rng), None);
+    assert_eq!(random_partition(100_000, &mut rng).iter().sum::<usize>(), 100_000);
+}
+
+#[test]
+fn self_conjugate() {
+    //! Tests that every self-conjugate partition of 20 is drawn about
+    //! equally often.
+
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use Partitions;
+
+    let n = 20;
+    let mut all = Vec::new();
+    let mut pp = Partitions::new(n);
+    while let Some(p) = pp.next() {
+        let conj: Vec<usize> = (1..(p[p.len() - 1] + 1)).rev()
+            .map(|i| p.iter().filter(|&&x| x >= i).count())
+            .collect();
+        if &conj[..] == p {
+            all.push(p.to_vec());
+        }
+    }
+
+    let s = SelfConjugate::new(n);
+    assert_eq!(s.count(), all.len() as f64);
+
+    let mut rng = StdRng::seed_from_u64(2);
+    let trials = 2000 * all.len();
+    let mut hits = vec![0; all.len()];
+    for _ in 0..trials {
+        let p = s.sample(&mut rng).unwrap();
+        hits[all.iter().position(|q| *q == p).unwrap()] += 1;
+    }
+    assert!(hits.iter().all(|&h| h > 1800 && h < 2200), "{:?}", hits);
+
+    assert!(SelfConjugate::new(2).sample(&mut rng).is_none());
+}
+
+#[test]
+fn t_cores() {
+    //! Compares against checking hook lengths, and tests uniformity.
+
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use Partitions;
+
+    fn is_core(p: &[usize], t: usize) -> bool {
+        let l = p.len();
+        (0..l).all(|i| {
+            let row = p[l - 1 - i];
+            (0..row).all(|j| {
+                let leg = p[..(l - 1 - i)].iter().filter(|&&x| x > j).count();
+                !(row - j + leg).is_multiple_of(t)
+            })
+        })
+    }
+
+    let mut rng = StdRng::seed_from_u64(3);
+    for t in 1..6 {
+        for n in 0..16 {
+            let mut cores = Vec::new();
+            let mut pp = Partitions::new(n);
+            while let Some(p) = pp.next() {
+                if is_core(p, t) {
+                    cores.push(p.to_vec());
+                }
+            }
+
+            let mut s = TCores::new(t, n);
+            assert_eq!(s.count(), cores.len() as u128, "t = {}, n = {}", t, n);
+
+            let trials = 200 * cores.len();
+            let mut hits = vec![0; cores.len()];
+            for _ in 0..trials {
+                let p = s.sample(&mut rng).unwrap();
+                hits[cores.iter().position(|q| *q == p).unwrap()] += 1;
+            }
+            assert!(hits.iter().all(|&h| h > 130 && h < 270), "{:?}", hits);
+        }
+    }
+}
+
+#[test]
+fn in_box() {
+    //! Tests that each partition in a 3 by 4 box is drawn about as often
+    //! as its weight says, for q below, at and above 1.
+
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use Partitions;
+
+    let (rows, cols) = (3, 4);
+    let mut all = Vec::new();
+    for n in 0..(rows * cols + 1) {
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            if p.len() <= rows && p.iter().all(|&x| x <= cols) {
+                all.push(p.to_vec());
+            }
+        }
+    }
+    assert_eq!(all.len(), 35);
+
+    let mut rng = StdRng::seed_from_u64(4);
+    for &q in &[0.6, 1.0, 1.5] {
+        let b = InBox::new(rows, cols, q);
+        let weight = |p: &[usize]| q.powi(p.iter().sum::<usize>() as i32);
+        let total: f64 = all.iter().map(|p| weight(p)).sum();
+
+        let trials = 100_000;
+        let mut hits = vec![0; all.len()];
+        for _ in 0..trials {
+            let p = b.sample(&mut rng);
+            hits[all.iter().position(|x| *x == p).unwrap()] += 1;
+        }
+        for (p, &h) in all.iter().zip(&hits) {
+            let e = trials as f64 * weight(p) / total;
+            assert!((h as f64 - e).abs() < 5.0 * e.sqrt() + 5.0, "q = {}, {:?}: {} against {}", q, p, h, e);
+        }
+    }
+
+    assert_eq!(InBox::new(0, 5, 0.5).sample(&mut rng), vec![]);
+    assert_eq!(InBox::new(5, 0, 2.0).sample(&mut rng), vec![]);
+}
+
+#[test]
+fn multiplicative() {
+    //! Tests the Ewens measure at θ = 1 against the sizes of the
+    //! conjugacy classes of the symmetric group.
+
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use Partitions;
+
+    let n = 7;
+    let mut all = Vec::new();
+    let mut pp = Partitions::new(n);
+    while let Some(p) = pp.next() {
+        // The centraliser order ∏ k^m_k m_k!.
+        let mut z = 1.0;
+        for k in 1..(n + 1) {
+            let m = p.iter().filter(|&&x| x == k).count();
+            for i in 1..(m + 1) {
+                z *= (k * i) as f64;
+            }
+        }
+        all.push((p.to_vec(), 1.0 / z));
+    }
+
+    let m = Multiplicative::ewens(n, 1.0);
+    assert!((m.total_weight() - 1.0).abs() < 1e-12);
+
+    let mut rng = StdRng::seed_from_u64(5);
+    let trials = 100_000;
+    let mut hits = vec![0; all.len()];
+    for _ in 0..trials {
+        let p = m.sample(&mut rng).unwrap();
+        hits[all.iter().position(|x| x.0 == p).unwrap()] += 1;
+    }
+    for (&(ref p, w), &h) in all.iter().zip(&hits) {
+        let e = trials as f64 * w;
+        assert!((h as f64 - e).abs() < 5.0 * e.sqrt() + 5.0, "{:?}: {} against {}", p, h, e);
+    }
+
+    let none = Multiplicative::new(5, |k, _| if k % 2 == 0 { 1.0 } else { 0.0 });
+    assert_eq!(none.total_weight(), 0.0);
+    assert!(none.sample(&mut rng).is_none());
+    assert_eq!(Multiplicative::ewens(0, 2.0).sample(&mut rng), Some(vec![]));
+}
@@ -0,0 +1,172 @@
+//! Numerical semigroups: the numbers that are sums of given parts.
+//!
+//! The numbers that have a partition into parts from a set `A` form a
+//! semigroup under addition. When the parts have no common factor only
+//! finitely many positive numbers are left out, the gaps, and the
+//! largest of them is the Frobenius number of `A`. By Schur's bound it
+//! is less than `(a - 1)(b - 1)`, for the smallest part `a` and the
+//! largest `b`, so the counts of `count::partitions_into_numbers` up to
+//! there say which numbers are sums and in how many ways.
+
+use alloc::vec::Vec;
+
+use count::partitions_into_numbers;
+
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a
+}
+
+/// The numerical semigroup generated by a set of parts.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::semigroup::NumericalSemigroup;
+///
+/// // Chicken nuggets come in boxes of 6, 9 and 20.
+/// let s = NumericalSemigroup::new(&[6, 9, 20]).unwrap();
+/// assert_eq!(s.frobenius(), Some(43));
+/// assert!(s.contains(44) && !s.contains(43));
+/// assert_eq!(s.genus(), 22);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NumericalSemigroup {
+    generators: Vec<usize>,
+    /// The number of partitions of each number below the conductor.
+    counts: Vec<Option<u128>>,
+}
+
+impl NumericalSemigroup {
+    /// Makes the semigroup generated by `parts`, or returns `None` if
+    /// they have a common factor greater than one, so that they leave
+    /// infinitely many gaps.
+    ///
+    /// Repeated parts count once. This takes time and space
+    /// proportional to the number of parts times Schur's bound.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parts` contains a zero.
+    pub fn new(parts: &[usize]) -> Option<NumericalSemigroup> {
+        assert!(!parts.contains(&0), "parts must be positive");
+        let mut generators = parts.to_vec();
+        generators.sort();
+        generators.dedup();
+        if generators.iter().fold(0, |g, &a| gcd(g, a)) != 1 {
+            return None;
+        }
+
+        let (a, b) = (generators[0], generators[generators.len() - 1]);
+        let mut counts = partitions_into_numbers((a - 1) * (b - 1), &generators);
+        let conductor = counts.iter().rposition(|&c| c == Some(0)).map_or(0, |f| f + 1);
+        counts.truncate(conductor);
+        Some(NumericalSemigroup { generators, counts })
+    }
+
+    /// Returns the generators, without repeats, in increasing order.
+    #[inline]
+    pub fn generators(&self) -> &[usize] {
+        &self.generators
+    }
+
+    /// Returns the generators that are not sums of the others, in
+    /// increasing order.
+    pub fn minimal_generators(&self) -> Vec<usize> {
+        self.generators.iter().cloned()
+            .filter(|&g| !(1..g).any(|x| self.contains(x) && self.contains(g - x)))
+            .collect()
+    }
+
+    /// Returns whether `n` is a sum of generators.
+    #[inline]
+    pub fn contains(&self, n: usize) -> bool {
+        self.counts.get(n).is_none_or(|&c| c != Some(0))
+    }
+
+    /// Counts the ways to write `n` as a sum of generators, or returns
+    /// `None` if the count does not fit in a `u128`.
+    pub fn representations(&self, n: usize) -> Option<u128> {
+        match self.counts.get(n) {
+            Some(&c) => c,
+            None => partitions_into_numbers(n, &self.generators)[n],
+        }
+    }
+
+    /// Returns the Frobenius number, the largest number that is not a
+    /// sum of generators, or `None` if every number is one.
+    #[inline]
+    pub fn frobenius(&self) -> Option<usize> {
+        self.counts.len().checked_sub(1)
+    }
+
+    /// Returns the conductor, the least number from which on every
+    /// number is a sum of generators.
+    #[inline]
+    pub fn conductor(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Returns the gaps, the positive numbers that are not sums of
+    /// generators, in increasing order.
+    pub fn gaps(&self) -> Vec<usize> {
+        (1..self.counts.len()).filter(|&n| self.counts[n] == Some(0)).collect()
+    }
+
+    /// Returns the number of gaps.
+    pub fn genus(&self) -> usize {
+        self.counts.iter().filter(|&&c| c == Some(0)).count()
+    }
+}
+
+#[test]
+fn gaps_brute_force() {
+    //! Compares against marking sums by hand, and tests the formulas for
+    //! two generators.
+
+    for a in 1..9 {
+        for b in a..14 {
+            for c in b..16 {
+                let parts = [a, b, c];
+                let s = match NumericalSemigroup::new(&parts) {
+                    Some(s) => s,
+                    None => {
+                        assert!(gcd(gcd(a, b), c) > 1);
+                        continue;
+                    }
+                };
+
+                let limit = 200;
+                let mut sums = vec![false; limit];
+                sums[0] = true;
+                for n in 1..limit {
+                    sums[n] = parts.iter().any(|&p| p <= n && sums[n - p]);
+                }
+                let gaps: Vec<usize> = (1..limit).filter(|&n| !sums[n]).collect();
+                assert_eq!(s.gaps(), gaps);
+                assert_eq!(s.genus(), gaps.len());
+                assert_eq!(s.frobenius(), gaps.last().cloned());
+                assert!((0..limit).all(|n| s.contains(n) == sums[n]));
+
+                let minimal: Vec<usize> = s.generators().iter().cloned()
+                    .filter(|&g| !(1..g).any(|x| sums[x] && sums[g - x]))
+                    .collect();
+                assert_eq!(s.minimal_generators(), minimal);
+
+                if c == b && gcd(a, b) == 1 && a > 1 {
+                    assert_eq!(s.frobenius(), Some(a * b - a - b));
+                    assert_eq!(s.genus(), (a - 1) * (b - 1) / 2);
+                }
+            }
+        }
+    }
+
+    assert!(NumericalSemigroup::new(&[4, 6]).is_none());
+    let s = NumericalSemigroup::new(&[1, 5]).unwrap();
+    assert_eq!((s.frobenius(), s.conductor(), s.gaps()), (None, 0, vec![]));
+    assert_eq!(s.representations(10), Some(3));
+}
@@ -0,0 +1,370 @@
+//! Truncated power series with coefficients modulo an integer.
+//!
+//! Series are slices of coefficients, lowest degree first. All
+//! arithmetic is done modulo `m`, which may be any integer from 1 to
+//! 2³⁰, prime or not. Products of long series use the number-theoretic
+//! transform over three primes and recombine the results with the
+//! Chinese remainder theorem, so a product of two series of length `N`
+//! takes `O(N log N)` time.
+
+use alloc::vec::Vec;
+
+use count::spt_numerator;
+
+/// The NTT primes, each with 3 as a primitive root.
+const PRIMES: [u64; 3] = [998_244_353, 167_772_161, 469_762_049];
+
+/// Below this length schoolbook multiplication is faster.
+const NAIVE: usize = 64;
+
+/// The largest modulus supported.
+pub const MAX_MODULUS: u32 = 1 << 30;
+
+/// The most terms that the sequences of this module can be worked out
+/// to, as the number-theoretic transform takes at most 2²³ points and a
+/// product of two series needs twice their length.
+pub const MAX_TERMS: usize = 1 << 22;
+
+/// Checks the arguments of the functions that return `n + 1` terms.
+fn check_terms(n: usize, m: u32) {
+    assert!(m > 0 && m <= MAX_MODULUS, "modulus out of range");
+    assert!(n < MAX_TERMS, "too many terms for the number-theoretic transform");
+}
+
+fn pow_mod(mut b: u64, mut e: u64, p: u64) -> u64 {
+    let mut r = 1;
+    b %= p;
+    while e > 0 {
+        if e & 1 == 1 {
+            r = r * b % p;
+        }
+        b = b * b % p;
+        e >>= 1;
+    }
+    r
+}
+
+fn ntt(a: &mut [u64], invert: bool, p: u64) {
+    let n = a.len();
+
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let mut w = pow_mod(3, (p - 1) / len as u64, p);
+        if invert {
+            w = pow_mod(w, p - 2, p);
+        }
+        for chunk in a.chunks_mut(len) {
+            let (lo, hi) = chunk.split_at_mut(len / 2);
+            let mut wk = 1;
+            for (x, y) in lo.iter_mut().zip(hi) {
+                let u = *x;
+                let v = *y * wk % p;
+                *x = if u + v >= p { u + v - p } else { u + v };
+                *y = if u >= v { u - v } else { u + p - v };
+                wk = wk * w % p;
+            }
+        }
+        len <<= 1;
+    }
+
+    if invert {
+        let inv = pow_mod(n as u64, p - 2, p);
+        for x in a.iter_mut() {
+            *x = *x * inv % p;
+        }
+    }
+}
+
+/// Multiplies two series modulo `m`, keeping the first `len` terms.
+///
+/// # Panics
+///
+/// Panics if `m` is zero or greater than `MAX_MODULUS`, or if the
+/// product has more than 2²³ terms before truncation.
+pub fn mul_mod(a: &[u32], b: &[u32], len: usize, m: u32) -> Vec<u32> {
+    assert!(m > 0 && m <= MAX_MODULUS, "modulus out of range");
+
+    let a = &a[..a.len().min(len)];
+    let b = &b[..b.len().min(len)];
+    let mut out = vec![0u32; len];
+    if a.is_empty() || b.is_empty() {
+        return out;
+    }
+
+    if a.len().min(b.len()) <= NAIVE {
+        let m = m as u64;
+        for (i, &x) in a.iter().enumerate() {
+            for (o, &y) in out[i..].iter_mut().zip(b) {
+                *o = ((*o as u64 + x as u64 * y as u64) % m) as u32;
+            }
+        }
+        return out;
+    }
+
+    let size = (a.len() + b.len() - 1).next_power_of_two();
+    assert!(size <= 1 << 23, "series too long");
+    let mut residues = Vec::with_capacity(3);
+    for &p in &PRIMES {
+        let mut fa: Vec<u64> = a.iter().map(|&x| x as u64 % p).collect();
+        let mut fb: Vec<u64> = b.iter().map(|&x| x as u64 % p).collect();
+        fa.resize(size, 0);
+        fb.resize(size, 0);
+        ntt(&mut fa, false, p);
+        ntt(&mut fb, false, p);
+        for (x, y) in fa.iter_mut().zip(&fb) {
+            *x = *x * y % p;
+        }
+        ntt(&mut fa, true, p);
+        residues.push(fa);
+    }
+
+    let [p1, p2, p3] = PRIMES;
+    let inv_p1 = pow_mod(p1, p2 - 2, p2);
+    let inv_p12 = pow_mod(p1 * p2 % p3, p3 - 2, p3);
+    let p12_mod = (p1 as u128 * p2 as u128 % m as u128) as u64;
+    let m = m as u64;
+
+    for (i, o) in out.iter_mut().enumerate().take(a.len() + b.len() - 1) {
+        let (r1, r2, r3) = (residues[0][i], residues[1][i], residues[2][i]);
+        let t = (r2 + p2 - r1 % p2) % p2 * inv_p1 % p2;
+        let x12 = r1 as u128 + p1 as u128 * t as u128;
+        let t = (r3 + p3 - (x12 % p3 as u128) as u64) % p3 * inv_p12 % p3;
+        *o = (((x12 % m as u128) as u64 + p12_mod * (t % m)) % m) as u32;
+    }
+
+    out
+}
+
+fn inverse_unit(a: u32, m: u32) -> Option<u32> {
+    let (mut r0, mut r1) = (m as i64, a as i64 % m as i64);
+    let (mut t0, mut t1) = (0i64, 1i64);
+    while r1 != 0 {
+        let q = r0 / r1;
+        let r = r0 - q * r1;
+        r0 = r1;
+        r1 = r;
+        let t = t0 - q * t1;
+        t0 = t1;
+        t1 = t;
+    }
+    if r0 == 1 || m == 1 {
+        Some(t0.rem_euclid(m as i64) as u32)
+    } else {
+        None
+    }
+}
+
+/// Inverts a series modulo `m`, keeping the first `len` terms.
+///
+/// Uses Newton iteration, doubling the number of correct terms with
+/// each step.
+///
+/// # Panics
+///
+/// Panics if the constant term is not invertible modulo `m`, if `m` is
+/// out of range, or if `len` is more than `MAX_TERMS`.
+pub fn inv_mod(f: &[u32], len: usize, m: u32) -> Vec<u32> {
+    assert!(m > 0 && m <= MAX_MODULUS, "modulus out of range");
+    assert!(len <= MAX_TERMS, "too many terms for the number-theoretic transform");
+    if len == 0 {
+        return Vec::new();
+    }
+
+    let c = f.first().cloned().unwrap_or(0);
+    let c = inverse_unit(c, m).expect("constant term is not invertible");
+
+    let mut g = vec![c];
+    while g.len() < len {
+        let k = (2 * g.len()).min(len);
+        // g <- g (2 - f g)
+        let mut e = mul_mod(f, &g, k, m);
+        for x in e.iter_mut() {
+            *x = if *x == 0 { 0 } else { m - *x };
+        }
+        e[0] = ((e[0] as u64 + 2) % m as u64) as u32;
+        g = mul_mod(&g, &e, k, m);
+    }
+
+    g
+}
+
+/// Returns the first `len` terms of the Euler function `∏ (1 - q^k)`
+/// modulo `m`, which by the pentagonal number theorem is sparse.
+pub(crate) fn euler_mod(len: usize, m: u32) -> Vec<u32> {
+    let mut e = vec![0u32; len];
+    let one = (1 % m as u64) as u32;
+    if len > 0 {
+        e[0] = one;
+    }
+
+    let mut k = 1;
+    loop {
+        let g1 = k * (3 * k - 1) / 2;
+        if g1 >= len {
+            break;
+        }
+        let sign = if k % 2 == 1 { (m - one) % m } else { one };
+        e[g1] = sign;
+        let g2 = g1 + k;
+        if g2 < len {
+            e[g2] = sign;
+        }
+        k += 1;
+    }
+
+    e
+}
+
+/// Returns `p(0), …, p(n)` modulo `m`, where `p` counts partitions.
+///
+/// This inverts the Euler function, which takes `O(n log n)` time and
+/// is much faster than evaluating a recurrence for each `p(k)` when `n`
+/// is in the millions.
+///
+/// # Panics
+///
+/// Panics if `m` is zero or greater than `MAX_MODULUS`, or if `n` is at
+/// least `MAX_TERMS`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::series::partition_numbers_mod;
+///
+/// let p = partition_numbers_mod(10, 1000);
+/// assert_eq!(p, vec![1, 1, 2, 3, 5, 7, 11, 15, 22, 30, 42]);
+///
+/// // Ramanujan's congruence p(5k + 4) ≡ 0 (mod 5).
+/// let p = partition_numbers_mod(100_000, 5);
+/// assert!(p.iter().skip(4).step_by(5).all(|&x| x == 0));
+/// ```
+pub fn partition_numbers_mod(n: usize, m: u32) -> Vec<u32> {
+    check_terms(n, m);
+    inv_mod(&euler_mod(n + 1, m), n + 1, m)
+}
+
+/// Stretches a series in `q` to one in `q^k`, keeping `len` terms.
+fn stretch(f: &[u32], k: usize, len: usize) -> Vec<u32> {
+    let mut out = vec![0u32; len];
+    for (i, &c) in f.iter().enumerate().take(len.div_ceil(k)) {
+        out[i * k] = c;
+    }
+    out
+}
+
+/// Returns the numbers of overpartitions of `0, …, n` modulo `m`.
+///
+/// Their generating function is `(q²; q²)_∞ / (q; q)_∞²`, which is
+/// worked out with a couple of products of series.
+///
+/// # Panics
+///
+/// Panics if `m` is zero or greater than `MAX_MODULUS`, or if `n` is at
+/// least `MAX_TERMS`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::series::overpartition_numbers_mod;
+///
+/// assert_eq!(overpartition_numbers_mod(6, 1000), vec![1, 2, 4, 8, 14, 24, 40]);
+/// ```
+pub fn overpartition_numbers_mod(n: usize, m: u32) -> Vec<u32> {
+    let len = n + 1;
+    let p = partition_numbers_mod(n, m);
+    let e2 = stretch(&euler_mod(len.div_ceil(2), m), 2, len);
+    mul_mod(&mul_mod(&p, &p, len, m), &e2, len, m)
+}
+
+/// Returns the numbers of overpartitions of `0, …, n` into odd parts
+/// modulo `m`.
+///
+/// Their generating function is
+/// `(q²; q²)_∞³ / ((q; q)_∞² (q⁴; q⁴)_∞)`.
+///
+/// # Panics
+///
+/// Panics if `m` is zero or greater than `MAX_MODULUS`, or if `n` is at
+/// least `MAX_TERMS`.
+pub fn odd_overpartition_numbers_mod(n: usize, m: u32) -> Vec<u32> {
+    check_terms(n, m);
+    let len = n + 1;
+    let e1 = euler_mod(len, m);
+    let e2 = stretch(&euler_mod(len.div_ceil(2), m), 2, len);
+    let e4 = stretch(&euler_mod(len.div_ceil(4), m), 4, len);
+    let den = mul_mod(&mul_mod(&e1, &e1, len, m), &e4, len, m);
+    let num = mul_mod(&mul_mod(&e2, &e2, len, m), &e2, len, m);
+    mul_mod(&num, &inv_mod(&den, len, m), len, m)
+}
+
+/// Returns `spt(0), …, spt(n)` modulo `m`, where `spt` counts the
+/// smallest parts over all partitions.
+///
+/// This multiplies the partition numbers by the numerator of Andrews'
+/// generating function, as given at `count::spt`, in `O(n log n)` time.
+///
+/// # Panics
+///
+/// Panics if `m` is zero or greater than `MAX_MODULUS`, or if `n` is at
+/// least `MAX_TERMS`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::series::spt_mod;
+///
+/// assert_eq!(spt_mod(8, 1000), vec![0, 1, 3, 5, 10, 14, 26, 35, 57]);
+/// ```
+pub fn spt_mod(n: usize, m: u32) -> Vec<u32> {
+    check_terms(n, m);
+    let num: Vec<u32> = spt_numerator(n)
+        .into_iter()
+        .map(|c| c.rem_euclid(m as i64) as u32)
+        .collect();
+    mul_mod(&num, &partition_numbers_mod(n, m), n + 1, m)
+}
+
+#[test]
+fn recurrence() {
+    //! Compares against the pentagonal number recurrence.
+
+    for &m in &[1, 2, 7, 1 << 16, 1_000_000_007, MAX_MODULUS] {
+        let n = 3000;
+        let m64 = m as u64;
+        let mut p = vec![0u64; n + 1];
+        p[0] = 1 % m64;
+        for i in 1..(n + 1) {
+            let mut s = 0;
+            let mut k = 1;
+            loop {
+                let g1 = k * (3 * k - 1) / 2;
+                if g1 > i {
+                    break;
+                }
+                let mut t = p[i - g1];
+                if g1 + k <= i {
+                    t = (t + p[i - g1 - k]) % m64;
+                }
+                s = if k % 2 == 1 { (s + t) % m64 } else { (s + m64 - t) % m64 };
+                k += 1;
+            }
+            p[i] = s;
+        }
+
+        let got = partition_numbers_mod(n, m);
+        assert!(got.iter().zip(&p).all(|(&a, &b)| a as u64 == b));
+    }
+}
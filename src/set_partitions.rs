@@ -0,0 +1,264 @@
+//! Set partitions, as restricted growth strings.
+//!
+//! A partition of the set `{0, …, n - 1}` into blocks is written as the
+//! string `a_0 a_1 … a_(n-1)`, where `a_i` numbers the block of `i`, and
+//! blocks are numbered in order of their smallest elements. So `a_0` is
+//! zero and each `a_i` is at most one more than all those before it.
+//! There are a Bell number of these strings for each `n`, and a Stirling
+//! number of the second kind with exactly `k` blocks.
+//!
+//! `SetPartitions` lists them in lexicographic order, optionally only
+//! those with exactly `k` blocks. The successor of a string only rewrites
+//! the part of it after the last entry that can still grow, in time
+//! proportional to that part. Like `Partitions`, it reuses one vector
+//! throughout, which can be handed in with `recycle` and taken back with
+//! `end`.
+//!
+//! # Examples
+//!
+//! ```
+//! use integer_partitions::set_partitions::SetPartitions;
+//!
+//! // {0, 1, 2}, {0, 1}{2}, {0, 2}{1}, {0}{1, 2}, {0}{1}{2}.
+//! let mut ss = SetPartitions::new(3);
+//! assert_eq!(ss.next(), Some(&[0, 0, 0][..]));
+//! assert_eq!(ss.next(), Some(&[0, 0, 1][..]));
+//! assert_eq!(ss.next(), Some(&[0, 1, 0][..]));
+//! assert_eq!(ss.next(), Some(&[0, 1, 1][..]));
+//! assert_eq!(ss.next(), Some(&[0, 1, 2][..]));
+//! assert_eq!(ss.next(), None);
+//!
+//! let mut ss = SetPartitions::blocks(4, 3);
+//! assert_eq!(ss.next(), Some(&[0, 0, 1, 2][..]));
+//! assert_eq!(ss.next(), Some(&[0, 1, 0, 2][..]));
+//! ```
+
+use alloc::vec::Vec;
+
+use streaming_iterator::StreamingIterator;
+
+/// Iterates over the partitions of `{0, …, n - 1}` as restricted growth
+/// strings.
+#[derive(Debug)]
+pub struct SetPartitions {
+    a: Vec<usize>,
+    /// max[i] is the largest of a[0..(i + 1)].
+    max: Vec<usize>,
+    n: usize,
+    k: Option<usize>,
+    state: State,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum State {
+    Fresh,
+    Going,
+    Done,
+}
+
+impl SetPartitions {
+    /// Makes a new iterator.
+    #[inline]
+    pub fn new(n: usize) -> SetPartitions {
+        SetPartitions::recycle(n, Vec::new())
+    }
+
+    /// Makes a new iterator over the partitions of `{0, …, n - 1}` into
+    /// exactly `k` blocks, in the same order as `new`.
+    #[inline]
+    pub fn blocks(n: usize, k: usize) -> SetPartitions {
+        let mut s = SetPartitions::new(n);
+        s.reinit_blocks(n, k);
+        s
+    }
+
+    /// Makes a new iterator, trying to avoid allocations.
+    ///
+    /// Any vector can be passed to this function, since its contents
+    /// will be cleared, but note that the vector will still reallocate
+    /// if its capacity is less than `n`.
+    #[inline]
+    pub fn recycle(n: usize, vec: Vec<usize>) -> SetPartitions {
+        let mut s = SetPartitions {
+            a: vec,
+            max: Vec::new(),
+            n,
+            k: None,
+            state: State::Fresh,
+        };
+        s.reinit(n);
+        s
+    }
+
+    /// Advances the iterator and returns the next set partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Returns the number of blocks of the current set partition.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no current set partition.
+    #[inline]
+    pub fn block_count(&self) -> usize {
+        self.get().expect("no current partition");
+        self.max.last().map_or(0, |&m| m + 1)
+    }
+
+    /// Restarts the iterator at the first partition of `{0, …, n - 1}`.
+    ///
+    /// The existing vector is reused, and only reallocates if its
+    /// capacity is less than `n`.
+    #[inline]
+    pub fn reinit(&mut self, n: usize) {
+        self.a.clear();
+        self.max.clear();
+        self.n = n;
+        self.k = None;
+        self.state = State::Fresh;
+    }
+
+    /// Restarts the iterator at the first partition of `{0, …, n - 1}`
+    /// into exactly `k` blocks, like `blocks`.
+    #[inline]
+    pub fn reinit_blocks(&mut self, n: usize, k: usize) {
+        self.reinit(n);
+        self.k = Some(k);
+    }
+
+    /// Destroys the iterator and returns a vector for further use.
+    ///
+    /// You only need to call this function if you want to reuse the
+    /// vector for something else. Its contents will be in an undefined
+    /// state, and so cannot be relied upon.
+    #[inline]
+    pub fn end(self) -> Vec<usize> {
+        self.a
+    }
+
+    /// Fills the string from `from` on with the least entries after a
+    /// largest block number of `m` so far, leaving `need` new blocks for
+    /// the end.
+    #[inline]
+    fn fill(&mut self, from: usize, m: usize, need: usize) {
+        let n = self.n;
+        for i in from..(n - need) {
+            self.a[i] = 0;
+            self.max[i] = m;
+        }
+        for (j, i) in ((n - need)..n).enumerate() {
+            self.a[i] = m + 1 + j;
+            self.max[i] = m + 1 + j;
+        }
+    }
+}
+
+impl StreamingIterator for SetPartitions {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        match self.state {
+            State::Going => Some(&self.a),
+            _ => None,
+        }
+    }
+
+    fn advance(&mut self) {
+        let n = self.n;
+        let found = match self.state {
+            State::Fresh => match self.k {
+                _ if n == 0 => self.k.is_none_or(|k| k == 0),
+                Some(k) if k == 0 || k > n => false,
+                k => {
+                    self.a.resize(n, 0);
+                    self.max.resize(n, 0);
+                    self.fill(1, 0, k.map_or(0, |k| k - 1));
+                    true
+                }
+            },
+            State::Going if n < 2 => false,
+            State::Going => {
+                // The last entry that can grow, with room after it for
+                // any blocks still missing.
+                let top = self.k.map(|k| k - 1);
+                let j = (1..n).rev().find(|&j| {
+                    let x = self.a[j] + 1;
+                    let m = self.max[j - 1].max(x);
+                    x <= self.max[j - 1] + 1 && top.is_none_or(|t| x <= t && t - m <= n - 1 - j)
+                });
+                match j {
+                    None => false,
+                    Some(j) => {
+                        let x = self.a[j] + 1;
+                        let m = self.max[j - 1].max(x);
+                        self.a[j] = x;
+                        self.max[j] = m;
+                        self.fill(j + 1, m, top.map_or(0, |t| t - m));
+                        true
+                    }
+                }
+            }
+            State::Done => false,
+        };
+        self.state = if found { State::Going } else { State::Done };
+    }
+}
+
+#[test]
+fn brute_force() {
+    //! Compares against the restricted growth strings found by
+    //! recursion, and the counts with the Bell and Stirling numbers.
+
+    fn all(n: usize, s: &mut Vec<usize>, out: &mut Vec<Vec<usize>>) {
+        if s.len() == n {
+            out.push(s.clone());
+            return;
+        }
+        let top = s.iter().map(|&x| x + 1).max().unwrap_or(0);
+        for x in 0..(top + 1) {
+            s.push(x);
+            all(n, s, out);
+            s.pop();
+        }
+    }
+
+    let bell = [1, 1, 2, 5, 15, 52, 203, 877, 4140, 21147];
+    let mut ss = SetPartitions::new(0);
+    for (n, &b) in bell.iter().enumerate() {
+        let mut expected = Vec::new();
+        all(n, &mut Vec::new(), &mut expected);
+        assert_eq!(expected.len(), b);
+
+        ss.reinit(n);
+        for x in &expected {
+            assert_eq!(ss.next(), Some(&x[..]));
+            assert_eq!(ss.block_count(), x.iter().max().map_or(0, |&m| m + 1));
+        }
+        assert_eq!(ss.next(), None);
+        assert_eq!(ss.next(), None);
+
+        for k in 0..(n + 2) {
+            ss.reinit_blocks(n, k);
+            for x in expected.iter().filter(|x| x.iter().max().map_or(0, |&m| m + 1) == k) {
+                assert_eq!(ss.next(), Some(&x[..]));
+            }
+            assert_eq!(ss.next(), None);
+        }
+    }
+
+    // S(9, 4).
+    let mut ss = SetPartitions::blocks(9, 4);
+    let mut c = 0;
+    while ss.next().is_some() {
+        c += 1;
+    }
+    assert_eq!(c, 7770);
+
+    let v = SetPartitions::blocks(7, 3).end();
+    let mut ss = SetPartitions::recycle(2, v);
+    assert_eq!(ss.next(), Some(&[0, 0][..]));
+}
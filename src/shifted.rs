@@ -0,0 +1,250 @@
+//! Shifted Young diagrams of strict partitions.
+//!
+//! The shifted diagram of a partition into distinct parts
+//! `λ_1 > λ_2 > … > λ_l` has `λ_i` cells in row `i`, starting in column
+//! `i`, so that each row is indented one further than the one above.
+//! Rows and columns are counted from zero, and as elsewhere in the crate
+//! strict partitions are given with their parts in increasing order.
+
+use alloc::vec::Vec;
+
+use factor::Product;
+use streaming_iterator::StreamingIterator;
+
+fn rows(p: &[usize]) -> Vec<usize> {
+    assert!(p.first() != Some(&0), "parts must be positive");
+    assert!(p.windows(2).all(|w| w[0] < w[1]), "parts must be distinct and increasing");
+    p.iter().rev().cloned().collect()
+}
+
+/// Iterates over the cells `(row, column)` of the shifted diagram of the
+/// strict partition `p`, row by row.
+///
+/// # Panics
+///
+/// Panics if the parts of `p` are not positive and strictly increasing.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::shifted::cells;
+///
+/// let c: Vec<_> = cells(&[1, 3]).collect();
+/// assert_eq!(c, vec![(0, 0), (0, 1), (0, 2), (1, 1)]);
+/// ```
+pub fn cells<'a>(p: &'a [usize]) -> impl Iterator<Item = (usize, usize)> + 'a {
+    rows(p);
+    p.iter().rev().enumerate().flat_map(|(i, &len)| (i..(i + len)).map(move |j| (i, j)))
+}
+
+/// Returns the shifted hook lengths of the strict partition `p`, as a
+/// list of rows like its diagram.
+///
+/// The shifted hook of a cell holds the cell, those to its right and
+/// those below it, and, if its column reaches the diagonal at row `j`,
+/// all of row `j + 1` as well.
+///
+/// # Panics
+///
+/// Panics if the parts of `p` are not positive and strictly increasing.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::shifted::hook_lengths;
+///
+/// assert_eq!(hook_lengths(&[1, 3]), vec![vec![4, 3, 1], vec![1]]);
+/// ```
+pub fn hook_lengths(p: &[usize]) -> Vec<Vec<usize>> {
+    let lambda = rows(p);
+    let l = lambda.len();
+
+    let mut hooks = Vec::with_capacity(l);
+    for (i, &len) in lambda.iter().enumerate() {
+        let mut row = Vec::with_capacity(len);
+        for j in i..(i + len) {
+            let arm = i + len - 1 - j;
+            let leg = ((i + 1)..l.min(j + 1)).filter(|&k| k + lambda[k] > j).count();
+            let next = if j < l { lambda.get(j + 1).cloned().unwrap_or(0) } else { 0 };
+            row.push(arm + leg + next + 1);
+        }
+        hooks.push(row);
+    }
+    hooks
+}
+
+/// Counts the standard tableaux of the shifted shape of `p`, by the
+/// shifted hook length formula `n! / ∏ h(c)`.
+///
+/// Returns `None` if the count does not fit in a `u128`.
+///
+/// # Panics
+///
+/// Panics if the parts of `p` are not positive and strictly increasing.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::shifted::count_standard;
+///
+/// assert_eq!(count_standard(&[1, 2, 3, 4]), Some(12));
+/// ```
+pub fn count_standard(p: &[usize]) -> Option<u128> {
+    let mut f = Product::new();
+    f.times_factorial(p.iter().sum());
+    for h in hook_lengths(p).iter().flat_map(|row| row.iter()) {
+        f.divide(*h);
+    }
+    f.to_u128()
+}
+
+/// Iterates over the standard tableaux of shifted shape.
+///
+/// Each tableau is a filling of the shifted diagram with
+/// `{0, …, n - 1}` that increases along rows and down columns, yielded
+/// as a list of rows from the longest down. Row `i` starts in column
+/// `i`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::shifted::ShiftedTableaux;
+///
+/// let mut st = ShiftedTableaux::new(&[1, 3]);
+/// assert_eq!(st.next(), Some(&[vec![0, 1, 2], vec![3]][..]));
+/// assert_eq!(st.next(), Some(&[vec![0, 1, 3], vec![2]][..]));
+/// assert_eq!(st.next(), None);
+/// ```
+#[derive(Debug)]
+pub struct ShiftedTableaux {
+    n: usize,
+    lambda: Vec<usize>,
+    /// The row that each entry went into.
+    word: Vec<usize>,
+    rows: Vec<Vec<usize>>,
+    state: State,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum State {
+    Fresh,
+    Going,
+    Done,
+}
+
+impl ShiftedTableaux {
+    /// Makes a new iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parts of `p` are not positive and strictly
+    /// increasing.
+    pub fn new(p: &[usize]) -> ShiftedTableaux {
+        let lambda = rows(p);
+        let n = lambda.iter().sum();
+        ShiftedTableaux {
+            n,
+            rows: lambda.iter().map(|&len| Vec::with_capacity(len)).collect(),
+            lambda,
+            word: Vec::with_capacity(n),
+            state: State::Fresh,
+        }
+    }
+
+    /// Advances the iterator and returns the next tableau.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[Vec<usize>]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Returns whether the next entry can go at the end of row `r`.
+    #[inline]
+    fn fits(&self, r: usize) -> bool {
+        let len = self.rows[r].len();
+        len < self.lambda[r] && (r == 0 || self.rows[r - 1].len() >= len + 2)
+    }
+
+    /// Extends the filling, trying rows from `start` on for the next
+    /// entry and backtracking when stuck. Returns whether a complete
+    /// tableau was found.
+    fn search(&mut self, mut start: usize) -> bool {
+        loop {
+            if self.word.len() == self.n {
+                return true;
+            }
+            match (start..self.lambda.len()).find(|&r| self.fits(r)) {
+                Some(r) => {
+                    self.rows[r].push(self.word.len());
+                    self.word.push(r);
+                    start = 0;
+                }
+                None => match self.word.pop() {
+                    Some(r) => {
+                        self.rows[r].pop();
+                        start = r + 1;
+                    }
+                    None => return false,
+                },
+            }
+        }
+    }
+}
+
+impl StreamingIterator for ShiftedTableaux {
+    type Item = [Vec<usize>];
+
+    #[inline]
+    fn get(&self) -> Option<&[Vec<usize>]> {
+        match self.state {
+            State::Going => Some(&self.rows),
+            _ => None,
+        }
+    }
+
+    fn advance(&mut self) {
+        let found = match self.state {
+            State::Fresh => self.search(0),
+            State::Going => match self.word.pop() {
+                Some(r) => {
+                    self.rows[r].pop();
+                    self.search(r + 1)
+                }
+                None => false,
+            },
+            State::Done => false,
+        };
+        self.state = if found { State::Going } else { State::Done };
+    }
+}
+
+#[test]
+fn hook_formula() {
+    //! Tests that enumeration agrees with the hook length formula.
+
+    use Partitions;
+
+    for n in 0..13 {
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            if p.windows(2).any(|w| w[0] == w[1]) {
+                continue;
+            }
+
+            let mut count = 0;
+            let mut st = ShiftedTableaux::new(p);
+            while let Some(t) = st.next() {
+                for (i, row) in t.iter().enumerate() {
+                    assert!(row.windows(2).all(|w| w[0] < w[1]));
+                    if i > 0 {
+                        assert!(row.iter().enumerate().all(|(j, &x)| t[i - 1][j + 1] < x));
+                    }
+                }
+                count += 1;
+            }
+
+            assert_eq!(Some(count), count_standard(p));
+            assert_eq!(cells(p).count(), n);
+        }
+    }
+}
@@ -0,0 +1,572 @@
+//! Partitions into few parts, using memory independent of `n`.
+//!
+//! The iterators in this module only store the parts themselves, so
+//! they work for any `n` that fits in the chosen integer type, as long
+//! as the number of parts stays small. Parts are yielded in
+//! non-decreasing order, like everywhere else in the crate.
+//!
+//! # Examples
+//!
+//! ```
+//! use integer_partitions::sparse::AtMost;
+//!
+//! let mut pp = AtMost::new(1_000_000_000_000u64, 30);
+//! let p = pp.next().unwrap();
+//! assert_eq!(p, &[1_000_000_000_000]);
+//! ```
+
+use alloc::vec::Vec;
+
+use int::Int;
+use streaming_iterator::StreamingIterator;
+#[cfg(feature = "validate")]
+use validate::{Order, Validator};
+use PartitionIterator;
+
+/// Hindenburg's algorithm for partitions into exactly `k` parts, as
+/// given in Knuth's TAOCP 7.2.1.4 (Algorithm H).
+///
+/// Knuth's `a_j` (with `a_1` the largest part) lives at `a[k - j]`.
+/// In strict mode part `a[i]` is stored with `i` added to it, which
+/// turns partitions into exactly `k` parts into distinct ones.
+#[derive(Debug)]
+struct Hindenburg<T> {
+    a: Vec<T>,
+    strict: bool,
+    state: State,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum State {
+    Fresh,
+    Going,
+    Done,
+}
+
+impl<T: Int> Hindenburg<T> {
+    fn new(strict: bool) -> Hindenburg<T> {
+        Hindenburg {
+            a: Vec::new(),
+            strict,
+            state: State::Done,
+        }
+    }
+
+    /// Restarts at the first partition of `n` into `k` parts, where `n`
+    /// is the sum before the strict offsets are added.
+    fn reset(&mut self, n: T, k: usize) {
+        self.a.clear();
+        self.state = State::Done;
+
+        if k == 0 {
+            if n == T::ZERO {
+                self.state = State::Fresh;
+            }
+            return;
+        }
+
+        let m = match T::from_usize(k - 1) {
+            Some(m) if m < n => m,
+            _ => return,
+        };
+
+        self.a.resize(k, T::ONE);
+        self.set(1, n - m);
+        for j in 2..(k + 1) {
+            self.set(j, T::ONE);
+        }
+        self.state = State::Fresh;
+    }
+
+    #[inline]
+    fn get(&self, j: usize) -> T {
+        let i = self.a.len() - j;
+        if self.strict {
+            self.a[i] - T::from_usize(i).unwrap()
+        } else {
+            self.a[i]
+        }
+    }
+
+    #[inline]
+    fn set(&mut self, j: usize, v: T) {
+        let i = self.a.len() - j;
+        self.a[i] = if self.strict {
+            v + T::from_usize(i).unwrap()
+        } else {
+            v
+        };
+    }
+
+    fn advance(&mut self) {
+        match self.state {
+            State::Fresh => {
+                self.state = State::Going;
+                return;
+            },
+            State::Done => return,
+            State::Going => {},
+        }
+
+        let m = self.a.len();
+        if m < 2 {
+            self.state = State::Done;
+            return;
+        }
+
+        let a1 = self.get(1);
+        let a2 = self.get(2);
+        if a2 + T::ONE < a1 {
+            self.set(1, a1 - T::ONE);
+            self.set(2, a2 + T::ONE);
+            return;
+        }
+
+        let mut j = 3;
+        let mut s = a1 + a2 - T::ONE;
+        while j <= m && self.get(j) + T::ONE >= a1 {
+            s = s + self.get(j);
+            j += 1;
+        }
+
+        if j > m {
+            self.state = State::Done;
+            return;
+        }
+
+        let x = self.get(j) + T::ONE;
+        self.set(j, x);
+        j -= 1;
+        while j > 1 {
+            self.set(j, x);
+            s = s - x;
+            j -= 1;
+        }
+        self.set(1, s);
+    }
+
+    #[inline]
+    fn current(&self) -> Option<&[T]> {
+        match self.state {
+            State::Going => Some(&self.a),
+            _ => None,
+        }
+    }
+}
+
+/// Iterates over the partitions of `n` into exactly `k` parts, in the
+/// order of `Partitions`.
+#[derive(Debug)]
+pub struct Exact<T> {
+    h: Hindenburg<T>,
+    n: T,
+    k: usize,
+    #[cfg(feature = "validate")]
+    check: Validator<T>,
+}
+
+impl<T: Int> Exact<T> {
+    /// Makes a new iterator.
+    #[inline]
+    pub fn new(n: T, k: usize) -> Exact<T> {
+        Exact::recycle(n, k, Vec::new())
+    }
+
+    /// Makes a new iterator, trying to avoid allocations.
+    ///
+    /// Any vector can be passed to this function, since its contents
+    /// will be cleared, but note that the vector will still reallocate
+    /// if its capacity is less than `k`.
+    pub fn recycle(n: T, k: usize, vec: Vec<T>) -> Exact<T> {
+        let mut h = Hindenburg::new(false);
+        h.a = vec;
+        h.reset(n, k);
+        Exact {
+            h,
+            n,
+            k,
+            #[cfg(feature = "validate")]
+            check: Validator::new("sparse::Exact", n, Order::Lex).parts(k, k),
+        }
+    }
+
+    /// Restarts the iterator at the first partition of `n` into exactly
+    /// `k` parts.
+    ///
+    /// The existing vector is reused, and only reallocates if its
+    /// capacity is less than `k`.
+    pub fn reinit(&mut self, n: T, k: usize) {
+        self.h.reset(n, k);
+        self.n = n;
+        self.k = k;
+        #[cfg(feature = "validate")]
+        {
+            self.check = Validator::new("sparse::Exact", n, Order::Lex).parts(k, k);
+        }
+    }
+
+    /// Destroys the iterator and returns a vector for further use.
+    ///
+    /// You only need to call this function if you want to reuse the
+    /// vector for something else. Its contents will be in an undefined
+    /// state, and so cannot be relied upon.
+    #[inline]
+    pub fn end(self) -> Vec<T> {
+        self.h.a
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[T]> {
+        StreamingIterator::next(self)
+    }
+}
+
+impl<T: Int> StreamingIterator for Exact<T> {
+    type Item = [T];
+
+    #[inline]
+    fn get(&self) -> Option<&[T]> {
+        self.h.current()
+    }
+
+    #[inline]
+    fn advance(&mut self) {
+        self.h.advance();
+        #[cfg(feature = "validate")]
+        self.check.check(self.h.current());
+    }
+}
+
+impl PartitionIterator for Exact<usize> {
+    #[inline]
+    fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Restarts the iterator at the first partition of `n` into as many
+    /// parts as before.
+    #[inline]
+    fn reinit(&mut self, n: usize) {
+        let k = self.k;
+        Exact::reinit(self, n, k)
+    }
+
+    #[inline]
+    fn end(self) -> Vec<usize> {
+        Exact::end(self)
+    }
+}
+
+/// Walks through the part counts `0..=k` in turn.
+#[derive(Debug)]
+struct Chain<T> {
+    h: Hindenburg<T>,
+    n: T,
+    j: usize,
+    k: usize,
+    #[cfg(feature = "validate")]
+    check: Validator<T>,
+}
+
+impl<T: Int> Chain<T> {
+    fn new(n: T, k: usize, strict: bool) -> Chain<T> {
+        let mut c = Chain {
+            h: Hindenburg::new(strict),
+            n,
+            j: 0,
+            k,
+            #[cfg(feature = "validate")]
+            check: Chain::validator(n, k, strict),
+        };
+        c.h.reset(n, 0);
+        c
+    }
+
+    #[cfg(feature = "validate")]
+    fn validator(n: T, k: usize, strict: bool) -> Validator<T> {
+        if strict {
+            Validator::new("sparse::Distinct", n, Order::LengthThenLex).parts(0, k).strict()
+        } else {
+            Validator::new("sparse::AtMost", n, Order::LengthThenLex).parts(0, k)
+        }
+    }
+
+    /// Restarts at the partitions of `n` into no parts at all.
+    fn reinit(&mut self, n: T) {
+        self.n = n;
+        self.j = 0;
+        self.h.reset(n, 0);
+        #[cfg(feature = "validate")]
+        {
+            self.check = Chain::validator(n, self.k, self.h.strict);
+        }
+    }
+
+    fn advance(&mut self) {
+        self.h.advance();
+
+        while self.h.state == State::Done && self.j < self.k {
+            self.j += 1;
+
+            // In strict mode j parts need at least 1 + 2 + ... + j.
+            let n = if self.h.strict {
+                let offset = self.j * (self.j - 1) / 2;
+                match T::from_usize(offset) {
+                    Some(o) if o < self.n => self.n - o,
+                    _ => return,
+                }
+            } else {
+                self.n
+            };
+
+            self.h.reset(n, self.j);
+            self.h.advance();
+        }
+
+        #[cfg(feature = "validate")]
+        self.check.check(self.h.current());
+    }
+}
+
+/// Iterates over the partitions of `n` into at most `k` parts.
+///
+/// Partitions are grouped by their number of parts, fewest first.
+#[derive(Debug)]
+pub struct AtMost<T> {
+    c: Chain<T>,
+}
+
+impl<T: Int> AtMost<T> {
+    /// Makes a new iterator.
+    pub fn new(n: T, k: usize) -> AtMost<T> {
+        AtMost {
+            c: Chain::new(n, k, false),
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[T]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Restarts the iterator at the first partition of `n` into at most
+    /// as many parts as before.
+    ///
+    /// The existing vector is reused, and only reallocates if its
+    /// capacity is less than `k`.
+    #[inline]
+    pub fn reinit(&mut self, n: T) {
+        self.c.reinit(n)
+    }
+
+    /// Destroys the iterator and returns a vector for further use.
+    ///
+    /// You only need to call this function if you want to reuse the
+    /// vector for something else. Its contents will be in an undefined
+    /// state, and so cannot be relied upon.
+    #[inline]
+    pub fn end(self) -> Vec<T> {
+        self.c.h.a
+    }
+}
+
+impl<T: Int> StreamingIterator for AtMost<T> {
+    type Item = [T];
+
+    #[inline]
+    fn get(&self) -> Option<&[T]> {
+        self.c.h.current()
+    }
+
+    #[inline]
+    fn advance(&mut self) {
+        self.c.advance()
+    }
+}
+
+impl PartitionIterator for AtMost<usize> {
+    #[inline]
+    fn n(&self) -> usize {
+        self.c.n
+    }
+
+    #[inline]
+    fn reinit(&mut self, n: usize) {
+        AtMost::reinit(self, n)
+    }
+
+    #[inline]
+    fn end(self) -> Vec<usize> {
+        AtMost::end(self)
+    }
+}
+
+/// Iterates over the partitions of `n` into at most `k` distinct parts.
+///
+/// Partitions are grouped by their number of parts, fewest first.
+#[derive(Debug)]
+pub struct Distinct<T> {
+    c: Chain<T>,
+}
+
+impl<T: Int> Distinct<T> {
+    /// Makes a new iterator.
+    pub fn new(n: T, k: usize) -> Distinct<T> {
+        Distinct {
+            c: Chain::new(n, k, true),
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[T]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Restarts the iterator at the first partition of `n` into at most
+    /// as many distinct parts as before.
+    ///
+    /// The existing vector is reused, and only reallocates if its
+    /// capacity is less than `k`.
+    #[inline]
+    pub fn reinit(&mut self, n: T) {
+        self.c.reinit(n)
+    }
+
+    /// Destroys the iterator and returns a vector for further use.
+    ///
+    /// You only need to call this function if you want to reuse the
+    /// vector for something else. Its contents will be in an undefined
+    /// state, and so cannot be relied upon.
+    #[inline]
+    pub fn end(self) -> Vec<T> {
+        self.c.h.a
+    }
+}
+
+impl<T: Int> StreamingIterator for Distinct<T> {
+    type Item = [T];
+
+    #[inline]
+    fn get(&self) -> Option<&[T]> {
+        self.c.h.current()
+    }
+
+    #[inline]
+    fn advance(&mut self) {
+        self.c.advance()
+    }
+}
+
+impl PartitionIterator for Distinct<usize> {
+    #[inline]
+    fn n(&self) -> usize {
+        self.c.n
+    }
+
+    #[inline]
+    fn reinit(&mut self, n: usize) {
+        Distinct::reinit(self, n)
+    }
+
+    #[inline]
+    fn end(self) -> Vec<usize> {
+        Distinct::end(self)
+    }
+}
+
+#[test]
+fn filtered() {
+    //! Compares against filtering the output of `Partitions`.
+
+    use Partitions;
+
+    fn collect<I: StreamingIterator<Item = [u16]>>(mut it: I) -> Vec<Vec<usize>> {
+        let mut v = Vec::new();
+        while let Some(p) = it.next() {
+            assert!(p.windows(2).all(|w| w[0] <= w[1]));
+            v.push(p.iter().map(|&x| x as usize).collect());
+        }
+        v.sort();
+        v
+    }
+
+    for n in 0..20 {
+        for k in 0..8 {
+            let mut exact = Vec::new();
+            let mut at_most = Vec::new();
+            let mut distinct = Vec::new();
+
+            let mut pp = Partitions::new(n);
+            while let Some(p) = pp.next() {
+                if p.len() == k {
+                    exact.push(p.to_vec());
+                }
+                if p.len() <= k {
+                    at_most.push(p.to_vec());
+                    if p.windows(2).all(|w| w[0] < w[1]) {
+                        distinct.push(p.to_vec());
+                    }
+                }
+            }
+            exact.sort();
+            at_most.sort();
+            distinct.sort();
+
+            assert_eq!(collect(Exact::new(n as u16, k)), exact);
+            assert_eq!(collect(AtMost::new(n as u16, k)), at_most);
+            assert_eq!(collect(Distinct::new(n as u16, k)), distinct);
+        }
+    }
+}
+
+#[test]
+fn exact_in_order() {
+    //! Tests that `Exact` comes in the order of `Partitions`, and reuses
+    //! its vector.
+
+    use {Partitions, PartitionsExact};
+
+    let mut ee = PartitionsExact::new(0, 0);
+    for n in 0..25 {
+        for k in 0..(n + 2) {
+            ee.reinit(n, k);
+            let mut pp = Partitions::new(n);
+            while let Some(p) = pp.next() {
+                if p.len() == k {
+                    assert_eq!(ee.next(), Some(p));
+                }
+            }
+            assert_eq!(ee.next(), None);
+        }
+    }
+
+    let v = ee.end();
+    let mut ee = Exact::recycle(4u8, 2, Vec::new());
+    assert_eq!(ee.next(), Some(&[1, 3][..]));
+    let mut ee = PartitionsExact::recycle(4, 4, v);
+    assert_eq!(ee.next(), Some(&[1, 1, 1, 1][..]));
+}
+
+#[test]
+fn huge() {
+    //! Tests the first few partitions of a number far too large for `Partitions`.
+
+    let n: u128 = 1 << 100;
+    let mut pp = Exact::new(n, 30);
+    for _ in 0..1000 {
+        let p = pp.next().unwrap();
+        assert_eq!(p.len(), 30);
+        assert_eq!(p.iter().sum::<u128>(), n);
+    }
+
+    let mut pp = Distinct::new(n, 30);
+    assert_eq!(pp.next(), Some(&[n][..]));
+    let p = pp.next().unwrap();
+    assert_eq!(p, &[1, n - 1]);
+}
@@ -0,0 +1,817 @@
+//! In-place successor functions for partitions and restricted families.
+//!
+//! Each family has a function that writes its first partition of `n`
+//! into a vector, and one that replaces a partition with the next one in
+//! the family, in the same lexicographic order as `Partitions`. They hold
+//! no state besides the vector itself, so a cursor can be stored, copied
+//! or moved around freely inside a larger search. Families of your own
+//! can be stepped through in the same way by implementing `Constraint`.
+//!
+//! The `next_*` functions return `false`, leaving the vector alone, when
+//! it already holds the last partition. They expect a partition of the
+//! family in non-decreasing order, and give meaningless results for
+//! anything else.
+//!
+//! # Examples
+//!
+//! ```
+//! use integer_partitions::successor::{first_exact, next_exact};
+//!
+//! let mut p = Vec::new();
+//! assert!(first_exact(7, 3, &mut p));
+//! let mut all = vec![p.clone()];
+//! while next_exact(&mut p) {
+//!     all.push(p.clone());
+//! }
+//! assert_eq!(all, vec![vec![1, 1, 5], vec![1, 2, 4], vec![1, 3, 3], vec![2, 2, 3]]);
+//! ```
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "validate")]
+use validate::{Order, Validator};
+use streaming_iterator::StreamingIterator;
+use PartitionIterator;
+
+/// A restriction on partitions that the successor functions can prune
+/// with.
+///
+/// Partitions are built a part at a time from the smallest, and each
+/// part is the one `first` picks, so a restriction that knows as soon as
+/// a prefix cannot be completed cuts off the whole subtree below it. The
+/// successor functions are generic over the constraint, so its methods
+/// are compiled into the loop.
+///
+/// The families of this module are implementations of this trait, and
+/// so are `odd::OddParts`, `Partitions::builder`, `poset::Dominated` and
+/// `young::UpToConjugation`.
+/// `Partitions`, `DistinctPartitions`, `sparse` and `supply` keep loops
+/// of their own, which do less work per step than this general one.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::successor::{Constraint, Constrained};
+///
+/// // Partitions whose neighbouring parts differ by at least 2.
+/// struct Gaps;
+///
+/// impl Constraint for Gaps {
+///     fn allows(&self, prefix: &[usize], x: usize, rest: usize) -> bool {
+///         prefix.last().is_none_or(|&y| x >= y + 2) && (rest == 0 || rest >= x + 2)
+///     }
+///
+///     fn first(&self, prefix: &[usize], lo: usize, s: usize) -> Option<usize> {
+///         // Any rest of at least x + 2 is a single last part.
+///         let x = prefix.last().map_or(lo, |&y| lo.max(y + 2));
+///         if s >= 2 * x + 2 {
+///             Some(x)
+///         } else if s >= x {
+///             Some(s)
+///         } else {
+///             None
+///         }
+///     }
+/// }
+///
+/// let mut pp = Constrained::new(9, Gaps);
+/// assert_eq!(pp.next(), Some(&[1, 3, 5][..]));
+/// assert_eq!(pp.next(), Some(&[1, 8][..]));
+/// assert_eq!(pp.next(), Some(&[2, 7][..]));
+/// assert_eq!(pp.next(), Some(&[3, 6][..]));
+/// assert_eq!(pp.next(), Some(&[9][..]));
+/// assert_eq!(pp.next(), None);
+/// ```
+pub trait Constraint {
+    /// Returns whether the part `x` can follow `prefix`, leaving `rest`
+    /// to make up with later parts.
+    ///
+    /// This is only asked for `x` at least the last part of `prefix`,
+    /// and when `rest` is zero it decides whether the partition is
+    /// complete and kept.
+    fn allows(&self, prefix: &[usize], x: usize, rest: usize) -> bool;
+
+    /// Returns the least part `x ≥ lo` that can follow `prefix`, and be
+    /// completed, given that it and the parts after it must add up to
+    /// `s`.
+    ///
+    /// This is asked once for every part placed, and whatever it returns
+    /// is trusted to have a completion, so it should work out the answer
+    /// directly rather than search for one.
+    fn first(&self, prefix: &[usize], lo: usize, s: usize) -> Option<usize>;
+}
+
+/// A constraint that the successor functions can step through, and
+/// check under the `validate` feature.
+pub(crate) trait Family: Constraint {
+    /// Returns a validator for the members of the family of size `n`.
+    #[cfg(feature = "validate")]
+    fn validator(&self, n: usize) -> Validator<usize>;
+}
+
+/// Fills `p` from its end with the least completion summing to `s`
+/// whose next part is `x`.
+fn fill<F: Family>(f: &F, p: &mut Vec<usize>, mut x: usize, mut s: usize) {
+    loop {
+        p.push(x);
+        s -= x;
+        if s == 0 {
+            return;
+        }
+        x = f.first(p, x, s).expect("family has no completion");
+    }
+}
+
+/// Writes the first partition of `n` in the family to `p`.
+pub(crate) fn start<F: Family>(f: &F, n: usize, p: &mut Vec<usize>) -> bool {
+    p.clear();
+    if n == 0 {
+        return true;
+    }
+    match f.first(p, 1, n) {
+        Some(x) => {
+            fill(f, p, x, n);
+            #[cfg(feature = "validate")]
+            f.validator(n).check(Some(p));
+            true
+        }
+        None => false,
+    }
+}
+
+/// Replaces `p` with the next partition in the family.
+///
+/// The rightmost part that can grow is increased as little as possible,
+/// and everything after it is refilled as small as possible.
+pub(crate) fn step<F: Family>(f: &F, p: &mut Vec<usize>) -> bool {
+    let mut r = match p.last() {
+        Some(&x) => x,
+        None => return false,
+    };
+    #[cfg(feature = "validate")]
+    let check = {
+        let v = f.validator(p.iter().sum());
+        v.resume(p);
+        v
+    };
+    for i in (0..(p.len() - 1)).rev() {
+        r += p[i];
+        if let Some(x) = f.first(&p[..i], p[i] + 1, r) {
+            p.truncate(i);
+            fill(f, p, x, r);
+            #[cfg(feature = "validate")]
+            check.check(Some(p));
+            return true;
+        }
+    }
+    false
+}
+
+struct Unrestricted;
+
+impl Constraint for Unrestricted {
+    #[inline]
+    fn allows(&self, _: &[usize], x: usize, rest: usize) -> bool {
+        rest == 0 || rest >= x
+    }
+
+    #[inline]
+    fn first(&self, _: &[usize], lo: usize, s: usize) -> Option<usize> {
+        if 2 * lo <= s {
+            Some(lo)
+        } else if lo <= s {
+            Some(s)
+        } else {
+            None
+        }
+    }
+}
+
+impl Family for Unrestricted {
+    #[cfg(feature = "validate")]
+    fn validator(&self, n: usize) -> Validator<usize> {
+        Validator::new("successor::next_partition", n, Order::Lex)
+    }
+}
+
+struct Strict;
+
+impl Constraint for Strict {
+    #[inline]
+    fn allows(&self, prefix: &[usize], x: usize, rest: usize) -> bool {
+        prefix.last().is_none_or(|&y| x > y) && (rest == 0 || rest > x)
+    }
+
+    #[inline]
+    fn first(&self, prefix: &[usize], lo: usize, s: usize) -> Option<usize> {
+        let lo = prefix.last().map_or(lo, |&y| lo.max(y + 1));
+        if 2 * lo < s {
+            Some(lo)
+        } else if lo <= s {
+            Some(s)
+        } else {
+            None
+        }
+    }
+}
+
+impl Family for Strict {
+    #[cfg(feature = "validate")]
+    fn validator(&self, n: usize) -> Validator<usize> {
+        Validator::new("successor::next_strict", n, Order::Lex).strict()
+    }
+}
+
+struct Exact(usize);
+
+impl Constraint for Exact {
+    #[inline]
+    fn allows(&self, prefix: &[usize], x: usize, rest: usize) -> bool {
+        match self.0.checked_sub(prefix.len() + 1) {
+            None => false,
+            Some(0) => rest == 0,
+            Some(c) => x.checked_mul(c).is_some_and(|t| t <= rest),
+        }
+    }
+
+    #[inline]
+    fn first(&self, prefix: &[usize], lo: usize, s: usize) -> Option<usize> {
+        match self.0.checked_sub(prefix.len()) {
+            None | Some(0) => None,
+            Some(1) if lo <= s => Some(s),
+            Some(1) => None,
+            Some(c) if lo.checked_mul(c).is_some_and(|t| t <= s) => Some(lo),
+            Some(_) => None,
+        }
+    }
+}
+
+impl Family for Exact {
+    #[cfg(feature = "validate")]
+    fn validator(&self, n: usize) -> Validator<usize> {
+        Validator::new("successor::next_exact", n, Order::Lex).parts(self.0, self.0)
+    }
+}
+
+struct Bounded(usize);
+
+impl Constraint for Bounded {
+    #[inline]
+    fn allows(&self, _: &[usize], x: usize, rest: usize) -> bool {
+        x <= self.0 && (rest == 0 || rest >= x)
+    }
+
+    fn first(&self, _: &[usize], lo: usize, s: usize) -> Option<usize> {
+        let m = self.0;
+        // The rest, t, needs some number c of parts in [x, m], which
+        // exists when ceil(t / m) <= floor(t / x).
+        (lo..(m.min(s) + 1)).find(|&x| {
+            let t = s - x;
+            t == 0 || t.div_ceil(m) <= t / x
+        })
+    }
+}
+
+impl Family for Bounded {
+    #[cfg(feature = "validate")]
+    fn validator(&self, n: usize) -> Validator<usize> {
+        Validator::new("successor::next_bounded", n, Order::Lex).max_part(self.0)
+    }
+}
+
+/// A constraint from outside the crate, checked only for the order.
+struct Custom<'a, C: 'a + ?Sized>(&'a C);
+
+impl<'a, C: Constraint + ?Sized> Constraint for Custom<'a, C> {
+    #[inline]
+    fn allows(&self, prefix: &[usize], x: usize, rest: usize) -> bool {
+        self.0.allows(prefix, x, rest)
+    }
+
+    #[inline]
+    fn first(&self, prefix: &[usize], lo: usize, s: usize) -> Option<usize> {
+        self.0.first(prefix, lo, s)
+    }
+}
+
+impl<'a, C: Constraint + ?Sized> Family for Custom<'a, C> {
+    #[cfg(feature = "validate")]
+    fn validator(&self, n: usize) -> Validator<usize> {
+        Validator::new("successor::next_constrained", n, Order::Lex)
+    }
+}
+
+/// Writes the first partition of `n`, all ones, to `p`.
+pub fn first_partition(n: usize, p: &mut Vec<usize>) {
+    start(&Unrestricted, n, p);
+}
+
+/// Replaces `p` with the next partition of the same number.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::successor::next_partition;
+///
+/// let mut p = vec![1, 1, 2];
+/// assert!(next_partition(&mut p));
+/// assert_eq!(p, vec![1, 3]);
+/// ```
+#[inline]
+pub fn next_partition(p: &mut Vec<usize>) -> bool {
+    step(&Unrestricted, p)
+}
+
+/// Writes the first partition of `n` into distinct parts to `p`.
+pub fn first_strict(n: usize, p: &mut Vec<usize>) {
+    start(&Strict, n, p);
+}
+
+/// Replaces `p` with the next partition of the same number into
+/// distinct parts.
+#[inline]
+pub fn next_strict(p: &mut Vec<usize>) -> bool {
+    step(&Strict, p)
+}
+
+/// Writes the first partition of `n` into exactly `k` parts to `p`, and
+/// returns whether there is one.
+pub fn first_exact(n: usize, k: usize, p: &mut Vec<usize>) -> bool {
+    if k == 0 || n == 0 {
+        p.clear();
+        return n == k;
+    }
+    start(&Exact(k), n, p)
+}
+
+/// Replaces `p` with the next partition of the same number into the
+/// same number of parts.
+#[inline]
+pub fn next_exact(p: &mut Vec<usize>) -> bool {
+    let k = p.len();
+    step(&Exact(k), p)
+}
+
+/// Writes the first partition of `n` with parts at most `m` to `p`, and
+/// returns whether there is one.
+pub fn first_bounded(n: usize, m: usize, p: &mut Vec<usize>) -> bool {
+    if m == 0 {
+        p.clear();
+        return n == 0;
+    }
+    start(&Bounded(m), n, p)
+}
+
+/// Replaces `p` with the next partition of the same number with parts
+/// at most `m`.
+#[inline]
+pub fn next_bounded(p: &mut Vec<usize>, m: usize) -> bool {
+    m > 0 && step(&Bounded(m), p)
+}
+
+/// A constraint on how many times particular parts appear.
+///
+/// Each rule gives a part a least and a greatest multiplicity, and
+/// parts without a rule may appear any number of times. A part is only
+/// placed when what is left can still be made up within the rules,
+/// which is worked out from the rules directly rather than searched for,
+/// so each step takes time that depends on the number of rules but
+/// hardly at all on `n`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::successor::{Constrained, Multiplicities};
+///
+/// // Exactly two 3s and at most one 1.
+/// let rules = Multiplicities::new().exactly(3, 2).at_most(1, 1);
+/// let mut pp = Constrained::new(10, rules);
+/// assert_eq!(pp.next(), Some(&[2, 2, 3, 3][..]));
+/// assert_eq!(pp.next(), Some(&[3, 3, 4][..]));
+/// assert_eq!(pp.next(), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Multiplicities {
+    /// Rules of `(part, least, greatest)`, in order of part.
+    rules: Vec<(usize, usize, usize)>,
+    /// For each rule, the largest least part of any way to make up its
+    /// part from spare copies, or zero if there is none.
+    most: Vec<usize>,
+}
+
+impl Multiplicities {
+    /// Makes a constraint with no rules, which allows every partition.
+    pub fn new() -> Multiplicities {
+        Multiplicities {
+            rules: Vec::new(),
+            most: Vec::new(),
+        }
+    }
+
+    /// Allows between `lo` and `hi` copies of `part`, inclusive,
+    /// replacing any earlier rule for it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `part` is zero or `lo > hi`.
+    pub fn between(mut self, part: usize, lo: usize, hi: usize) -> Multiplicities {
+        assert!(part > 0, "parts must be positive");
+        assert!(lo <= hi, "no multiplicity lies between the bounds");
+        match self.rules.binary_search_by_key(&part, |r| r.0) {
+            Ok(i) => self.rules[i] = (part, lo, hi),
+            Err(i) => self.rules.insert(i, (part, lo, hi)),
+        }
+        self.settle();
+        self
+    }
+
+    /// Allows exactly `m` copies of `part`.
+    pub fn exactly(self, part: usize, m: usize) -> Multiplicities {
+        self.between(part, m, m)
+    }
+
+    /// Allows at most `m` copies of `part`.
+    pub fn at_most(self, part: usize, m: usize) -> Multiplicities {
+        self.between(part, 0, m)
+    }
+
+    /// Requires at least `m` copies of `part`.
+    pub fn at_least(self, part: usize, m: usize) -> Multiplicities {
+        self.between(part, m, usize::MAX)
+    }
+
+    /// Returns the least and greatest number of copies of `part`
+    /// allowed.
+    pub fn bounds(&self, part: usize) -> (usize, usize) {
+        match self.rules.binary_search_by_key(&part, |r| r.0) {
+            Ok(i) => (self.rules[i].1, self.rules[i].2),
+            Err(_) => (0, usize::MAX),
+        }
+    }
+
+    /// Returns the largest least part of any way to make up `v > 0`
+    /// from copies beyond those the rules require, or zero if there is
+    /// none.
+    #[inline]
+    fn most(&self, v: usize) -> usize {
+        match self.rules.binary_search_by_key(&v, |r| r.0) {
+            Ok(i) => self.most[i],
+            Err(_) => v,
+        }
+    }
+
+    /// Returns how many copies of `part` may be used beyond those
+    /// required.
+    #[inline]
+    fn spare(&self, part: usize) -> usize {
+        let (lo, hi) = self.bounds(part);
+        hi - lo
+    }
+
+    /// Works out `most` for every part with a rule, from the smallest.
+    ///
+    /// A part that may appear at least once more makes itself up, and
+    /// one that may not is split into some copies of a smaller part and
+    /// a rest made up of parts above it. Only the parts with rules, or
+    /// the rests that land on them, stop a split, so the largest least
+    /// part is found after a few tries.
+    fn settle(&mut self) {
+        self.most.clear();
+        for i in 0..self.rules.len() {
+            let (z, lo, hi) = self.rules[i];
+            let m = if hi > lo {
+                z
+            } else {
+                (1..(z / 2 + 1)).rev().find(|&a| {
+                    (1..((z / a).min(self.spare(a)) + 1)).any(|j| z == j * a || self.most(z - j * a) > a)
+                }).unwrap_or(0)
+            };
+            self.most.push(m);
+        }
+    }
+
+    /// Decides whether `x` can follow `prefix` and be completed by
+    /// parts adding up to `t`, or else returns the next part worth
+    /// trying, if there is any.
+    fn check(&self, prefix: &[usize], x: usize, t: usize) -> Result<(), Option<usize>> {
+        let last = prefix.last().cloned().unwrap_or(0);
+        let run = prefix.iter().rev().take_while(|&&y| y == last).count();
+
+        // The rules from the last part up to x must be met by now, and
+        // those beyond need room in the rest. Past the next part that
+        // some copies of are required, no larger x can work.
+        let from = self.rules.partition_point(|r| r.0 < last);
+        let (mut lo_x, mut hi_x) = (0, usize::MAX);
+        let mut need = 0usize;
+        let mut demand = None;
+        for &(z, lo, hi) in &self.rules[from..] {
+            if z < x {
+                let have = if z == last { run } else { 0 };
+                if have < lo {
+                    return Err(None);
+                }
+            } else if z == x {
+                lo_x = lo;
+                hi_x = hi;
+            } else {
+                need = need.saturating_add(z.saturating_mul(lo));
+                if lo > 0 && demand.is_none() {
+                    demand = Some(z);
+                }
+            }
+        }
+        let m = if x == last { run + 1 } else { 1 };
+        if m > hi_x {
+            return Err(Some(x + 1));
+        }
+        need = need.saturating_add(x.saturating_mul(lo_x.saturating_sub(m)));
+        if need > t {
+            return Err(demand);
+        }
+
+        // What is left over is made up of spare copies of x and parts
+        // above it. If it is less than x, only the x that leaves none
+        // can work, unless a required part comes first.
+        let t = t - need;
+        if t > 0 && t < x {
+            return Err(Some(demand.map_or(x + t, |z| z.min(x + t))));
+        }
+        let spare = hi_x - lo_x.max(m);
+        if (0..((t / x).min(spare) + 1)).any(|j| t == j * x || self.most(t - j * x) > x) {
+            Ok(())
+        } else {
+            Err(Some(x + 1))
+        }
+    }
+}
+
+impl Constraint for Multiplicities {
+    #[inline]
+    fn allows(&self, prefix: &[usize], x: usize, rest: usize) -> bool {
+        self.check(prefix, x, rest).is_ok()
+    }
+
+    fn first(&self, prefix: &[usize], lo: usize, s: usize) -> Option<usize> {
+        let mut x = lo;
+        while x <= s {
+            match self.check(prefix, x, s - x) {
+                Ok(()) => return Some(x),
+                Err(next) => x = next?,
+            }
+        }
+        None
+    }
+}
+
+/// Writes the first partition of `n` that `c` allows to `p`, and
+/// returns whether there is one.
+///
+/// The empty partition of zero has no parts to ask `c` about, and is
+/// always allowed.
+pub fn first_constrained<C: Constraint + ?Sized>(n: usize, c: &C, p: &mut Vec<usize>) -> bool {
+    start(&Custom(c), n, p)
+}
+
+/// Replaces `p` with the next partition of the same number that `c`
+/// allows.
+#[inline]
+pub fn next_constrained<C: Constraint + ?Sized>(p: &mut Vec<usize>, c: &C) -> bool {
+    step(&Custom(c), p)
+}
+
+/// Iterates over the partitions of `n` that a constraint allows, in the
+/// order of `Partitions`.
+///
+/// This holds the current partition in a vector and steps it with
+/// `next_constrained`.
+#[derive(Debug)]
+pub struct Constrained<C> {
+    n: usize,
+    c: C,
+    p: Vec<usize>,
+    state: State,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum State {
+    Fresh,
+    Going,
+    Done,
+}
+
+impl<C: Constraint> Constrained<C> {
+    /// Makes a new iterator.
+    pub fn new(n: usize, c: C) -> Constrained<C> {
+        Constrained {
+            n,
+            c,
+            p: Vec::new(),
+            state: State::Fresh,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Returns the constraint.
+    #[inline]
+    pub fn constraint(&self) -> &C {
+        &self.c
+    }
+
+    /// Restarts the iterator at the first partition of `n` allowed by
+    /// the same constraint.
+    #[inline]
+    pub fn reinit(&mut self, n: usize) {
+        self.n = n;
+        self.p.clear();
+        self.state = State::Fresh;
+    }
+
+    /// Destroys the iterator and returns a vector for further use.
+    ///
+    /// You only need to call this function if you want to reuse the
+    /// vector for something else. Its contents will be in an undefined
+    /// state, and so cannot be relied upon.
+    #[inline]
+    pub fn end(self) -> Vec<usize> {
+        self.p
+    }
+}
+
+impl<C: Constraint> StreamingIterator for Constrained<C> {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        match self.state {
+            State::Going => Some(&self.p),
+            _ => None,
+        }
+    }
+
+    fn advance(&mut self) {
+        let found = match self.state {
+            State::Fresh => first_constrained(self.n, &self.c, &mut self.p),
+            State::Going => next_constrained(&mut self.p, &self.c),
+            State::Done => false,
+        };
+        self.state = if found { State::Going } else { State::Done };
+    }
+}
+
+impl<C: Constraint> PartitionIterator for Constrained<C> {
+    #[inline]
+    fn n(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn reinit(&mut self, n: usize) {
+        Constrained::reinit(self, n)
+    }
+
+    #[inline]
+    fn end(self) -> Vec<usize> {
+        Constrained::end(self)
+    }
+}
+
+#[test]
+fn families() {
+    //! Compares each family against filtering `Partitions`.
+
+    use Partitions;
+
+    fn check<F: Fn(&[usize]) -> bool>(n: usize, keep: F, first: bool, p: &mut Vec<usize>, next: &dyn Fn(&mut Vec<usize>) -> bool) {
+        let mut pp = Partitions::new(n);
+        let mut started = false;
+        while let Some(x) = pp.next() {
+            if !keep(x) {
+                continue;
+            }
+            if started {
+                assert!(next(p));
+            } else {
+                assert!(first);
+                started = true;
+            }
+            assert_eq!(&p[..], x);
+        }
+        assert_eq!(started, first);
+        if started {
+            let last = p.clone();
+            assert!(!next(p));
+            assert_eq!(*p, last);
+        }
+    }
+
+    let mut p = Vec::new();
+    for n in 0..22 {
+        first_partition(n, &mut p);
+        check(n, |_| true, true, &mut p, &next_partition);
+
+        first_strict(n, &mut p);
+        check(n, |x| x.windows(2).all(|w| w[0] < w[1]), true, &mut p, &next_strict);
+
+        for k in 0..(n + 2) {
+            let first = first_exact(n, k, &mut p);
+            check(n, |x| x.len() == k, first, &mut p, &next_exact);
+        }
+
+        for m in 0..(n + 2) {
+            let first = first_bounded(n, m, &mut p);
+            check(n, |x| x.iter().all(|&y| y <= m), first, &mut p, &|p| next_bounded(p, m));
+        }
+
+        // The same families as user constraints.
+        let first = first_constrained(n, &Strict, &mut p);
+        check(n, |x| x.windows(2).all(|w| w[0] < w[1]), first, &mut p, &|p| next_constrained(p, &Strict));
+        // Zero has its empty partition in any family.
+        for k in (0..(n.min(6) + 2)).filter(|&k| n > 0 || k == 0) {
+            let first = first_constrained(n, &Exact(k), &mut p);
+            check(n, |x| x.len() == k, first, &mut p, &|p| next_constrained(p, &Exact(k)));
+        }
+        let first = first_constrained(n, &Bounded(3), &mut p);
+        check(n, |x| x.iter().all(|&y| y <= 3), first, &mut p, &|p| next_constrained(p, &Bounded(3)));
+    }
+}
+
+#[test]
+fn multiplicities() {
+    //! Compares rules on multiplicities against filtering `Partitions`.
+
+    use Partitions;
+
+    let rules = [
+        Multiplicities::new(),
+        Multiplicities::new().exactly(3, 2).at_most(1, 1),
+        Multiplicities::new().at_least(2, 2).exactly(5, 0),
+        Multiplicities::new().between(1, 1, 2).between(4, 1, 1).at_most(2, 0),
+        Multiplicities::new().exactly(7, 1).exactly(7, 2).at_least(6, 1),
+    ];
+    for r in &rules {
+        for n in 0..22 {
+            let mut expected = Vec::new();
+            let mut pp = Partitions::new(n);
+            while let Some(p) = pp.next() {
+                let ok = (1..30).all(|k| {
+                    let (lo, hi) = r.bounds(k);
+                    let c = p.iter().filter(|&&x| x == k).count();
+                    lo <= c && c <= hi
+                });
+                // The empty partition of zero is always allowed.
+                if ok || n == 0 {
+                    expected.push(p.to_vec());
+                }
+            }
+
+            let mut got = Vec::new();
+            let mut cc = Constrained::new(n, r.clone());
+            while let Some(p) = cc.next() {
+                got.push(p.to_vec());
+            }
+            assert_eq!(got, expected, "{:?}, n = {}", r, n);
+        }
+    }
+}
+
+#[test]
+fn multiplicities_large() {
+    //! Tests rules on multiplicities against a count of the partitions
+    //! they allow, at an `n` too large to search through.
+
+    // No 1s, exactly two 3s and at most one 5: the rest is 74 or 69
+    // made up of the other parts.
+    let n = 80;
+    let mut ways = vec![0u64; n + 1];
+    ways[0] = 1;
+    for x in (2..(n + 1)).filter(|&x| x != 3 && x != 5) {
+        for v in x..(n + 1) {
+            ways[v] += ways[v - x];
+        }
+    }
+
+    let rules = Multiplicities::new().between(1, 0, 0).between(3, 2, 2).between(5, 0, 1);
+    let mut cc = Constrained::new(n, rules.clone());
+    let mut c = 0;
+    while let Some(p) = cc.next() {
+        assert!([1, 3, 5].iter().all(|&k| {
+            let (lo, hi) = rules.bounds(k);
+            let m = p.iter().filter(|&&x| x == k).count();
+            lo <= m && m <= hi
+        }));
+        c += 1;
+    }
+    assert_eq!(c, ways[n - 6] + ways[n - 11]);
+
+    // No odd parts, so an odd number has no partitions at all, and
+    // every even prefix is a dead end.
+    let n = 201;
+    let rules = (0..(n / 2 + 1)).fold(Multiplicities::new(), |r, k| r.exactly(2 * k + 1, 0));
+    assert_eq!(Constrained::new(n, rules).next(), None);
+}
@@ -0,0 +1,330 @@
+//! Partitions built from a limited supply of each part.
+
+use alloc::vec::Vec;
+use core::mem;
+
+use streaming_iterator::StreamingIterator;
+#[cfg(feature = "validate")]
+use validate::{Order, Validator};
+use PartitionIterator;
+
+/// Iterates over the partitions of `n` in which each part comes from a
+/// given supply.
+///
+/// The supply is a list of `(part, count)` pairs: `part` may be used at
+/// most `count` times, and parts that are not listed may not be used at
+/// all. A count of `usize::MAX`, or any count of at least `n / part`,
+/// leaves the part unlimited. Partitions come in the same order as they
+/// do from `Partitions`.
+///
+/// A table of which amounts are still reachable from the remaining
+/// parts is built up front, taking `O(n)` time and space for each
+/// distinct part, so that the enumeration never runs into a dead end.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::supply::SupplyPartitions;
+///
+/// // At most three 5s and two 7s.
+/// let mut pp = SupplyPartitions::new(24, &[(5, 3), (7, 2)]);
+/// assert_eq!(pp.next(), Some(&[5, 5, 7, 7][..]));
+/// assert_eq!(pp.next(), None);
+/// ```
+#[derive(Debug)]
+pub struct SupplyPartitions {
+    n: usize,
+    supply: Vec<(usize, usize)>,
+    parts: Vec<usize>,
+    caps: Vec<usize>,
+    reach: Vec<bool>,
+    mults: Vec<usize>,
+    rest: Vec<usize>,
+    a: Vec<usize>,
+    state: State,
+    #[cfg(feature = "validate")]
+    check: Validator<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum State {
+    Fresh,
+    Going,
+    Done,
+}
+
+impl SupplyPartitions {
+    /// Makes a new iterator.
+    ///
+    /// Repeated parts in `supply` have their counts added together.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `supply` contains a part of size zero.
+    #[inline]
+    pub fn new(n: usize, supply: &[(usize, usize)]) -> SupplyPartitions {
+        SupplyPartitions::recycle(n, supply, Vec::new())
+    }
+
+    /// Makes a new iterator, trying to avoid allocations.
+    ///
+    /// Any vector can be passed to this function, since its contents
+    /// will be cleared, but note that the vector will still reallocate
+    /// if its capacity is less than `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `supply` contains a part of size zero.
+    #[inline]
+    pub fn recycle(n: usize, supply: &[(usize, usize)], vec: Vec<usize>) -> SupplyPartitions {
+        SupplyPartitions::build(n, supply.to_vec(), vec)
+    }
+
+    fn build(n: usize, mut supply: Vec<(usize, usize)>, mut a: Vec<usize>) -> SupplyPartitions {
+        #[cfg(feature = "validate")]
+        let check = Validator::new("SupplyPartitions", n, Order::Lex).supply(&supply);
+        supply.sort();
+
+        let mut parts: Vec<usize> = Vec::new();
+        let mut caps: Vec<usize> = Vec::new();
+        for &(part, count) in &supply {
+            assert!(part > 0, "parts must be positive");
+            if part > n || count == 0 {
+                continue;
+            }
+            if parts.last() == Some(&part) {
+                let c = caps.last_mut().unwrap();
+                *c = c.saturating_add(count);
+            } else {
+                parts.push(part);
+                caps.push(count);
+            }
+        }
+        for (c, &p) in caps.iter_mut().zip(&parts) {
+            *c = (*c).min(n / p);
+        }
+
+        let d = parts.len();
+        let w = n + 1;
+
+        // reach[j * w + r] says whether r can be made from parts j.. alone.
+        let mut reach = vec![false; (d + 1) * w];
+        reach[d * w] = true;
+        let mut window = vec![0usize; w];
+        for j in (0..d).rev() {
+            let (p, c) = (parts[j], caps[j]);
+            for r in 0..w {
+                let mut x = reach[(j + 1) * w + r] as usize;
+                if r >= p {
+                    x += window[r - p];
+                }
+                if r >= (c + 1) * p {
+                    x -= reach[(j + 1) * w + r - (c + 1) * p] as usize;
+                }
+                window[r] = x;
+                reach[j * w + r] = x > 0;
+            }
+        }
+
+        let state = if reach[n] { State::Fresh } else { State::Done };
+        a.clear();
+        a.reserve(n);
+
+        SupplyPartitions {
+            n,
+            supply,
+            parts,
+            caps,
+            reach,
+            mults: vec![0; d],
+            rest: vec![0; d + 1],
+            a,
+            state,
+            #[cfg(feature = "validate")]
+            check,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Restarts the iterator at the first partition of `n` from the same
+    /// supply, rebuilding the table of reachable amounts.
+    ///
+    /// The supply is the one first given, so `n` is partitioned into the
+    /// same parts, with the same limits on each.
+    pub fn reinit(&mut self, n: usize) {
+        let supply = mem::take(&mut self.supply);
+        let a = mem::take(&mut self.a);
+        *self = SupplyPartitions::build(n, supply, a);
+    }
+
+    /// Destroys the iterator and returns a vector for further use.
+    ///
+    /// You only need to call this function if you want to reuse the
+    /// vector for something else. Its contents will be in an undefined
+    /// state, and so cannot be relied upon.
+    #[inline]
+    pub fn end(self) -> Vec<usize> {
+        self.a
+    }
+
+    #[inline]
+    fn reachable(&self, j: usize, r: usize) -> bool {
+        self.reach[j * (self.n + 1) + r]
+    }
+
+    /// Uses as many copies of each part from `from` onwards as possible.
+    fn fill(&mut self, from: usize) {
+        for i in from..self.parts.len() {
+            let (p, r) = (self.parts[i], self.rest[i]);
+            let mut t = self.caps[i].min(r / p);
+            while !self.reachable(i + 1, r - t * p) {
+                t -= 1;
+            }
+            self.set(i, t);
+        }
+    }
+
+    #[inline]
+    fn set(&mut self, i: usize, t: usize) {
+        let p = self.parts[i];
+        self.mults[i] = t;
+        self.rest[i + 1] = self.rest[i] - t * p;
+        for _ in 0..t {
+            self.a.push(p);
+        }
+    }
+
+    /// Moves on to the next partition.
+    fn step(&mut self) {
+        match self.state {
+            State::Fresh => {
+                self.state = State::Going;
+                self.rest[0] = self.n;
+                self.fill(0);
+                return;
+            },
+            State::Done => return,
+            State::Going => {},
+        }
+
+        // Find the last part whose multiplicity can be lowered.
+        for j in (0..self.parts.len()).rev() {
+            let (p, r) = (self.parts[j], self.rest[j]);
+            let len = self.a.len() - self.mults[j];
+            self.a.truncate(len);
+
+            let mut t = self.mults[j];
+            while t > 0 {
+                t -= 1;
+                if self.reachable(j + 1, r - t * p) {
+                    self.set(j, t);
+                    self.fill(j + 1);
+                    return;
+                }
+            }
+        }
+
+        self.state = State::Done;
+    }
+}
+
+impl StreamingIterator for SupplyPartitions {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&Self::Item> {
+        match self.state {
+            State::Going => Some(&self.a),
+            _ => None,
+        }
+    }
+
+    fn advance(&mut self) {
+        self.step();
+        #[cfg(feature = "validate")]
+        self.check.check(self.get());
+    }
+}
+
+impl PartitionIterator for SupplyPartitions {
+    #[inline]
+    fn n(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn reinit(&mut self, n: usize) {
+        SupplyPartitions::reinit(self, n)
+    }
+
+    #[inline]
+    fn end(self) -> Vec<usize> {
+        SupplyPartitions::end(self)
+    }
+}
+
+#[test]
+fn filtered() {
+    //! Compares against filtering the output of `Partitions`.
+
+    use Partitions;
+
+    let supplies: &[&[(usize, usize)]] = &[
+        &[],
+        &[(1, 100)],
+        &[(1, 2), (2, 3), (3, 1), (5, 4)],
+        &[(2, 1), (2, 2), (4, 5), (6, 3)],
+        &[(1, 1), (2, 1), (3, 1), (4, 1), (5, 1), (6, 1), (7, 1)],
+        &[(3, 3), (5, 3), (7, 2), (11, 1)],
+    ];
+
+    for supply in supplies {
+        for n in 0..30 {
+            let mut q = SupplyPartitions::new(n, supply);
+            let mut p = Partitions::new(n);
+
+            while let Some(x) = p.next() {
+                let ok = x.iter().all(|&part| {
+                    let have: usize = supply.iter()
+                        .filter(|s| s.0 == part)
+                        .map(|s| s.1)
+                        .sum();
+                    x.iter().filter(|&&y| y == part).count() <= have
+                });
+                if ok {
+                    assert_eq!(q.next(), Some(x));
+                }
+            }
+
+            assert_eq!(q.next(), None);
+        }
+    }
+}
+
+#[test]
+fn reinit() {
+    //! Tests that a reinitialised or recycled iterator gives the same
+    //! partitions as a new one, from the same supply.
+
+    let supply = &[(1, 2), (2, 3), (5, usize::MAX)];
+    let mut q = SupplyPartitions::new(0, supply);
+    for &n in &[7, 25, 3, 0, 12] {
+        q.reinit(n);
+        let mut fresh = SupplyPartitions::new(n, supply);
+        while let Some(p) = fresh.next() {
+            assert_eq!(q.next(), Some(p));
+        }
+        assert_eq!(q.next(), None);
+    }
+
+    let mut q = SupplyPartitions::recycle(4, &[(1, 4), (2, 1)], q.end());
+    assert_eq!(q.next(), Some(&[1, 1, 1, 1][..]));
+    assert_eq!(q.next(), Some(&[1, 1, 2][..]));
+    assert_eq!(q.next(), None);
+}
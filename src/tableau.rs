@@ -0,0 +1,770 @@
+//! Young tableaux.
+//!
+//! A standard Young tableau with `n` cells is a filling of a Young
+//! diagram with `{0, …, n - 1}` that increases along each row and down
+//! each column. Tableaux are stored as lists of rows, longest first, in
+//! English notation.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "num-bigint")]
+use num_bigint::BigUint;
+
+use factor::{factorise, Product};
+use streaming_iterator::StreamingIterator;
+use young::conjugate;
+use {PartitionIterator, Partitions};
+
+/// Returns whether `t` is a standard Young tableau.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::tableau::is_standard;
+///
+/// assert!(is_standard(&[vec![0, 1, 3], vec![2]]));
+/// assert!(!is_standard(&[vec![0, 2], vec![3, 1]]));
+/// ```
+pub fn is_standard(t: &[Vec<usize>]) -> bool {
+    let n: usize = t.iter().map(|row| row.len()).sum();
+    let mut seen = vec![false; n];
+
+    for (r, row) in t.iter().enumerate() {
+        if row.is_empty() || (r > 0 && row.len() > t[r - 1].len()) {
+            return false;
+        }
+        for (c, &x) in row.iter().enumerate() {
+            if x >= n || seen[x] {
+                return false;
+            }
+            seen[x] = true;
+            if (c > 0 && row[c - 1] > x) || (r > 0 && t[r - 1][c] > x) {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Empties cell `(0, 0)` and slides the hole out by jeu de taquin,
+/// removing the cell it ends up in. Returns that cell.
+fn slide_out(t: &mut Vec<Vec<usize>>) -> (usize, usize) {
+    let (mut r, mut c) = (0, 0);
+    loop {
+        let right = t[r].get(c + 1).cloned();
+        let down = t.get(r + 1).and_then(|row| row.get(c)).cloned();
+        match (right, down) {
+            (Some(x), Some(y)) if y < x => {
+                t[r][c] = y;
+                r += 1;
+            }
+            (Some(x), _) => {
+                t[r][c] = x;
+                c += 1;
+            }
+            (None, Some(y)) => {
+                t[r][c] = y;
+                r += 1;
+            }
+            (None, None) => break,
+        }
+    }
+
+    t[r].pop();
+    if t[r].is_empty() {
+        t.pop();
+    }
+    (r, c)
+}
+
+/// Returns the promotion of a standard Young tableau.
+///
+/// The entry 0 is removed, the hole is slid out to an outer corner by
+/// jeu de taquin, every entry is decreased by one, and `n - 1` is put
+/// in the cell left empty.
+///
+/// `t` must be a standard Young tableau, or the result is meaningless.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::tableau::promotion;
+///
+/// let t = vec![vec![0, 1, 3], vec![2, 4]];
+/// assert_eq!(promotion(&t), vec![vec![0, 2, 4], vec![1, 3]]);
+/// ```
+pub fn promotion(t: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let n: usize = t.iter().map(|row| row.len()).sum();
+    let mut w = t.to_vec();
+    if n == 0 {
+        return w;
+    }
+
+    let (r, _) = slide_out(&mut w);
+    for x in w.iter_mut().flat_map(|row| row.iter_mut()) {
+        *x -= 1;
+    }
+    if r == w.len() {
+        w.push(Vec::new());
+    }
+    w[r].push(n - 1);
+    w
+}
+
+/// Returns the evacuation of a standard Young tableau.
+///
+/// Promotion is applied to ever smaller tableaux: each time the hole of
+/// entry 0 is slid out, the cell it leaves is given the largest label
+/// not yet used, and it is then dropped from the tableau. Evacuation is
+/// an involution.
+///
+/// `t` must be a standard Young tableau, or the result is meaningless.
+pub fn evacuation(t: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let n: usize = t.iter().map(|row| row.len()).sum();
+    let mut out = t.to_vec();
+    let mut w = t.to_vec();
+
+    for i in 0..n {
+        let (r, c) = slide_out(&mut w);
+        for x in w.iter_mut().flat_map(|row| row.iter_mut()) {
+            *x -= 1;
+        }
+        out[r][c] = n - 1 - i;
+    }
+    out
+}
+
+/// Returns the size of the orbit of `t` under promotion, the least
+/// `k > 0` such that promoting `k` times gives `t` back.
+///
+/// On rectangular shapes with `n` cells this always divides `n`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::tableau::promotion_order;
+///
+/// assert_eq!(promotion_order(&[vec![0, 1], vec![2, 3]]), 2);
+/// assert_eq!(promotion_order(&[vec![0, 1, 3], vec![2]]), 3);
+/// ```
+pub fn promotion_order(t: &[Vec<usize>]) -> usize {
+    let mut u = promotion(t);
+    let mut k = 1;
+    while u != t {
+        u = promotion(&u);
+        k += 1;
+    }
+    k
+}
+
+/// Returns the hook lengths of `p`, as a list of rows like its diagram.
+///
+/// The hook of a cell holds the cell itself and those to its right and
+/// below it.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::tableau::hook_lengths;
+///
+/// assert_eq!(hook_lengths(&[1, 3]), vec![vec![4, 2, 1], vec![1]]);
+/// ```
+pub fn hook_lengths(p: &[usize]) -> Vec<Vec<usize>> {
+    let mut conj = Vec::new();
+    conjugate(p, &mut conj);
+    let l = p.len();
+    (0..l)
+        .map(|i| (0..p[l - 1 - i]).map(|j| hook(p, &conj, i, j)).collect())
+        .collect()
+}
+
+/// Returns the hook length of the cell in row `i` and column `j`, given
+/// the partition and its conjugate.
+#[inline]
+fn hook(p: &[usize], conj: &[usize], i: usize, j: usize) -> usize {
+    p[p.len() - 1 - i] + conj[conj.len() - 1 - j] - i - j - 1
+}
+
+/// Returns `f^λ`, as a product of primes.
+pub(crate) fn dimension(p: &[usize]) -> Product {
+    let mut f = Product::new();
+    f.times_factorial(p.iter().sum());
+    for h in hook_lengths(p).iter().flat_map(|row| row.iter()) {
+        f.divide(*h);
+    }
+    f
+}
+
+/// Counts the standard tableaux of shape `p`, by the hook length formula
+/// `f^λ = n! / ∏ h(c)`. This is also the dimension of the irreducible
+/// representation of the symmetric group indexed by `p`.
+///
+/// Returns `None` if the count does not fit in a `u128`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::tableau::count_standard;
+///
+/// assert_eq!(count_standard(&[1, 2, 3]), Some(16));
+/// ```
+pub fn count_standard(p: &[usize]) -> Option<u128> {
+    dimension(p).to_u128()
+}
+
+/// Counts the standard tableaux of shape `p` exactly, like
+/// `count_standard`.
+///
+/// This is only available with the `num-bigint` feature.
+#[cfg(feature = "num-bigint")]
+pub fn count_standard_big(p: &[usize]) -> BigUint {
+    dimension(p).to_biguint().expect("hook length formula gives an integer")
+}
+
+/// Iterates over the partitions of `n` along with the number of
+/// standard tableaux of each shape.
+///
+/// Partitions come in the order of `Partitions`. The factorisation of
+/// `n!` and of every possible hook length is worked out once, so each
+/// shape only costs a pass over its cells and the final multiplication.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::tableau::Dimensions;
+///
+/// // The squares of the dimensions add up to n!.
+/// let mut d = Dimensions::new(6);
+/// let mut sum = 0;
+/// while d.next().is_some() {
+///     sum += d.dimension().unwrap().pow(2);
+/// }
+/// assert_eq!(sum, 720);
+/// ```
+#[derive(Debug)]
+pub struct Dimensions {
+    parts: Partitions,
+    conj: Vec<usize>,
+    /// The prime factors of each number up to `n`.
+    factors: Vec<Vec<(usize, i64)>>,
+    /// How many cells have each hook length.
+    hooks: Vec<i64>,
+    base: Product,
+    product: Product,
+    value: Option<u128>,
+}
+
+impl Dimensions {
+    /// Makes a new iterator.
+    pub fn new(n: usize) -> Dimensions {
+        let mut base = Product::new();
+        base.times_factorial(n);
+        let factors = (0..(n + 1)).map(|k| if k == 0 { Vec::new() } else { factorise(k) }).collect();
+        Dimensions {
+            parts: Partitions::new(n),
+            conj: Vec::with_capacity(n),
+            factors,
+            hooks: vec![0; n + 1],
+            product: base.clone(),
+            base,
+            value: None,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Returns the number of standard tableaux of the current shape, or
+    /// `None` if it does not fit in a `u128`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator is not on a partition.
+    #[inline]
+    pub fn dimension(&self) -> Option<u128> {
+        self.parts.get().expect("no current partition");
+        self.value
+    }
+
+    /// Returns the number of standard tableaux of the current shape
+    /// exactly.
+    ///
+    /// This is only available with the `num-bigint` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator is not on a partition.
+    #[cfg(feature = "num-bigint")]
+    pub fn dimension_big(&self) -> BigUint {
+        self.parts.get().expect("no current partition");
+        self.product.to_biguint().expect("hook length formula gives an integer")
+    }
+
+    /// Restarts the iterator at the first partition of `n`.
+    ///
+    /// The factorisations already worked out are kept, so only those of
+    /// the numbers past the largest `n` so far are added.
+    pub fn reinit(&mut self, n: usize) {
+        self.parts.reinit(n);
+        self.base = Product::new();
+        self.base.times_factorial(n);
+        self.product.clone_from(&self.base);
+        for k in self.factors.len()..(n + 1) {
+            self.factors.push(if k == 0 { Vec::new() } else { factorise(k) });
+        }
+        self.hooks.clear();
+        self.hooks.resize(n + 1, 0);
+        self.value = None;
+    }
+
+    /// Destroys the iterator and returns a vector for further use.
+    ///
+    /// You only need to call this function if you want to reuse the
+    /// vector for something else. Its contents will be in an undefined
+    /// state, and so cannot be relied upon.
+    #[inline]
+    pub fn end(self) -> Vec<usize> {
+        self.parts.end()
+    }
+}
+
+impl StreamingIterator for Dimensions {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        self.parts.get()
+    }
+
+    fn advance(&mut self) {
+        self.parts.advance();
+        let p = match self.parts.get() {
+            Some(p) => p,
+            None => return,
+        };
+
+        conjugate(p, &mut self.conj);
+        for i in 0..p.len() {
+            for j in 0..p[p.len() - 1 - i] {
+                self.hooks[hook(p, &self.conj, i, j)] += 1;
+            }
+        }
+
+        self.product.clone_from(&self.base);
+        for (h, c) in self.hooks.iter_mut().enumerate() {
+            if *c > 0 {
+                self.product.times_factored(&self.factors[h], -*c);
+                *c = 0;
+            }
+        }
+        self.value = self.product.to_u128();
+    }
+}
+
+impl PartitionIterator for Dimensions {
+    #[inline]
+    fn n(&self) -> usize {
+        self.parts.n()
+    }
+
+    #[inline]
+    fn reinit(&mut self, n: usize) {
+        Dimensions::reinit(self, n)
+    }
+
+    #[inline]
+    fn end(self) -> Vec<usize> {
+        Dimensions::end(self)
+    }
+}
+
+/// Iterates over the Littlewood–Richardson tableaux of shape `λ / μ`
+/// and content `ν`.
+///
+/// These are the semistandard fillings of the skew diagram `λ / μ` in
+/// which the entry `i` appears as often as the `i`th largest part of
+/// `ν`, and whose reverse reading word, from right to left along each
+/// row and from the top row down, is a lattice word. Their number is the
+/// Littlewood–Richardson coefficient `c^λ_{μν}`.
+///
+/// The partitions are given in non-decreasing order, like everywhere
+/// else in the crate. Each tableau is yielded as a list of rows, one for
+/// each part of `λ` from the largest down, holding only the entries of
+/// the cells outside `μ`, so that row `r` starts in column `μ_r`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::tableau::LittlewoodRichardson;
+///
+/// let mut lr = LittlewoodRichardson::new(&[1, 2, 3], &[1, 2], &[1, 2]);
+/// assert_eq!(lr.next(), Some(&[vec![0], vec![0], vec![1]][..]));
+/// assert_eq!(lr.next(), Some(&[vec![0], vec![1], vec![0]][..]));
+/// assert_eq!(lr.next(), None);
+/// ```
+#[derive(Debug)]
+pub struct LittlewoodRichardson {
+    /// The cells of `λ / μ` in reverse reading order.
+    cells: Vec<(usize, usize)>,
+    inner: Vec<usize>,
+    outer: Vec<usize>,
+    content: Vec<usize>,
+    counts: Vec<usize>,
+    rows: Vec<Vec<usize>>,
+    state: State,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum State {
+    Fresh,
+    Going,
+    Done,
+}
+
+impl LittlewoodRichardson {
+    /// Makes a new iterator.
+    ///
+    /// If `μ` does not fit inside `λ`, or the sizes do not satisfy
+    /// `|λ| = |μ| + |ν|`, there are no tableaux.
+    pub fn new(lambda: &[usize], mu: &[usize], nu: &[usize]) -> LittlewoodRichardson {
+        let outer: Vec<usize> = lambda.iter().rev().cloned().collect();
+        let mut inner: Vec<usize> = mu.iter().rev().cloned().collect();
+        let content: Vec<usize> = nu.iter().rev().cloned().collect();
+
+        let fits = inner.len() <= outer.len() && inner.iter().zip(&outer).all(|(m, l)| m <= l);
+        let sizes = lambda.iter().sum::<usize>() == mu.iter().sum::<usize>() + nu.iter().sum::<usize>();
+        inner.resize(outer.len(), 0);
+
+        let mut cells = Vec::new();
+        let mut rows = Vec::new();
+        if fits {
+            for (r, (&m, &l)) in inner.iter().zip(&outer).enumerate() {
+                for c in (m..l).rev() {
+                    cells.push((r, c));
+                }
+                rows.push(vec![0; l - m]);
+            }
+        }
+
+        LittlewoodRichardson {
+            cells,
+            inner,
+            outer,
+            counts: vec![0; content.len()],
+            content,
+            rows,
+            state: if fits && sizes { State::Fresh } else { State::Done },
+        }
+    }
+
+    /// Advances the iterator and returns the next tableau.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[Vec<usize>]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Returns the least entry no smaller than `start` that can go in
+    /// the cell with index `i`, given the cells before it.
+    fn first_fit(&self, i: usize, start: usize) -> Option<usize> {
+        let (r, c) = self.cells[i];
+
+        let mut lo = start;
+        if r > 0 && c >= self.inner[r - 1] {
+            lo = lo.max(self.rows[r - 1][c - self.inner[r - 1]] + 1);
+        }
+        let hi = if c + 1 < self.outer[r] {
+            self.rows[r][c + 1 - self.inner[r]] + 1
+        } else {
+            self.content.len()
+        };
+
+        (lo..hi).find(|&v| {
+            self.counts[v] < self.content[v] && (v == 0 || self.counts[v] < self.counts[v - 1])
+        })
+    }
+
+    fn set(&mut self, i: usize, v: usize) {
+        let (r, c) = self.cells[i];
+        self.rows[r][c - self.inner[r]] = v;
+        self.counts[v] += 1;
+    }
+
+    fn unset(&mut self, i: usize) -> usize {
+        let (r, c) = self.cells[i];
+        let v = self.rows[r][c - self.inner[r]];
+        self.counts[v] -= 1;
+        v
+    }
+
+    /// Extends a partial filling of the first `i` cells, trying entries
+    /// from `start` up in cell `i` and backtracking when stuck. Returns
+    /// whether a complete tableau was found.
+    fn search(&mut self, mut i: usize, mut start: usize) -> bool {
+        loop {
+            if i == self.cells.len() {
+                return true;
+            }
+            match self.first_fit(i, start) {
+                Some(v) => {
+                    self.set(i, v);
+                    i += 1;
+                    start = 0;
+                }
+                None => {
+                    if i == 0 {
+                        return false;
+                    }
+                    i -= 1;
+                    start = self.unset(i) + 1;
+                }
+            }
+        }
+    }
+}
+
+impl StreamingIterator for LittlewoodRichardson {
+    type Item = [Vec<usize>];
+
+    #[inline]
+    fn get(&self) -> Option<&[Vec<usize>]> {
+        match self.state {
+            State::Going => Some(&self.rows),
+            _ => None,
+        }
+    }
+
+    fn advance(&mut self) {
+        let found = match self.state {
+            State::Fresh => self.search(0, 0),
+            State::Going => {
+                let len = self.cells.len();
+                len > 0 && {
+                    let v = self.unset(len - 1);
+                    self.search(len - 1, v + 1)
+                }
+            }
+            State::Done => false,
+        };
+        self.state = if found { State::Going } else { State::Done };
+    }
+}
+
+/// Returns the Littlewood–Richardson coefficient `c^λ_{μν}`, the
+/// multiplicity of the Schur function `s_λ` in `s_μ s_ν`, by counting
+/// the tableaux.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::tableau::littlewood_richardson;
+///
+/// assert_eq!(littlewood_richardson(&[1, 2, 3], &[1, 2], &[1, 2]), 2);
+/// assert_eq!(littlewood_richardson(&[1, 2, 3], &[1, 1, 1], &[3]), 0);
+/// ```
+pub fn littlewood_richardson(lambda: &[usize], mu: &[usize], nu: &[usize]) -> u64 {
+    let mut lr = LittlewoodRichardson::new(lambda, mu, nu);
+    let mut c = 0;
+    while lr.next().is_some() {
+        c += 1;
+    }
+    c
+}
+
+#[test]
+fn operators() {
+    //! Tests the relations between promotion and evacuation on every
+    //! standard Young tableau of some small shapes.
+
+    fn all(shape: &[usize]) -> Vec<Vec<Vec<usize>>> {
+        // Place the largest entry in each outer corner in turn.
+        let n: usize = shape.iter().sum();
+        if n == 0 {
+            return vec![Vec::new()];
+        }
+        let mut out = Vec::new();
+        for r in 0..shape.len() {
+            if r + 1 == shape.len() || shape[r + 1] < shape[r] {
+                let mut smaller = shape.to_vec();
+                smaller[r] -= 1;
+                if smaller[r] == 0 {
+                    smaller.pop();
+                }
+                for mut t in all(&smaller) {
+                    if r == t.len() {
+                        t.push(Vec::new());
+                    }
+                    t[r].push(n - 1);
+                    out.push(t);
+                }
+            }
+        }
+        out
+    }
+
+    let shapes: &[&[usize]] = &[&[], &[1], &[3], &[2, 1], &[3, 2], &[3, 3], &[4, 2, 1], &[3, 3, 3], &[4, 4], &[5, 3, 2]];
+    for &shape in shapes {
+        let n: usize = shape.iter().sum();
+        let rectangle = shape.iter().all(|&l| l == shape[0]);
+
+        for t in all(shape) {
+            assert!(is_standard(&t));
+            let p = promotion(&t);
+            let e = evacuation(&t);
+            assert!(is_standard(&p) && is_standard(&e));
+            assert_eq!(evacuation(&e), t);
+            assert_eq!(promotion(&evacuation(&p)), e);
+            if rectangle {
+                assert_eq!(n % promotion_order(&t), 0);
+            }
+        }
+    }
+}
+
+#[test]
+fn littlewood_richardson_rule() {
+    //! Checks each tableau and the symmetries of the coefficients.
+
+    use young::conjugate;
+    use Partitions;
+
+    let mut all = Vec::new();
+    for n in 0..7 {
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            all.push(p.to_vec());
+        }
+    }
+    let conj = |p: &[usize]| {
+        let mut out = Vec::new();
+        conjugate(p, &mut out);
+        out
+    };
+
+    for lambda in &all {
+        let n: usize = lambda.iter().sum();
+        for mu in &all {
+            for nu in all.iter().filter(|nu| nu.iter().sum::<usize>() + mu.iter().sum::<usize>() == n) {
+                let mut lr = LittlewoodRichardson::new(lambda, mu, nu);
+                let mut c = 0;
+                while let Some(t) = lr.next() {
+                    c += 1;
+                    let mut word: Vec<usize> = Vec::new();
+                    for (r, row) in t.iter().enumerate() {
+                        assert!(row.windows(2).all(|w| w[0] <= w[1]));
+                        word.extend(row.iter().rev().cloned());
+                        let start = mu.len().checked_sub(r + 1).map_or(0, |i| mu[i]);
+                        if r > 0 {
+                            let above = mu.len().checked_sub(r).map_or(0, |i| mu[i]);
+                            for (j, &x) in row.iter().enumerate() {
+                                let col = start + j;
+                                if col >= above {
+                                    assert!(t[r - 1][col - above] < x);
+                                }
+                            }
+                        }
+                    }
+                    let mut counts = vec![0; nu.len()];
+                    for &x in &word {
+                        counts[x] += 1;
+                        assert!(x == 0 || counts[x] <= counts[x - 1]);
+                    }
+                    assert!(counts.iter().eq(nu.iter().rev()));
+                }
+
+                assert_eq!(c, littlewood_richardson(lambda, nu, mu));
+                assert_eq!(c, littlewood_richardson(&conj(lambda), &conj(mu), &conj(nu)));
+            }
+        }
+
+        // Pieri's rule for a single box.
+        for mu in all.iter().filter(|mu| mu.iter().sum::<usize>() + 1 == n) {
+            let mut inner = mu.clone();
+            while inner.len() < lambda.len() {
+                inner.insert(0, 0);
+            }
+            let fits = inner.len() == lambda.len() && inner.iter().zip(lambda).all(|(m, l)| m <= l);
+            assert_eq!(littlewood_richardson(lambda, mu, &[1]), fits as u64);
+        }
+    }
+}
+
+#[test]
+fn hook_length_formula() {
+    //! Compares the hook length formula against the branching rule, and
+    //! the dimensions against `n!`.
+
+    fn branch(p: &[usize]) -> u128 {
+        // A tableau of shape p is one of a smaller shape, with its
+        // largest entry in a corner.
+        if p.is_empty() {
+            return 1;
+        }
+        let mut total = 0;
+        for i in 0..p.len() {
+            if i == 0 || p[i - 1] < p[i] {
+                let mut q = p.to_vec();
+                q[i] -= 1;
+                if q[i] == 0 {
+                    q.remove(i);
+                }
+                total += branch(&q);
+            }
+        }
+        total
+    }
+
+    let mut factorial: u128 = 1;
+    for n in 0..26 {
+        if n > 0 {
+            factorial *= n as u128;
+        }
+        let mut d = Dimensions::new(n);
+        let mut pp = Partitions::new(n);
+        let mut sum = 0;
+        while let Some(p) = pp.next() {
+            assert_eq!(d.next(), Some(p));
+            let f = d.dimension().unwrap();
+            assert_eq!(Some(f), count_standard(p));
+            if n < 11 {
+                assert_eq!(f, branch(p));
+            }
+            sum += f * f;
+        }
+        assert_eq!(d.next(), None);
+        if n < 21 {
+            assert_eq!(sum, factorial);
+        }
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+#[test]
+fn hook_length_formula_big() {
+    //! Tests exact dimensions past the range of `u128`.
+
+    let mut d = Dimensions::new(30);
+    while let Some(p) = d.next().map(|p| p.to_vec()) {
+        let f = d.dimension_big();
+        assert_eq!(f, count_standard_big(&p));
+        assert_eq!(f, BigUint::from(d.dimension().unwrap()));
+    }
+
+    // The 10 by 10 square, by dividing out the hooks directly.
+    let square = [10; 10];
+    let mut f = BigUint::from(1u32);
+    for k in 1..101u32 {
+        f *= k;
+    }
+    for i in 0..10u32 {
+        for j in 0..10u32 {
+            f /= 19 - i - j;
+        }
+    }
+    assert_eq!(count_standard(&square), None);
+    assert_eq!(count_standard_big(&square), f);
+}
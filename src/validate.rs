@@ -0,0 +1,207 @@
+//! Checks on every partition that the iterators yield.
+//!
+//! With the `validate` feature `Partitions`, the iterators of `sparse`
+//! and `supply`, and the functions of `successor` each carry a
+//! `Validator`, as do the many iterators built on `Partitions` through
+//! it. The validator is shown every partition as it is reached, and
+//! panics with a description of the fault as soon as one breaks the
+//! iterator's contract: positive parts in non-decreasing order (or
+//! non-increasing, for the reverse lexicographic order), adding
+//! up to `n`, within the iterator's restrictions, and coming in its
+//! documented order. This is slow, and meant for running tests under
+//! while changing or wrapping the iterators.
+
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+
+use int::Int;
+
+/// The order that an iterator promises to yield partitions in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Order {
+    /// Lexicographic order of the parts, smallest first.
+    Lex,
+    /// Fewest parts first, and lexicographic order among partitions
+    /// with the same number of parts.
+    LengthThenLex,
+    /// Parts in non-increasing order, in reverse lexicographic order.
+    RevLex,
+}
+
+/// The contract of an iterator, and the last partition that it yielded.
+#[derive(Debug, Clone)]
+pub(crate) struct Validator<T> {
+    what: &'static str,
+    n: T,
+    order: Order,
+    strict: bool,
+    min_part: T,
+    max_part: Option<T>,
+    parts: (usize, usize),
+    supply: Option<Vec<(T, usize)>>,
+    last: RefCell<Vec<T>>,
+    started: Cell<bool>,
+}
+
+impl<T: Int> Validator<T> {
+    /// Makes a validator for the iterator called `what` over the
+    /// partitions of `n`, with no restrictions yet.
+    pub(crate) fn new(what: &'static str, n: T, order: Order) -> Validator<T> {
+        Validator {
+            what,
+            n,
+            order,
+            strict: false,
+            min_part: T::ONE,
+            max_part: None,
+            parts: (0, usize::MAX),
+            supply: None,
+            last: RefCell::new(Vec::new()),
+            started: Cell::new(false),
+        }
+    }
+
+    /// Requires the parts to be distinct.
+    pub(crate) fn strict(mut self) -> Validator<T> {
+        self.strict = true;
+        self
+    }
+
+    /// Requires every part to be at least `m`.
+    pub(crate) fn min_part(mut self, m: T) -> Validator<T> {
+        self.min_part = m.max(T::ONE);
+        self
+    }
+
+    /// Requires every part to be at most `m`.
+    pub(crate) fn max_part(mut self, m: T) -> Validator<T> {
+        self.max_part = Some(m);
+        self
+    }
+
+    /// Requires the number of parts to lie in `lo..=hi`.
+    pub(crate) fn parts(mut self, lo: usize, hi: usize) -> Validator<T> {
+        self.parts = (lo, hi);
+        self
+    }
+
+    /// Requires each part to be one of those listed, used at most as
+    /// many times as listed.
+    pub(crate) fn supply(mut self, supply: &[(T, usize)]) -> Validator<T> {
+        self.supply = Some(supply.to_vec());
+        self
+    }
+
+    /// Takes `p` as the last partition yielded, without checking it.
+    pub(crate) fn resume(&self, p: &[T]) {
+        let mut last = self.last.borrow_mut();
+        last.clear();
+        last.extend_from_slice(p);
+        self.started.set(true);
+    }
+
+    /// Checks the partition that the iterator has just reached, if any.
+    ///
+    /// This only needs a shared reference, so that it can be called
+    /// with the iterator's own `get`.
+    pub(crate) fn check(&self, p: Option<&[T]>) {
+        let p = match p {
+            Some(p) => p,
+            None => return,
+        };
+        let what = self.what;
+
+        if let Some(&x) = p.iter().find(|&&x| x < self.min_part) {
+            panic!("{} yielded {:?}, with a part {:?} below {:?}", what, p, x, self.min_part);
+        }
+        let descending = self.order == Order::RevLex;
+        let largest = if descending { p.first() } else { p.last() };
+        if let (Some(m), Some(&x)) = (self.max_part, largest) {
+            if x > m {
+                panic!("{} yielded {:?}, with a part {:?} above {:?}", what, p, x, m);
+            }
+        }
+        let unordered = |w: &[T]| {
+            let (a, b) = if descending { (w[1], w[0]) } else { (w[0], w[1]) };
+            a > b || (self.strict && a == b)
+        };
+        if let Some(w) = p.windows(2).find(|w| unordered(w)) {
+            let order = match (descending, self.strict) {
+                (false, true) => "increasing",
+                (false, false) => "non-decreasing",
+                (true, true) => "decreasing",
+                (true, false) => "non-increasing",
+            };
+            panic!("{} yielded {:?}, whose parts {:?} are not {}", what, p, w, order);
+        }
+        let sum = p.iter().fold(T::ZERO, |s, &x| s + x);
+        if sum != self.n {
+            panic!("{} yielded {:?}, which adds up to {:?} instead of {:?}", what, p, sum, self.n);
+        }
+        let (lo, hi) = self.parts;
+        if p.len() < lo || p.len() > hi {
+            panic!("{} yielded {:?}, with {} parts out of {}..={}", what, p, p.len(), lo, hi);
+        }
+
+        if let Some(ref supply) = self.supply {
+            for w in p.chunk_by(|a, b| a == b) {
+                let have = supply.iter().filter(|s| s.0 == w[0]).fold(0usize, |c, s| c.saturating_add(s.1));
+                if w.len() > have {
+                    panic!("{} yielded {:?}, with {} of the part {:?} but a supply of {}", what, p, w.len(), w[0], have);
+                }
+            }
+        }
+
+        if self.started.get() {
+            let last = &self.last.borrow()[..];
+            let after = match self.order {
+                Order::Lex => last < p,
+                Order::LengthThenLex => (last.len(), last) < (p.len(), p),
+                Order::RevLex => last > p,
+            };
+            if !after {
+                panic!("{} yielded {:?} after {:?}, out of order", what, p, last);
+            }
+        }
+        self.resume(p);
+    }
+}
+
+#[test]
+fn catches_faults() {
+    //! Tests that each kind of fault is caught.
+
+    use std::panic::{self, AssertUnwindSafe};
+
+    let faulty = |v: &Validator<usize>, p: &[usize]| {
+        panic::catch_unwind(AssertUnwindSafe(|| v.check(Some(p)))).is_err()
+    };
+
+    let v = Validator::new("test", 6, Order::Lex).min_part(2).max_part(4).parts(1, 2);
+    assert!(faulty(&v, &[1, 5]));
+    assert!(faulty(&v, &[6]));
+    assert!(faulty(&v, &[4, 2]));
+    assert!(faulty(&v, &[2, 2]));
+    assert!(faulty(&v, &[2, 2, 2]));
+    v.check(Some(&[3, 3]));
+    assert!(faulty(&v, &[2, 4]));
+    v.check(None);
+
+    let v = Validator::new("test", 6, Order::LengthThenLex).strict();
+    v.check(Some(&[6]));
+    assert!(faulty(&v, &[3, 3]));
+    v.check(Some(&[1, 5]));
+    v.check(Some(&[2, 4]));
+    assert!(faulty(&v, &[1, 5]));
+
+    let v = Validator::new("test", 6, Order::RevLex).max_part(4);
+    assert!(faulty(&v, &[5, 1]));
+    assert!(faulty(&v, &[2, 4]));
+    v.check(Some(&[3, 3]));
+    assert!(faulty(&v, &[4, 2]));
+    v.check(Some(&[3, 2, 1]));
+
+    let v = Validator::new("test", 6, Order::Lex).supply(&[(1, 2), (2, 1), (2, 1)]);
+    v.check(Some(&[1, 1, 2, 2]));
+    assert!(faulty(&v, &[2, 2, 2]));
+}
@@ -0,0 +1,653 @@
+//! Young diagrams and operations on them.
+
+use alloc::vec::Vec;
+
+use sparse::AtMost;
+use streaming_iterator::StreamingIterator;
+use successor::{start, step, Constraint, Family};
+#[cfg(feature = "validate")]
+use validate::{Order, Validator};
+use PartitionIterator;
+
+/// Writes the conjugate of `p` to `out`, replacing its contents.
+///
+/// The conjugate is the partition whose Young diagram is the transpose
+/// of that of `p`. Both are in non-decreasing order.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::young::conjugate;
+///
+/// let mut out = Vec::new();
+/// conjugate(&[1, 1, 3], &mut out);
+/// assert_eq!(out, vec![1, 1, 3]);
+/// conjugate(&[2, 4], &mut out);
+/// assert_eq!(out, vec![1, 1, 2, 2]);
+/// ```
+pub fn conjugate(p: &[usize], out: &mut Vec<usize>) {
+    out.clear();
+
+    // The columns with indices in (p[i - 1], p[i]] each have one cell for
+    // every part from p[i] on. Going from the largest part down gives
+    // them shortest first.
+    let len = p.len();
+    for i in (0..len).rev() {
+        let below = if i == 0 { 0 } else { p[i - 1] };
+        for _ in below..p[i] {
+            out.push(len - i);
+        }
+    }
+}
+
+/// Returns whether `p` is its own conjugate, without building the
+/// conjugate.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::young::is_self_conjugate;
+///
+/// assert!(is_self_conjugate(&[1, 1, 3]));
+/// assert!(!is_self_conjugate(&[1, 3]));
+/// ```
+pub fn is_self_conjugate(p: &[usize]) -> bool {
+    let l = p.len();
+    p.last().map_or(0, |&x| x) == l && (0..l).all(|i| p[l - 1 - i] == column(p, i))
+}
+
+/// Returns the length of column `j` of `p`, counting from zero: the
+/// number of parts greater than `j`.
+#[inline]
+fn column(p: &[usize], j: usize) -> usize {
+    p.len() - p.partition_point(|&x| x <= j)
+}
+
+/// Returns the arm of the cell in row `i` and column `j` of `p`,
+/// counting from zero at the top left: the number of cells to its
+/// right.
+///
+/// # Panics
+///
+/// Panics if the cell is not in the diagram.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::young::{arm, hook, leg};
+///
+/// // The corner cell of 4 + 2 + 1.
+/// assert_eq!((arm(&[1, 2, 4], 0, 0), leg(&[1, 2, 4], 0, 0)), (3, 2));
+/// assert_eq!(hook(&[1, 2, 4], 0, 0), 6);
+/// ```
+pub fn arm(p: &[usize], i: usize, j: usize) -> usize {
+    let l = p.len();
+    assert!(i < l && j < p[l - 1 - i], "cell is not in the diagram");
+    p[l - 1 - i] - j - 1
+}
+
+/// Returns the leg of the cell in row `i` and column `j` of `p`: the
+/// number of cells below it.
+///
+/// # Panics
+///
+/// Panics if the cell is not in the diagram.
+pub fn leg(p: &[usize], i: usize, j: usize) -> usize {
+    let l = p.len();
+    assert!(i < l && j < p[l - 1 - i], "cell is not in the diagram");
+    column(p, j) - i - 1
+}
+
+/// Returns the hook length of the cell in row `i` and column `j` of
+/// `p`: its arm and leg and the cell itself. `tableau::hook_lengths`
+/// gives them all at once.
+///
+/// # Panics
+///
+/// Panics if the cell is not in the diagram.
+#[inline]
+pub fn hook(p: &[usize], i: usize, j: usize) -> usize {
+    arm(p, i, j) + leg(p, i, j) + 1
+}
+
+/// Returns the side of the Durfee square of `p`, without the rest of
+/// the decomposition that `durfee` makes.
+pub fn durfee_side(p: &[usize]) -> usize {
+    let l = p.len();
+    (1..(l + 1)).take_while(|&i| p[l - i] >= i).count()
+}
+
+/// Partitions whose largest part is at least their number of parts.
+///
+/// One of each conjugate pair is, since conjugating swaps the two.
+struct Wide;
+
+impl Constraint for Wide {
+    #[inline]
+    fn allows(&self, prefix: &[usize], x: usize, rest: usize) -> bool {
+        // The rest as one last part makes the largest part as large, and
+        // the parts as few, as they can be.
+        let k = prefix.len();
+        if rest == 0 {
+            x > k
+        } else {
+            rest >= x && rest >= k + 2
+        }
+    }
+
+    #[inline]
+    fn first(&self, prefix: &[usize], lo: usize, s: usize) -> Option<usize> {
+        let k = prefix.len();
+        if lo <= s && s - lo >= lo.max(k + 2) {
+            Some(lo)
+        } else if s >= lo.max(k + 1) {
+            Some(s)
+        } else {
+            None
+        }
+    }
+}
+
+impl Family for Wide {
+    #[cfg(feature = "validate")]
+    fn validator(&self, n: usize) -> Validator<usize> {
+        Validator::new("young::UpToConjugation", n, Order::Lex)
+    }
+}
+
+/// Iterates over the partitions of `n` up to conjugation.
+///
+/// Of each pair of conjugate partitions only one is yielded: the one
+/// whose largest part is greater than its number of parts, or if the
+/// two are equal, the one that comes first from `Partitions`.
+/// Self-conjugate partitions are yielded once. The order is otherwise
+/// that of `Partitions`.
+///
+/// Only partitions whose largest part is at least their number of parts
+/// are generated, so the other half is never visited, and only those
+/// where the two are equal are conjugated, to break the tie.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::young::UpToConjugation;
+///
+/// let mut pp = UpToConjugation::new(4);
+/// assert_eq!(pp.next(), Some(&[1, 3][..]));
+/// assert!(!pp.is_self_conjugate());
+/// assert_eq!(pp.next(), Some(&[2, 2][..]));
+/// assert!(pp.is_self_conjugate());
+/// assert_eq!(pp.next(), Some(&[4][..]));
+/// assert!(!pp.is_self_conjugate());
+/// assert_eq!(pp.next(), None);
+/// ```
+#[derive(Debug)]
+pub struct UpToConjugation {
+    n: usize,
+    p: Vec<usize>,
+    conj: Vec<usize>,
+    self_conjugate: bool,
+    state: State,
+}
+
+impl UpToConjugation {
+    /// Makes a new iterator.
+    pub fn new(n: usize) -> UpToConjugation {
+        UpToConjugation {
+            n,
+            p: Vec::new(),
+            conj: Vec::new(),
+            self_conjugate: false,
+            state: State::Fresh,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Returns whether the current partition is its own conjugate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator is not on a partition.
+    #[inline]
+    pub fn is_self_conjugate(&self) -> bool {
+        self.get().expect("no current partition");
+        self.self_conjugate
+    }
+
+    /// Restarts the iterator at the first partition of `n`.
+    #[inline]
+    pub fn reinit(&mut self, n: usize) {
+        self.n = n;
+        self.p.clear();
+        self.state = State::Fresh;
+    }
+
+    /// Destroys the iterator and returns a vector for further use.
+    ///
+    /// You only need to call this function if you want to reuse the
+    /// vector for something else. Its contents will be in an undefined
+    /// state, and so cannot be relied upon.
+    #[inline]
+    pub fn end(self) -> Vec<usize> {
+        self.p
+    }
+}
+
+impl StreamingIterator for UpToConjugation {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        match self.state {
+            State::Going => Some(&self.p),
+            _ => None,
+        }
+    }
+
+    fn advance(&mut self) {
+        loop {
+            let found = match self.state {
+                State::Fresh => start(&Wide, self.n, &mut self.p),
+                State::Going => step(&Wide, &mut self.p),
+                State::Done => false,
+            };
+            if !found {
+                self.state = State::Done;
+                return;
+            }
+            self.state = State::Going;
+
+            let p = &self.p;
+            if p.last().map_or(0, |&x| x) > p.len() {
+                self.self_conjugate = false;
+                return;
+            }
+            conjugate(p, &mut self.conj);
+            if p <= &self.conj {
+                self.self_conjugate = *p == self.conj;
+                return;
+            }
+        }
+    }
+}
+
+impl PartitionIterator for UpToConjugation {
+    #[inline]
+    fn n(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn reinit(&mut self, n: usize) {
+        UpToConjugation::reinit(self, n)
+    }
+
+    #[inline]
+    fn end(self) -> Vec<usize> {
+        UpToConjugation::end(self)
+    }
+}
+
+/// The decomposition of a partition along its Durfee square.
+///
+/// The Durfee square is the largest square of cells in the top left of
+/// the Young diagram. What lies to its right is a partition into at most
+/// `side` parts, and what lies below it is a partition into parts of at
+/// most `side`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Durfee {
+    /// The side of the Durfee square.
+    pub side: usize,
+    /// The partition to the right of the square.
+    pub right: Vec<usize>,
+    /// The partition below the square.
+    pub below: Vec<usize>,
+}
+
+/// Decomposes `p` along its Durfee square.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::young::durfee;
+///
+/// let d = durfee(&[1, 2, 2, 3, 5]);
+/// assert_eq!(d.side, 2);
+/// assert_eq!(d.right, vec![1, 3]);
+/// assert_eq!(d.below, vec![1, 2, 2]);
+/// ```
+pub fn durfee(p: &[usize]) -> Durfee {
+    let l = p.len();
+    let side = durfee_side(p);
+    Durfee {
+        side,
+        right: p[(l - side)..].iter().filter(|&&x| x > side).map(|&x| x - side).collect(),
+        below: p[..(l - side)].to_vec(),
+    }
+}
+
+/// Iterates over the partitions of `n`, organised by their Durfee
+/// decomposition.
+///
+/// Partitions come in order of the side `s` of their Durfee square,
+/// then of the size of the part to the right of the square, then of
+/// that partition into at most `s` parts, and last of the partition
+/// below the square, each of the two partitions in the order of
+/// `sparse::AtMost`.
+///
+/// # Examples
+///
+/// ```
+/// use integer_partitions::young::DurfeePartitions;
+///
+/// let mut pp = DurfeePartitions::new(4);
+/// assert_eq!(pp.next(), Some(&[1, 1, 1, 1][..]));
+/// assert_eq!(pp.next(), Some(&[1, 1, 2][..]));
+/// assert_eq!(pp.right(), &[1]);
+/// assert_eq!(pp.below(), &[1, 1]);
+/// assert_eq!(pp.next(), Some(&[1, 3][..]));
+/// assert_eq!(pp.next(), Some(&[4][..]));
+/// assert_eq!(pp.next(), Some(&[2, 2][..]));
+/// assert_eq!(pp.side(), 2);
+/// assert_eq!(pp.next(), None);
+/// ```
+#[derive(Debug)]
+pub struct DurfeePartitions {
+    n: usize,
+    side: usize,
+    right_size: usize,
+    right: AtMost<usize>,
+    below: AtMost<usize>,
+    below_conj: Vec<usize>,
+    a: Vec<usize>,
+    state: State,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum State {
+    Fresh,
+    Going,
+    Done,
+}
+
+impl DurfeePartitions {
+    /// Makes a new iterator.
+    pub fn new(n: usize) -> DurfeePartitions {
+        DurfeePartitions {
+            n,
+            side: 0,
+            right_size: 0,
+            right: AtMost::new(0, 0),
+            below: AtMost::new(0, 0),
+            below_conj: Vec::new(),
+            a: Vec::with_capacity(n),
+            state: State::Fresh,
+        }
+    }
+
+    /// Advances the iterator and returns the next partition.
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        StreamingIterator::next(self)
+    }
+
+    /// Returns the side of the Durfee square of the current partition.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator is not on a partition.
+    #[inline]
+    pub fn side(&self) -> usize {
+        self.get().expect("no current partition");
+        self.side
+    }
+
+    /// Returns the partition to the right of the Durfee square.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator is not on a partition.
+    #[inline]
+    pub fn right(&self) -> &[usize] {
+        self.get().expect("no current partition");
+        self.right.get().unwrap()
+    }
+
+    /// Returns the partition below the Durfee square.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator is not on a partition.
+    #[inline]
+    pub fn below(&self) -> &[usize] {
+        self.get().expect("no current partition");
+        &self.below_conj
+    }
+
+    /// Restarts the iterator at the first partition of `n`.
+    #[inline]
+    pub fn reinit(&mut self, n: usize) {
+        self.n = n;
+        self.side = 0;
+        self.right_size = 0;
+        self.a.clear();
+        self.state = State::Fresh;
+    }
+
+    /// Destroys the iterator and returns a vector for further use.
+    ///
+    /// You only need to call this function if you want to reuse the
+    /// vector for something else. Its contents will be in an undefined
+    /// state, and so cannot be relied upon.
+    #[inline]
+    pub fn end(self) -> Vec<usize> {
+        self.a
+    }
+
+    /// Starts on the partitions below the square, once the square and
+    /// the partition to its right are fixed.
+    fn start_below(&mut self) {
+        let s = self.side;
+        self.below = AtMost::new(self.n - s * s - self.right_size, s);
+        self.below.advance();
+    }
+
+    /// Moves on to the next square size or right-hand size that has any
+    /// partitions at all, and returns whether there was one.
+    fn start_right(&mut self) -> bool {
+        loop {
+            let s = self.side;
+            if s * s + self.right_size > self.n {
+                if self.right_size == 0 {
+                    return false;
+                }
+                self.side += 1;
+                self.right_size = 0;
+                continue;
+            }
+            self.right = AtMost::new(self.right_size, s);
+            self.right.advance();
+            if self.right.get().is_some() {
+                self.start_below();
+                if self.below.get().is_some() {
+                    return true;
+                }
+            }
+            self.right_size += 1;
+        }
+    }
+
+    fn build(&mut self) {
+        let s = self.side;
+        conjugate(self.below.get().unwrap(), &mut self.below_conj);
+        let right = self.right.get().unwrap();
+
+        self.a.clear();
+        self.a.extend_from_slice(&self.below_conj);
+        for _ in right.len()..s {
+            self.a.push(s);
+        }
+        self.a.extend(right.iter().map(|&x| x + s));
+    }
+}
+
+impl StreamingIterator for DurfeePartitions {
+    type Item = [usize];
+
+    #[inline]
+    fn get(&self) -> Option<&[usize]> {
+        match self.state {
+            State::Going => Some(&self.a),
+            _ => None,
+        }
+    }
+
+    fn advance(&mut self) {
+        let found = match self.state {
+            State::Fresh => {
+                self.side = if self.n == 0 { 0 } else { 1 };
+                self.start_right()
+            }
+            State::Going => {
+                self.below.advance();
+                self.below.get().is_some() || {
+                    self.right.advance();
+                    if self.right.get().is_some() {
+                        self.start_below();
+                        true
+                    } else {
+                        self.right_size += 1;
+                        self.start_right()
+                    }
+                }
+            }
+            State::Done => false,
+        };
+
+        if found {
+            self.build();
+            self.state = State::Going;
+        } else {
+            self.state = State::Done;
+        }
+    }
+}
+
+impl PartitionIterator for DurfeePartitions {
+    #[inline]
+    fn n(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn reinit(&mut self, n: usize) {
+        DurfeePartitions::reinit(self, n)
+    }
+
+    #[inline]
+    fn end(self) -> Vec<usize> {
+        DurfeePartitions::end(self)
+    }
+}
+
+#[test]
+fn up_to_conjugation() {
+    //! Tests that each conjugate pair is represented exactly once.
+
+    use std::collections::HashSet;
+    use Partitions;
+
+    for n in 0..30 {
+        let mut all = HashSet::new();
+        let mut p = Partitions::new(n);
+        while let Some(x) = p.next() {
+            all.insert(x.to_vec());
+        }
+
+        let mut seen = HashSet::new();
+        let mut conj = Vec::new();
+        let mut pp = UpToConjugation::new(n);
+        while let Some(x) = pp.next().map(|x| x.to_vec()) {
+            conjugate(&x, &mut conj);
+            let y = conj.clone();
+            conjugate(&y, &mut conj);
+            assert_eq!(x, conj);
+            assert!(x.last() > y.last() || (x.last() == y.last() && x <= y));
+            assert_eq!(pp.is_self_conjugate(), x == y);
+            assert!(seen.insert(x));
+            assert!(seen.insert(y) || pp.is_self_conjugate());
+        }
+
+        assert_eq!(seen, all);
+    }
+}
+
+#[test]
+fn durfee_decomposition() {
+    //! Tests that the Durfee enumeration covers every partition once,
+    //! with the decomposition that `durfee` finds.
+
+    use std::collections::HashSet;
+    use Partitions;
+
+    for n in 0..30 {
+        let mut all = HashSet::new();
+        let mut p = Partitions::new(n);
+        while let Some(x) = p.next() {
+            all.insert(x.to_vec());
+        }
+
+        let mut seen = HashSet::new();
+        let mut dp = DurfeePartitions::new(n);
+        let mut last = 0;
+        while let Some(x) = dp.next().map(|x| x.to_vec()) {
+            let d = durfee(&x);
+            assert_eq!(d.side, dp.side());
+            assert_eq!(&d.right[..], dp.right());
+            assert_eq!(&d.below[..], dp.below());
+            assert!(d.side >= last);
+            last = d.side;
+
+            assert!(d.right.len() <= d.side && d.below.iter().all(|&b| b <= d.side));
+            let size: usize = d.right.iter().chain(&d.below).sum();
+            assert_eq!(d.side * d.side + size, n);
+            assert!(seen.insert(x));
+        }
+
+        assert_eq!(seen, all);
+    }
+}
+
+#[test]
+fn cells() {
+    //! Compares the cell statistics against the conjugate and the hook
+    //! lengths of `tableau`.
+
+    use tableau::hook_lengths;
+    use Partitions;
+
+    let mut conj = Vec::new();
+    for n in 0..16 {
+        let mut pp = Partitions::new(n);
+        while let Some(p) = pp.next() {
+            conjugate(p, &mut conj);
+            assert_eq!(is_self_conjugate(p), p == &conj[..]);
+            assert_eq!(durfee_side(p), durfee(p).side);
+            let l = p.len();
+            for (i, row) in hook_lengths(p).iter().enumerate() {
+                for (j, &h) in row.iter().enumerate() {
+                    assert_eq!(arm(p, i, j), p[l - 1 - i] - j - 1);
+                    assert_eq!(leg(p, i, j), conj[conj.len() - 1 - j] - i - 1);
+                    assert_eq!(hook(p, i, j), h);
+                }
+            }
+        }
+    }
+}